@@ -1,33 +1,652 @@
-//! Benchmarking setup for pallet-template
+//! Benchmarking setup for pallet-miner
+#![cfg(feature = "runtime-benchmarks")]
 
 use super::*;
-use frame::{deps::frame_benchmarking::v2::*, prelude::*};
+use crate::url::{Rule, Url, MAX_RULE_SEGMENTS};
+use frame_benchmarking::v2::*;
+use frame_support::{
+	traits::{Currency, EnsureOrigin, Get, Hooks},
+	BoundedVec,
+};
+use frame_system::RawOrigin;
+use pallet_validator::ValidatorSet;
+use sp_runtime::traits::{Bounded, Hash, One, Saturating, Zero};
+use sp_std::{vec, vec::Vec};
+
+/// A host with more labels than a wildcard rule can have.
+const URL_PREFIX: &[u8] = b"https://a.archive.example.org/";
+
+/// A URL of `len` bytes with as many path segments as fit, the worst case for whitelist
+/// matching.
+fn url(len: u32) -> Vec<u8> {
+	let mut url = URL_PREFIX.to_vec();
+	while url.len() < len as usize {
+		url.push(if url.len().is_multiple_of(2) { b'a' } else { b'/' });
+	}
+	// Trailing slashes are removed on submission.
+	if url.last() == Some(&b'/') {
+		url.pop();
+		url.push(b'a');
+	}
+	url
+}
+
+/// A whitelist rule of `len` bytes with as many path segments as a rule can have.
+fn rule(len: u32) -> Vec<u8> {
+	let mut rule = URL_PREFIX.to_vec();
+	rule.resize(len as usize, b'a');
+	let path_len = len as usize - URL_PREFIX.len();
+	for segment in 1..MAX_RULE_SEGMENTS {
+		let index = URL_PREFIX.len() + segment * path_len / MAX_RULE_SEGMENTS;
+		if index + 1 < rule.len() && rule[index - 1] != b'/' {
+			rule[index] = b'/';
+		}
+	}
+	rule
+}
+
+/// Whitelist only the last rule `url` is matched against, so that every candidate is read.
+fn whitelist_last_candidate<T: Config>(url: &[u8]) {
+	let Rule { host, path_prefix } =
+		Url::parse(url).ok().and_then(|url| url.candidate_rules().last()).expect("valid URL");
+	Whitelist::<T>::insert(
+		BoundedVec::<u8, T::MaxUrlLength>::truncate_from(host),
+		BoundedVec::<u8, T::MaxUrlLength>::truncate_from(path_prefix),
+		Pallet::<T>::default_whitelist_entry(None),
+	);
+}
+
+/// `n` distinct whitelist rules of the maximum length.
+fn max_length_rules<T: Config>(n: u32) -> BoundedVec<BoundedVec<u8, T::MaxUrlLength>, T::MaxWhitelistBatch> {
+	let len = T::MaxUrlLength::get();
+	let rules = (0..n)
+		.map(|i| {
+			let mut rule = rule(len - 8);
+			rule.extend((0..8).map(|digit| b"0123456789abcdef"[(i >> (28 - 4 * digit) & 0xf) as usize]));
+			BoundedVec::truncate_from(rule)
+		})
+		.collect::<Vec<_>>();
+	BoundedVec::truncate_from(rules)
+}
+
+/// Register `v` validators named `name`. Committees are sampled by reading every validator, so
+/// the worst case is a full validator set.
+fn register_validators<T: Config>(name: &'static str, v: u32) {
+	for i in 0..v {
+		T::Validators::register_for_benchmarks(&account(name, i, 0));
+	}
+}
+
+/// Give `who` enough funds to register and submit.
+fn fund<T: Config>(who: &T::AccountId) {
+	T::Currency::make_free_balance_be(who, BalanceOf::<T>::max_value() / 2u32.into());
+}
+
+/// A funded account registered as a miner with the smallest deposit.
+fn registered_miner<T: Config>(who: T::AccountId) -> T::AccountId {
+	fund::<T>(&who);
+	Pallet::<T>::register_miner(RawOrigin::Signed(who.clone()).into(), T::MinMinerDeposit::get())
+		.expect("miner is funded");
+	who
+}
+
+/// A pending submission of the longest URL with `fingerprint`, and the full committee of
+/// validators assigned to it.
+fn pending_submission<T: Config>(fingerprint: Option<u64>) -> (T::Hash, Vec<T::AccountId>) {
+	let miner = registered_miner::<T>(account("miner", 0, 0));
+	let url = url(T::MaxUrlLength::get());
+	whitelist_last_candidate::<T>(&url);
+	register_validators::<T>("validator", T::CommitteeSize::get());
+	let hash = T::Hashing::hash_of(&u32::MAX);
+	let id = Pallet::<T>::submission_id(&url, &hash);
+	Pallet::<T>::submit_hash(RawOrigin::Signed(miner).into(), url, hash, vec![b'b'], 1, vec![b't'], fingerprint)
+		.expect("URL is whitelisted");
+	let committee = Committees::<T>::get(id).map(|committee| committee.members.to_vec()).unwrap_or_default();
+	(id, committee)
+}
+
+/// Have `validator` commit to a vote on `id` for `content_hash` and `fingerprint`.
+fn commit_vote_for<T: Config>(validator: &T::AccountId, id: T::Hash, content_hash: &T::Hash, fingerprint: &Option<u64>) {
+	let commitment = Pallet::<T>::vote_commitment_of(content_hash, fingerprint, &[0u8; 32], validator);
+	Pallet::<T>::commit_vote(RawOrigin::Signed(validator.clone()).into(), id, commitment)
+		.expect("validator is on the committee");
+}
 
 #[benchmarks]
 mod benchmarks {
 	use super::*;
-	#[cfg(test)]
-	use crate::pallet::Pallet as Template;
-	use frame_system::RawOrigin;
 
 	#[benchmark]
-	fn do_something() {
+	fn register_miner() {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let deposit = T::MinMinerDeposit::get();
+
+		#[extrinsic_call]
+		register_miner(RawOrigin::Signed(caller.clone()), deposit);
+
+		assert_eq!(Miners::<T>::get(&caller), Some(deposit));
+	}
+
+	#[benchmark]
+	fn bond_extra() {
+		let caller = registered_miner::<T>(whitelisted_caller());
+		let amount = T::MinMinerDeposit::get();
+
+		#[extrinsic_call]
+		bond_extra(RawOrigin::Signed(caller.clone()), amount);
+
+		assert_eq!(Miners::<T>::get(&caller), Some(amount.saturating_add(amount)));
+	}
+
+	#[benchmark]
+	fn chill() {
+		let caller = registered_miner::<T>(whitelisted_caller());
+
+		#[extrinsic_call]
+		chill(RawOrigin::Signed(caller.clone()));
+
+		assert!(Chilled::<T>::contains_key(&caller));
+	}
+
+	#[benchmark]
+	fn unbond() {
+		let caller = registered_miner::<T>(whitelisted_caller());
+		let deposit = T::MinMinerDeposit::get();
+
+		// Worst case: all but one of the chunk slots are taken and unbonding everything chills
+		// the miner.
+		let unlock_at = frame_system::Pallet::<T>::block_number().saturating_add(T::UnbondingPeriod::get());
+		let chunks = (1..T::MaxUnbondingChunks::get())
+			.map(|_| UnlockChunk { value: Zero::zero(), unlock_at })
+			.collect::<Vec<_>>();
+		Unbonding::<T>::insert(&caller, BoundedVec::truncate_from(chunks));
+
+		#[extrinsic_call]
+		unbond(RawOrigin::Signed(caller.clone()), deposit);
+
+		assert!(Chilled::<T>::contains_key(&caller));
+	}
+
+	#[benchmark]
+	fn withdraw_unbonded() {
+		let caller = registered_miner::<T>(whitelisted_caller());
+
+		// Worst case: every chunk slot is taken, all of them unlock and the miner deregisters.
+		let unlock_at = frame_system::Pallet::<T>::block_number();
+		let chunks = (0..T::MaxUnbondingChunks::get())
+			.map(|i| UnlockChunk {
+				value: if i == 0 { T::MinMinerDeposit::get() } else { Zero::zero() },
+				unlock_at,
+			})
+			.collect::<Vec<_>>();
+		Unbonding::<T>::insert(&caller, BoundedVec::truncate_from(chunks));
+		Miners::<T>::insert(&caller, BalanceOf::<T>::zero());
+
+		#[extrinsic_call]
+		withdraw_unbonded(RawOrigin::Signed(caller.clone()));
+
+		assert!(!Miners::<T>::contains_key(&caller));
+	}
+
+	#[benchmark]
+	fn submit_hash(
+		u: Linear<{ URL_PREFIX.len() as u32 + 1 }, { T::MaxUrlLength::get() }>,
+		v: Linear<{ T::CommitteeSize::get() }, { T::Validators::max_validators() }>,
+	) {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		Pallet::<T>::register_miner(RawOrigin::Signed(caller.clone()).into(), T::MinMinerDeposit::get())
+			.expect("caller is funded");
+		let url = url(u);
+		let bounded_url: BoundedVec<u8, T::MaxUrlLength> = url.clone().try_into().unwrap();
+		// The only matching rule is the last candidate checked.
+		whitelist_last_candidate::<T>(&url);
+
+		// Worst case: the URL's history is full and its oldest snapshot has to be dropped.
+		let snapshots = (0..T::MaxSnapshotsPerUrl::get())
+			.map(|i| Snapshot {
+				block_number: Zero::zero(),
+				content_hash: T::Hashing::hash_of(&i),
+				miner: caller.clone(),
+				status: SubmissionStatus::Rejected,
+			})
+			.collect::<Vec<_>>();
+		History::<T>::insert(&bounded_url, BoundedVec::truncate_from(snapshots));
+
+		// A full committee is sampled from the registered validators.
+		register_validators::<T>("validator", v);
+		let hash = T::Hashing::hash_of(&u32::MAX);
+		let id = Pallet::<T>::submission_id(&url, &hash);
+		let cid = vec![b'b'; T::MaxCidLength::get() as usize];
+		let mime_type = vec![b't'; T::MaxMimeTypeLength::get() as usize];
+
+		#[extrinsic_call]
+		submit_hash(RawOrigin::Signed(caller), url, hash, cid, u64::MAX, mime_type, Some(u64::MAX));
+
+		assert_eq!(SubmissionStatuses::<T>::get(id), Some(SubmissionStatus::Pending));
+		assert_eq!(
+			Committees::<T>::get(id).map(|committee| committee.members.len() as u32),
+			Some(T::CommitteeSize::get())
+		);
+	}
+
+	#[benchmark]
+	fn add_to_whitelist(
+		u: Linear<{ URL_PREFIX.len() as u32 + 1 }, { T::MaxUrlLength::get() }>,
+	) -> Result<(), BenchmarkError> {
+		let origin =
+			T::WhitelistOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let url = rule(u);
+
+		#[extrinsic_call]
+		add_to_whitelist(origin as T::RuntimeOrigin, url.clone());
+
+		let (host, path_prefix) = Pallet::<T>::whitelist_keys(&url).unwrap();
+		assert!(Whitelist::<T>::contains_key(&host, &path_prefix));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn remove_from_whitelist(
+		u: Linear<{ URL_PREFIX.len() as u32 + 1 }, { T::MaxUrlLength::get() }>,
+	) -> Result<(), BenchmarkError> {
+		let origin =
+			T::WhitelistOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let url = rule(u);
+		let (host, path_prefix) = Pallet::<T>::whitelist_keys(&url).unwrap();
+		Whitelist::<T>::insert(&host, &path_prefix, Pallet::<T>::default_whitelist_entry(None));
+
+		#[extrinsic_call]
+		remove_from_whitelist(origin as T::RuntimeOrigin, url);
+
+		assert!(!Whitelist::<T>::contains_key(&host, &path_prefix));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn batch_add_to_whitelist(n: Linear<1, { T::MaxWhitelistBatch::get() }>) -> Result<(), BenchmarkError> {
+		let origin =
+			T::WhitelistOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let rules = max_length_rules::<T>(n);
+
+		#[extrinsic_call]
+		batch_add_to_whitelist(origin as T::RuntimeOrigin, rules.clone());
+
+		for rule in rules {
+			let (host, path_prefix) = Pallet::<T>::whitelist_keys(&rule).unwrap();
+			assert!(Whitelist::<T>::contains_key(&host, &path_prefix));
+		}
+		Ok(())
+	}
+
+	#[benchmark]
+	fn batch_remove_from_whitelist(
+		n: Linear<1, { T::MaxWhitelistBatch::get() }>,
+	) -> Result<(), BenchmarkError> {
+		let origin =
+			T::WhitelistOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let rules = max_length_rules::<T>(n);
+		for rule in &rules {
+			let (host, path_prefix) = Pallet::<T>::whitelist_keys(rule).unwrap();
+			Whitelist::<T>::insert(&host, &path_prefix, Pallet::<T>::default_whitelist_entry(None));
+		}
+
+		#[extrinsic_call]
+		batch_remove_from_whitelist(origin as T::RuntimeOrigin, rules.clone());
+
+		for rule in rules {
+			let (host, path_prefix) = Pallet::<T>::whitelist_keys(&rule).unwrap();
+			assert!(!Whitelist::<T>::contains_key(&host, &path_prefix));
+		}
+		Ok(())
+	}
+
+	#[benchmark]
+	fn update_whitelist_entry(
+		u: Linear<{ URL_PREFIX.len() as u32 + 1 }, { T::MaxUrlLength::get() }>,
+	) -> Result<(), BenchmarkError> {
+		let origin =
+			T::WhitelistOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let url = rule(u);
+		let (host, path_prefix) = Pallet::<T>::whitelist_keys(&url).unwrap();
+		Whitelist::<T>::insert(&host, &path_prefix, Pallet::<T>::default_whitelist_entry(None));
+
+		#[extrinsic_call]
+		update_whitelist_entry(
+			origin as T::RuntimeOrigin,
+			url,
+			Some(WhitelistStatus::Paused),
+			Some(Priority::High),
+			Some(One::one()),
+			Some(false),
+			Some(FINGERPRINT_BITS - 1),
+		);
+
+		let entry = Whitelist::<T>::get(&host, &path_prefix).unwrap();
+		assert_eq!(entry.status, WhitelistStatus::Paused);
+		assert_eq!(entry.priority, Priority::High);
+		Ok(())
+	}
+
+	#[benchmark]
+	fn claim_task(u: Linear<{ URL_PREFIX.len() as u32 + 1 }, { T::MaxUrlLength::get() }>) {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		Pallet::<T>::register_miner(RawOrigin::Signed(caller.clone()).into(), T::MinMinerDeposit::get())
+			.expect("caller is funded");
+		let url = url(u);
+		let bounded_url: BoundedVec<u8, T::MaxUrlLength> = url.clone().try_into().unwrap();
+		// The only matching rule is the last candidate checked.
+		whitelist_last_candidate::<T>(&url);
+
+		// Worst case: the task is due and another miner's claim on it has to be replaced.
+		let now = frame_system::Pallet::<T>::block_number();
+		let other: T::AccountId = account("other", 0, 0);
+		CrawlTasks::<T>::insert(
+			&bounded_url,
+			CrawlTask { due_at: now, claim: Some(TaskClaim { miner: other, expires_at: now }) },
+		);
+
+		#[extrinsic_call]
+		claim_task(RawOrigin::Signed(caller.clone()), url);
+
+		let task = CrawlTasks::<T>::get(&bounded_url).unwrap();
+		assert_eq!(task.claim.map(|claim| claim.miner), Some(caller));
+	}
+
+	#[benchmark]
+	fn commit_submission() {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		Pallet::<T>::register_miner(RawOrigin::Signed(caller.clone()).into(), T::MinMinerDeposit::get())
+			.expect("caller is funded");
+
+		// Worst case: all but one of the block's commitment slots are taken.
+		let expires_at = frame_system::Pallet::<T>::block_number().saturating_add(T::RevealPeriod::get());
+		let commitments = (1..T::MaxCommitmentsPerBlock::get())
+			.map(|i| T::Hashing::hash_of(&i))
+			.collect::<Vec<_>>();
+		CommitmentExpiries::<T>::insert(expires_at, BoundedVec::truncate_from(commitments));
+		let commitment = T::Hashing::hash_of(&0u32);
+
+		#[extrinsic_call]
+		commit_submission(RawOrigin::Signed(caller), commitment);
+
+		assert!(Commitments::<T>::contains_key(commitment));
+	}
+
+	#[benchmark]
+	fn reveal_submission(
+		u: Linear<{ URL_PREFIX.len() as u32 + 1 }, { T::MaxUrlLength::get() }>,
+		v: Linear<{ T::CommitteeSize::get() }, { T::Validators::max_validators() }>,
+	) {
+		let caller: T::AccountId = whitelisted_caller();
+		let other: T::AccountId = account("other", 0, 0);
+		for miner in [&caller, &other] {
+			fund::<T>(miner);
+			Pallet::<T>::register_miner(RawOrigin::Signed(miner.clone()).into(), T::MinMinerDeposit::get())
+				.expect("miner is funded");
+		}
+		// The caller takes a seat on the committee that has to be filled again.
+		T::Validators::register_for_benchmarks(&caller);
+		register_validators::<T>("validator", v.saturating_sub(1));
+		let url = url(u);
+		whitelist_last_candidate::<T>(&url);
+
+		// Worst case: another miner that committed later already submitted the same content.
+		let hash = T::Hashing::hash_of(&u32::MAX);
+		let salt = [0u8; 32];
+		let cid = vec![b'b'; T::MaxCidLength::get() as usize];
+		let mime_type = vec![b't'; T::MaxMimeTypeLength::get() as usize];
+		let commitment = Pallet::<T>::commitment_of(&url, &hash, &salt, &caller);
+		let id = Pallet::<T>::submission_id(&url, &hash);
+		Pallet::<T>::commit_submission(RawOrigin::Signed(caller.clone()).into(), commitment)
+			.expect("caller is registered");
+		frame_system::Pallet::<T>::set_block_number(
+			frame_system::Pallet::<T>::block_number().saturating_add(One::one()),
+		);
+		Pallet::<T>::submit_hash(
+			RawOrigin::Signed(other).into(),
+			url.clone(),
+			hash,
+			cid.clone(),
+			u64::MAX,
+			mime_type.clone(),
+			Some(u64::MAX),
+		)
+		.expect("URL is whitelisted");
+
+		#[extrinsic_call]
+		reveal_submission(
+			RawOrigin::Signed(caller.clone()),
+			url,
+			hash,
+			cid,
+			u64::MAX,
+			mime_type,
+			Some(u64::MAX),
+			salt,
+		);
+
+		assert_eq!(PendingSubmissions::<T>::get(id).map(|pending| pending.record.miner), Some(caller));
+	}
+
+	#[benchmark]
+	fn reassign_committee(v: Linear<{ 2 * T::CommitteeSize::get() }, { T::Validators::max_validators() }>) {
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		Pallet::<T>::register_miner(RawOrigin::Signed(caller.clone()).into(), T::MinMinerDeposit::get())
+			.expect("caller is funded");
+		let url = url(URL_PREFIX.len() as u32 + 1);
+		whitelist_last_candidate::<T>(&url);
+
+		// Worst case: the whole committee timed out and is replaced by validators registered since.
+		register_validators::<T>("validator", T::CommitteeSize::get());
+		let hash = T::Hashing::hash_of(&u32::MAX);
+		let id = Pallet::<T>::submission_id(&url, &hash);
+		Pallet::<T>::submit_hash(
+			RawOrigin::Signed(caller.clone()).into(),
+			url,
+			hash,
+			vec![b'b'],
+			1,
+			vec![b't'],
+			None,
+		)
+		.expect("URL is whitelisted");
+		let assigned = Committees::<T>::get(id).map(|committee| committee.members).unwrap_or_default();
+		register_validators::<T>("replacement", v - T::CommitteeSize::get());
+		frame_system::Pallet::<T>::set_block_number(
+			frame_system::Pallet::<T>::block_number().saturating_add(T::AssignmentTimeout::get()),
+		);
+
+		#[extrinsic_call]
+		reassign_committee(RawOrigin::Signed(caller), id);
+
+		let committee = Committees::<T>::get(id).expect("committee was reassigned");
+		assert!(committee.members.iter().all(|member| !assigned.contains(member)));
+	}
+
+	#[benchmark]
+	fn challenge_submission(v: Linear<{ 2 * T::CommitteeSize::get() }, { T::Validators::max_validators() }>) {
+		let miner: T::AccountId = account("miner", 0, 0);
+		fund::<T>(&miner);
+		Pallet::<T>::register_miner(RawOrigin::Signed(miner.clone()).into(), T::MinMinerDeposit::get())
+			.expect("miner is funded");
+		let url = url(URL_PREFIX.len() as u32 + 1);
+		whitelist_last_candidate::<T>(&url);
+
+		// Worst case: a full committee is sampled for the new validator round from the validators
+		// that did not approve the record.
+		register_validators::<T>("validator", v);
+		let hash = T::Hashing::hash_of(&u32::MAX);
+		let id = Pallet::<T>::submission_id(&url, &hash);
+		Pallet::<T>::submit_hash(
+			RawOrigin::Signed(miner).into(),
+			url,
+			hash,
+			vec![b'b'],
+			1,
+			vec![b't'],
+			None,
+		)
+		.expect("URL is whitelisted");
+		let pending = PendingSubmissions::<T>::get(id).expect("submission is pending");
+		Pallet::<T>::finalize(id, pending, SubmissionStatus::Approved);
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+
+		#[extrinsic_call]
+		challenge_submission(RawOrigin::Signed(caller.clone()), id);
+
+		assert_eq!(Disputes::<T>::get(id).map(|dispute| dispute.challenger), Some(caller));
+	}
+
+	#[benchmark]
+	fn commit_vote() {
+		// Worst case: the last member commits, so every member is checked.
+		let (id, committee) = pending_submission::<T>(None);
+		let content_hash = T::Hashing::hash_of(&u32::MAX);
+		let (caller, others) = committee.split_last().expect("committee is full");
+		for validator in others {
+			commit_vote_for::<T>(validator, id, &content_hash, &None);
+		}
+		let commitment = Pallet::<T>::vote_commitment_of(&content_hash, &None, &[0u8; 32], caller);
+
+		#[extrinsic_call]
+		commit_vote(RawOrigin::Signed(caller.clone()), id, commitment);
+
+		assert!(VoteCommitments::<T>::contains_key(id, caller));
+	}
+
+	#[benchmark]
+	fn reveal_vote() {
+		// Worst case: the last member reveals a similar fingerprint, which looks up the rule of
+		// the URL, and the submission is approved and its task rewarded.
+		let fingerprint = Some(u64::MAX);
+		let (id, committee) = pending_submission::<T>(fingerprint);
+		let content_hash = T::Hashing::hash_of(&0u32);
+		for validator in &committee {
+			commit_vote_for::<T>(validator, id, &content_hash, &fingerprint);
+		}
+		let (caller, others) = committee.split_last().expect("committee is full");
+		for validator in others {
+			Pallet::<T>::reveal_vote(RawOrigin::Signed(validator.clone()).into(), id, content_hash, fingerprint, [0u8; 32])
+				.expect("votes can be revealed");
+		}
+
+		#[extrinsic_call]
+		reveal_vote(RawOrigin::Signed(caller.clone()), id, content_hash, fingerprint, [0u8; 32]);
+
+		assert_eq!(SubmissionStatuses::<T>::get(id), Some(SubmissionStatus::Approved));
+	}
+
+	#[benchmark]
+	fn report_mismatch() {
+		let (id, _) = pending_submission::<T>(None);
+		let pending = PendingSubmissions::<T>::get(id).expect("submission is pending");
+		Pallet::<T>::finalize(id, pending, SubmissionStatus::Approved);
+
+		#[extrinsic_call]
+		report_mismatch(RawOrigin::Root, id);
+
+		assert_eq!(SubmissionStatuses::<T>::get(id), Some(SubmissionStatus::Rejected));
+	}
+
+	#[benchmark]
+	fn finalize_submission() {
+		// Worst case: the voting period ends on a submission all but one member approved, so its
+		// task is rewarded, the voters are paid and the last member misses its vote.
+		let fingerprint = Some(u64::MAX);
+		let (id, committee) = pending_submission::<T>(fingerprint);
+		let content_hash = T::Hashing::hash_of(&0u32);
+		for validator in &committee {
+			commit_vote_for::<T>(validator, id, &content_hash, &fingerprint);
+		}
+		let (_, voters) = committee.split_last().expect("committee is full");
+		for validator in voters {
+			Pallet::<T>::reveal_vote(RawOrigin::Signed(validator.clone()).into(), id, content_hash, fingerprint, [0u8; 32])
+				.expect("votes can be revealed");
+		}
+		let pending = PendingSubmissions::<T>::get(id).expect("submission is pending");
+		let deadline = pending.submitted_at.saturating_add(T::VotingPeriod::get());
+
+		#[block]
+		{
+			Pallet::<T>::on_initialize(deadline);
+		}
+
+		assert_eq!(SubmissionStatuses::<T>::get(id), Some(SubmissionStatus::Approved));
+	}
+
+	#[benchmark]
+	fn propose_whitelist_change(u: Linear<{ URL_PREFIX.len() as u32 + 1 }, { T::MaxUrlLength::get() }>) {
 		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let url = rule(u);
+
+		// Worst case: all but one of the block's proposal slots are taken.
+		let end = frame_system::Pallet::<T>::block_number().saturating_add(T::ProposalVotingPeriod::get());
+		let ids = (1..T::MaxProposalsPerBlock::get()).collect::<Vec<_>>();
+		ProposalDeadlines::<T>::insert(end, BoundedVec::truncate_from(ids));
+		NextProposalId::<T>::put(0);
+
+		#[extrinsic_call]
+		propose_whitelist_change(RawOrigin::Signed(caller.clone()), url, WhitelistAction::Add);
+
+		assert_eq!(WhitelistProposals::<T>::get(0).map(|proposal| proposal.proposer), Some(caller));
+	}
+
+	#[benchmark]
+	fn vote_whitelist_proposal() {
+		let proposer: T::AccountId = account("proposer", 0, 0);
+		fund::<T>(&proposer);
+		Pallet::<T>::propose_whitelist_change(
+			RawOrigin::Signed(proposer).into(),
+			rule(URL_PREFIX.len() as u32 + 1),
+			WhitelistAction::Add,
+		)
+		.expect("proposer is funded");
+		let proposal_id = NextProposalId::<T>::get().wrapping_sub(1);
+		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		let stake: BalanceOf<T> = 1u32.into();
+
 		#[extrinsic_call]
-		do_something(RawOrigin::Signed(caller), 100);
+		vote_whitelist_proposal(RawOrigin::Signed(caller.clone()), proposal_id, true, stake);
 
-		assert_eq!(Something::<T>::get().map(|v| v.block_number), Some(100u32.into()));
+		assert!(ProposalVotes::<T>::contains_key(proposal_id, &caller));
 	}
 
 	#[benchmark]
-	fn cause_error() {
-		Something::<T>::put(CompositeStruct { block_number: 100u32.into() });
+	fn unlock_proposal_vote() {
+		let proposer: T::AccountId = account("proposer", 0, 0);
+		fund::<T>(&proposer);
+		Pallet::<T>::propose_whitelist_change(
+			RawOrigin::Signed(proposer).into(),
+			rule(URL_PREFIX.len() as u32 + 1),
+			WhitelistAction::Add,
+		)
+		.expect("proposer is funded");
+		let proposal_id = NextProposalId::<T>::get().wrapping_sub(1);
 		let caller: T::AccountId = whitelisted_caller();
+		fund::<T>(&caller);
+		Pallet::<T>::vote_whitelist_proposal(
+			RawOrigin::Signed(caller.clone()).into(),
+			proposal_id,
+			true,
+			1u32.into(),
+		)
+		.expect("caller is funded");
+		// Voting has closed.
+		WhitelistProposals::<T>::remove(proposal_id);
+
 		#[extrinsic_call]
-		cause_error(RawOrigin::Signed(caller));
+		unlock_proposal_vote(RawOrigin::Signed(caller.clone()), proposal_id);
 
-		assert_eq!(Something::<T>::get().map(|v| v.block_number), Some(101u32.into()));
+		assert!(!ProposalVotes::<T>::contains_key(proposal_id, &caller));
 	}
 
-	impl_benchmark_test_suite!(Template, crate::mock::new_test_ext(), crate::mock::Test);
+	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
 }
@@ -1,7 +1,17 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+
 pub use pallet::*;
 
+pub mod migrations;
+pub mod offchain;
+pub mod url;
+pub mod weights;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
 #[frame_support::pallet]
 pub mod pallet {
     use frame_support::{
@@ -9,66 +19,678 @@ pub mod pallet {
         pallet_prelude::*,
 		BoundedVec,
 		PalletId,
-        traits::{Currency, ExistenceRequirement, ReservableCurrency},
+        traits::{Currency, ExistenceRequirement, Imbalance, Randomness, ReservableCurrency},
     };
     use frame_system::pallet_prelude::*;
-    use sp_std::vec::Vec;
-	use sp_runtime::traits::AccountIdConversion;
+	use frame_system::offchain::{Account, AppCrypto, CreateSignedTransaction, SendSignedTransaction, Signer};
+    use sp_std::{cmp::Ordering, vec, vec::Vec};
+	use sp_runtime::{
+		offchain::storage::StorageValueRef,
+		traits::{AccountIdConversion, Hash as HashT, Saturating, UniqueSaturatedInto, Zero},
+		PerThing, Perbill,
+	};
+	use pallet_treasury::RewardPayout;
+	use pallet_validator::ValidatorSet;
+	use crate::weights::WeightInfo;
+	use crate::url::{Rule, Url, UrlError};
+	use crate::offchain;
 
-    type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+    pub type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+    pub type NegativeImbalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::NegativeImbalance;
+	pub type BoundedUrlOf<T> = BoundedVec<u8, <T as Config>::MaxUrlLength>;
 
     #[pallet::pallet]
     #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(_);
 
-    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
 
 
 	#[pallet::config]
-	pub trait Config: frame_system::Config {
+	pub trait Config:
+		frame_system::Config + pallet_timestamp::Config + CreateSignedTransaction<Call<Self>>
+	{
+		/// A type representing the weights required by the dispatchables of this pallet.
+		type WeightInfo: crate::weights::WeightInfo;
 		type Currency: ReservableCurrency<Self::AccountId>;
+		/// Validation fee charged for every submission.
+		#[pallet::constant]
 		type SubmissionFee: Get<BalanceOf<Self>>;
+		/// Account receiving the reward pool's share of submission fees and slashed deposits.
+		type RewardPool: Get<Self::AccountId>;
 		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 		#[pallet::constant]
     	type PalletId: Get<PalletId>;
 		#[pallet::constant]
     	type MaxUrlLength: Get<u32>; // Maximum length for URLs
+		/// Number of revealed validator votes needed to approve or reject a submission by majority.
+		#[pallet::constant]
+		type ApprovalQuorum: Get<u32>;
+		/// Number of blocks validators have to vote. The votes revealed by then are tallied, and
+		/// the submission expires if they do not decide it.
+		#[pallet::constant]
+		type VotingPeriod: Get<BlockNumberFor<Self>>;
+		/// Maximum number of submissions that can enter their voting window in a single block.
+		#[pallet::constant]
+		type MaxSubmissionsPerBlock: Get<u32>;
+		/// Smallest deposit a registered miner may keep bonded.
+		#[pallet::constant]
+		type MinMinerDeposit: Get<BalanceOf<Self>>;
+		/// Number of blocks unbonded deposit stays reserved before it can be withdrawn.
+		#[pallet::constant]
+		type UnbondingPeriod: Get<BlockNumberFor<Self>>;
+		/// Maximum number of unbonding chunks a miner can have at once.
+		#[pallet::constant]
+		type MaxUnbondingChunks: Get<u32>;
+		/// Share of a miner's reserved deposit slashed for a rejected or fraudulent submission.
+		#[pallet::constant]
+		type SlashFraction: Get<Perbill>;
+		/// Pays the miner and approving validators once a submission is approved.
+		type Rewards: RewardPayout<Self::AccountId>;
+		/// Registered validators, which may vote on submissions and are told how their votes
+		/// compared with the outcome.
+		type Validators: ValidatorSet<Self::AccountId>;
+		/// Source of the randomness submission committees are sampled with. No committee is
+		/// assigned while it has none to offer.
+		type Randomness: Randomness<Option<Self::Hash>, BlockNumberFor<Self>>;
+		/// Number of validators assigned to vote on each submission.
+		#[pallet::constant]
+		type CommitteeSize: Get<u32>;
+		/// Number of blocks assigned validators have to commit their votes before they can be
+		/// replaced, after which the committed votes can be revealed.
+		#[pallet::constant]
+		type AssignmentTimeout: Get<BlockNumberFor<Self>>;
+		/// Number of snapshots kept per URL; the oldest is dropped to make room for a new one.
+		#[pallet::constant]
+		type MaxSnapshotsPerUrl: Get<u32>;
+		/// Maximum length of the off-chain storage CID of archived content.
+		#[pallet::constant]
+		type MaxCidLength: Get<u32>;
+		/// Maximum length of the MIME type of archived content.
+		#[pallet::constant]
+		type MaxMimeTypeLength: Get<u32>;
+		/// Origin allowed to edit the whitelist directly, next to enacted proposals.
+		type WhitelistOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+		/// Maximum number of rules added or removed by a single batch whitelist call.
+		#[pallet::constant]
+		type MaxWhitelistBatch: Get<u32>;
+		/// Re-archive interval of newly whitelisted rules, in blocks.
+		#[pallet::constant]
+		type DefaultRearchiveInterval: Get<BlockNumberFor<Self>>;
+		/// Share of the full reward paid for pages of `Priority::Normal` rules. `Priority::High`
+		/// rules are paid the full reward.
+		#[pallet::constant]
+		type NormalPriorityReward: Get<Perbill>;
+		/// Share of the full reward paid for pages of `Priority::Low` rules.
+		#[pallet::constant]
+		type LowPriorityReward: Get<Perbill>;
+		/// Share of a page's reward paid for confirming that it is unchanged, once the
+		/// confirmation's committee agreed.
+		#[pallet::constant]
+		type UnchangedReward: Get<Perbill>;
+		/// Fee held from a miner confirming that a page is unchanged, for the validators
+		/// checking the confirmation.
+		#[pallet::constant]
+		type UnchangedFee: Get<BalanceOf<Self>>;
+		/// Number of blocks after a submission commitment during which it can be revealed.
+		#[pallet::constant]
+		type RevealPeriod: Get<BlockNumberFor<Self>>;
+		/// Maximum number of submission commitments that can be made in a single block.
+		#[pallet::constant]
+		type MaxCommitmentsPerBlock: Get<u32>;
+		/// Deposit reserved from the proposer of a whitelist change. It is returned if the
+		/// proposal passes and moved to the reward pool otherwise.
+		#[pallet::constant]
+		type ProposalDeposit: Get<BalanceOf<Self>>;
+		/// Number of blocks a whitelist proposal is open for voting.
+		#[pallet::constant]
+		type ProposalVotingPeriod: Get<BlockNumberFor<Self>>;
+		/// Minimum stake, for and against combined, that has to vote on a whitelist proposal
+		/// for it to pass.
+		#[pallet::constant]
+		type ProposalMinTurnout: Get<BalanceOf<Self>>;
+		/// Share of the voting stake that has to be in favour of a whitelist proposal for it to
+		/// pass.
+		#[pallet::constant]
+		type ProposalApprovalThreshold: Get<Perbill>;
+		/// Maximum number of whitelist proposals whose voting closes in a single block.
+		#[pallet::constant]
+		type MaxProposalsPerBlock: Get<u32>;
+		/// Maximum number of accounts that can vote on a single whitelist proposal.
+		#[pallet::constant]
+		type MaxProposalVoters: Get<u32>;
+		/// Number of blocks a miner's claim on a crawl task lasts.
+		#[pallet::constant]
+		type TaskClaimDuration: Get<BlockNumberFor<Self>>;
+		/// Bond reserved from an account challenging an approved record. It is returned if the
+		/// record is overturned and slashed if it is upheld.
+		#[pallet::constant]
+		type ChallengeBond: Get<BalanceOf<Self>>;
+		/// Number of blocks after its approval during which a record can be challenged.
+		#[pallet::constant]
+		type ChallengePeriod: Get<BlockNumberFor<Self>>;
+		/// Share of the loser's slashed bond paid to the winner of a dispute. The rest is split
+		/// between the dispute's validators that voted with the outcome, as disputes carry no
+		/// submission fee to pay them from.
+		#[pallet::constant]
+		type ChallengeReward: Get<Perbill>;
+		/// Key the offchain worker signs the votes of local validators with, see
+		/// [`crate::offchain`].
+		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+	}
+
+	/// Version of the URL canonicalization applied to `ArchiveRecord::url`.
+	///
+	/// `0` marks records migrated from before the version was tracked, `1` URLs stored exactly
+	/// as submitted, `2` URLs with a lowercase scheme and host and no trailing slashes and `3`
+	/// the full canonicalization described in [`crate::url`].
+	pub const CANONICALIZATION_VERSION: u16 = 3;
+
+	/// Size in bits of the content fingerprints submissions and votes may carry.
+	pub const FINGERPRINT_BITS: u8 = 64;
+
+	/// Metadata of an archived page.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct ArchiveRecord<AccountId, Moment, Hash, Url, Cid, MimeType> {
+		pub miner: AccountId,
+		pub url: Url,
+		/// Time the content was submitted, from `pallet_timestamp`.
+		pub timestamp: Moment,
+		pub content_hash: Hash,
+		/// Where the content itself is stored off-chain, e.g. an IPFS CID or Arweave
+		/// transaction id.
+		pub cid: Cid,
+		/// Size of the content in bytes.
+		pub content_length: u64,
+		pub mime_type: MimeType,
+		/// `CANONICALIZATION_VERSION` at the time of submission.
+		pub canonicalization_version: u16,
+		/// Locality-sensitive fingerprint of the canonical text, such as a 64-bit SimHash, for
+		/// pages whose scrapes rarely match exactly.
+		pub fingerprint: Option<u64>,
+		/// How similar the content found by the validators approving the submission was, as
+		/// the share of matching fingerprint bits of the least similar one. Exact matches of the
+		/// content hash count as fully similar.
+		pub similarity: Perbill,
+	}
+
+	pub type ArchiveRecordOf<T> = ArchiveRecord<
+		<T as frame_system::Config>::AccountId,
+		<T as pallet_timestamp::Config>::Moment,
+		<T as frame_system::Config>::Hash,
+		BoundedVec<u8, <T as Config>::MaxUrlLength>,
+		BoundedVec<u8, <T as Config>::MaxCidLength>,
+		BoundedVec<u8, <T as Config>::MaxMimeTypeLength>,
+	>;
+
+	/// One submission of a URL's content, as recorded in that URL's history.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct Snapshot<AccountId, BlockNumber, Hash> {
+		/// Block in which the content was submitted.
+		pub block_number: BlockNumber,
+		pub content_hash: Hash,
+		pub miner: AccountId,
+		pub status: SubmissionStatus,
+	}
+
+	pub type SnapshotOf<T> = Snapshot<
+		<T as frame_system::Config>::AccountId,
+		BlockNumberFor<T>,
+		<T as frame_system::Config>::Hash,
+	>;
+
+	/// Part of a miner's deposit that is waiting out the unbonding period.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct UnlockChunk<Balance, BlockNumber> {
+		pub value: Balance,
+		pub unlock_at: BlockNumber,
+	}
+
+	/// Where a submission is in its validation lifecycle.
+	#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum SubmissionStatus {
+		/// Waiting for validator votes.
+		Pending,
+		/// Reached the approval quorum and is now a canonical archive record.
+		Approved,
+		/// Reached the rejection quorum.
+		Rejected,
+		/// The voting period ended before either quorum was reached.
+		Expired,
+		/// A miner's confirmation that the page still has the content of its latest approved
+		/// snapshot was approved by its committee.
+		Unchanged,
+		/// An approved record that was challenged and is being validated again.
+		Disputed,
+		/// An approved record that lost a dispute and is no longer canonical.
+		Overturned,
 	}
 
+	/// Change to the whitelist requested by a proposal.
+	#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum WhitelistAction {
+		Add,
+		Remove,
+	}
+
+	/// Whether submissions matching a whitelist rule are accepted.
+	#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum WhitelistStatus {
+		Enabled,
+		/// The rule is kept but matching submissions are refused.
+		Paused,
+	}
+
+	/// How valuable archiving the pages of a whitelist rule is, which scales their reward.
+	#[derive(Clone, Copy, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum Priority {
+		/// Paid `LowPriorityReward` of the full reward.
+		Low,
+		/// Paid `NormalPriorityReward` of the full reward.
+		Normal,
+		/// Paid the full reward.
+		High,
+	}
+
+	/// Metadata of a whitelist rule.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct WhitelistEntry<AccountId, BlockNumber> {
+		pub status: WhitelistStatus,
+		pub priority: Priority,
+		/// Number of blocks after which the rule's pages should be archived again.
+		pub rearchive_interval: BlockNumber,
+		/// Whether miners must honour the site's `robots.txt` when fetching its pages.
+		pub respect_robots: bool,
+		/// Largest Hamming distance between the fingerprints of a miner's and a validator's
+		/// scrape of the rule's pages for them to count as the same content.
+		pub max_fingerprint_distance: u8,
+		/// The account whose proposal added the rule, `None` if it was added by
+		/// `WhitelistOrigin` or at genesis.
+		pub added_by: Option<AccountId>,
+	}
+
+	pub type WhitelistEntryOf<T> =
+		WhitelistEntry<<T as frame_system::Config>::AccountId, BlockNumberFor<T>>;
+
+	/// A miner's lock on a crawl task.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct TaskClaim<AccountId, BlockNumber> {
+		pub miner: AccountId,
+		/// First block at which the claim no longer holds.
+		pub expires_at: BlockNumber,
+	}
+
+	/// A URL in the re-archive queue.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct CrawlTask<AccountId, BlockNumber> {
+		/// Block from which the URL should be archived again.
+		pub due_at: BlockNumber,
+		pub claim: Option<TaskClaim<AccountId, BlockNumber>>,
+	}
+
+	pub type CrawlTaskOf<T> = CrawlTask<<T as frame_system::Config>::AccountId, BlockNumberFor<T>>;
+
+	/// A whitelist change open for stake-weighted voting.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct WhitelistProposal<AccountId, Balance, BlockNumber, Url> {
+		pub proposer: AccountId,
+		pub url: Url,
+		pub action: WhitelistAction,
+		/// Reserved from the proposer until voting closes.
+		pub deposit: Balance,
+		/// Block at which voting closes and the proposal is enacted or rejected.
+		pub end: BlockNumber,
+		/// Stake voting in favour.
+		pub ayes: Balance,
+		/// Stake voting against.
+		pub nays: Balance,
+		pub voters: u32,
+	}
+
+	pub type WhitelistProposalOf<T> = WhitelistProposal<
+		<T as frame_system::Config>::AccountId,
+		BalanceOf<T>,
+		BlockNumberFor<T>,
+		BoundedVec<u8, <T as Config>::MaxUrlLength>,
+	>;
+
+	/// A proposal vote, with the stake reserved behind it.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct ProposalVote<Balance> {
+		pub aye: bool,
+		pub stake: Balance,
+	}
+
+	/// A submission that is still collecting validator votes.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct PendingSubmission<Record, Balance, BlockNumber> {
+		/// The archive record stored once the submission is approved.
+		pub record: Record,
+		/// Fee held in the pallet account until the submission is finalized.
+		pub fee: Balance,
+		pub submitted_at: BlockNumber,
+		/// Block at which the miner committed to the submission, which decides between miners
+		/// submitting the same content for the same URL.
+		pub committed_at: BlockNumber,
+		pub ayes: u32,
+		pub nays: u32,
+	}
+
+	pub type PendingSubmissionOf<T> =
+		PendingSubmission<ArchiveRecordOf<T>, BalanceOf<T>, BlockNumberFor<T>>;
+
+	/// The validators assigned to vote on a pending submission.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct Committee<Members, BlockNumber> {
+		pub members: Members,
+		/// Block at which validators last joined the committee.
+		pub assigned_at: BlockNumber,
+	}
+
+	pub type CommitteeOf<T> = Committee<
+		BoundedVec<<T as frame_system::Config>::AccountId, <T as Config>::CommitteeSize>,
+		BlockNumberFor<T>,
+	>;
+
+	/// A challenge against an approved record, settled by a fresh validator round.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct Dispute<AccountId, Balance, BlockNumber> {
+		pub challenger: AccountId,
+		/// Bond reserved from the challenger until the dispute is settled.
+		pub bond: Balance,
+		pub opened_at: BlockNumber,
+	}
+
+	pub type DisputeOf<T> =
+		Dispute<<T as frame_system::Config>::AccountId, BalanceOf<T>, BlockNumberFor<T>>;
+
     /// Tracks registered miners and their deposits.
     #[pallet::storage]
     #[pallet::getter(fn miners)]
     pub type Miners<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, OptionQuery>;
 
-    /// Whitelist for valid URLs.
+	/// Unbonded deposit chunks that are still reserved, per miner.
+	#[pallet::storage]
+	#[pallet::getter(fn unbonding)]
+	pub type Unbonding<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<UnlockChunk<BalanceOf<T>, BlockNumberFor<T>>, T::MaxUnbondingChunks>,
+		ValueQuery
+	>;
+
+	/// Miners that stopped accepting new submissions.
+	#[pallet::storage]
+	#[pallet::getter(fn chilled)]
+	pub type Chilled<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, (), OptionQuery>;
+
+	/// Number of submissions per miner that are still waiting for validator votes.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_submission_count)]
+	pub type PendingSubmissionCount<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    /// Whitelist rules, by host (prefixed with `*.` to include subdomains) and path prefix.
+    /// See [`crate::url`] for how submitted URLs are matched against them.
     #[pallet::storage]
 	#[pallet::getter(fn whitelist)]
-	pub type Whitelist<T: Config> = StorageMap<
+	pub type Whitelist<T: Config> = StorageDoubleMap<
 		_,
 		Blake2_128Concat,
 		BoundedVec<u8, T::MaxUrlLength>,
-		(),
+		Blake2_128Concat,
+		BoundedVec<u8, T::MaxUrlLength>,
+		WhitelistEntryOf<T>,
 		OptionQuery
 	>;
-    /// Tracks submitted hashes and associated metadata.
+    /// Canonical archive records, by submission id. See [`Pallet::submission_id`].
 	#[pallet::storage]
 	#[pallet::getter(fn submissions)]
-	pub type Submissions<T: Config> = StorageMap<
+	pub type Submissions<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::Hash, ArchiveRecordOf<T>, OptionQuery>;
+
+	/// Snapshots of every URL in submission order, oldest first.
+	#[pallet::storage]
+	#[pallet::getter(fn history)]
+	pub type History<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		BoundedVec<u8, T::MaxUrlLength>,
+		BoundedVec<SnapshotOf<T>, T::MaxSnapshotsPerUrl>,
+		ValueQuery
+	>;
+
+	/// Re-archive queue, by canonical URL. URLs without a task have never been archived and are
+	/// always due.
+	#[pallet::storage]
+	#[pallet::getter(fn crawl_tasks)]
+	pub type CrawlTasks<T: Config> =
+		StorageMap<_, Blake2_128Concat, BoundedVec<u8, T::MaxUrlLength>, CrawlTaskOf<T>, OptionQuery>;
+
+	/// Block at which each unrevealed submission commitment was made.
+	#[pallet::storage]
+	#[pallet::getter(fn commitments)]
+	pub type Commitments<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::Hash, BlockNumberFor<T>, OptionQuery>;
+
+	/// Submission commitments indexed by the last block at which they can be revealed.
+	#[pallet::storage]
+	pub type CommitmentExpiries<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		BlockNumberFor<T>,
+		BoundedVec<T::Hash, T::MaxCommitmentsPerBlock>,
+		ValueQuery
+	>;
+
+	/// Submissions that are still inside their voting window, by submission id.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_submissions)]
+	pub type PendingSubmissions<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::Hash, PendingSubmissionOf<T>, OptionQuery>;
+
+	/// Validators allowed to vote on each pending submission.
+	#[pallet::storage]
+	#[pallet::getter(fn committees)]
+	pub type Committees<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::Hash, CommitteeOf<T>, OptionQuery>;
+
+	/// Lifecycle status of every submission, by submission id.
+	#[pallet::storage]
+	#[pallet::getter(fn submission_status)]
+	pub type SubmissionStatuses<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::Hash, SubmissionStatus, OptionQuery>;
+
+	/// Votes revealed on pending submissions, `true` meaning the validator found the same
+	/// content hash as the miner.
+	#[pallet::storage]
+	#[pallet::getter(fn votes)]
+	pub type Votes<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::Hash,
+		Blake2_128Concat,
+		T::AccountId,
+		bool,
+		OptionQuery
+	>;
+
+	/// Unrevealed vote commitments of the committee members of pending submissions.
+	#[pallet::storage]
+	#[pallet::getter(fn vote_commitments)]
+	pub type VoteCommitments<T: Config> = StorageDoubleMap<
 		_,
 		Blake2_128Concat,
 		T::Hash,
-		(T::AccountId, BoundedVec<u8, T::MaxUrlLength>),
+		Blake2_128Concat,
+		T::AccountId,
+		T::Hash,
 		OptionQuery
 	>;
+
+	/// Last block at which each approved record can still be challenged.
+	#[pallet::storage]
+	#[pallet::getter(fn challenge_deadlines)]
+	pub type ChallengeDeadlines<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::Hash, BlockNumberFor<T>, OptionQuery>;
+
+	/// Last block at which any approved record of each miner can still be challenged. Miners
+	/// cannot withdraw their deposit before, so that a successful challenge has something to
+	/// slash.
+	#[pallet::storage]
+	#[pallet::getter(fn challengeable_until)]
+	pub type ChallengeableUntil<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, BlockNumberFor<T>, OptionQuery>;
+
+	/// The committee that approved each record, so that it does not validate the record again if
+	/// it is disputed. Removed once a dispute over the record is settled or the record is
+	/// rejected or superseded.
+	#[pallet::storage]
+	#[pallet::getter(fn approving_committees)]
+	pub type ApprovingCommittees<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::Hash,
+		BoundedVec<T::AccountId, T::CommitteeSize>,
+		OptionQuery,
+	>;
+
+	/// Open disputes over approved records, which are pending validation again meanwhile.
+	#[pallet::storage]
+	#[pallet::getter(fn disputes)]
+	pub type Disputes<T: Config> = StorageMap<_, Blake2_128Concat, T::Hash, DisputeOf<T>, OptionQuery>;
+
+	/// Pending submissions that only confirm a URL's content is unchanged, by attestation id.
+	/// They are validated like other submissions but do not become archive records.
+	#[pallet::storage]
+	#[pallet::getter(fn attestations)]
+	pub type Attestations<T: Config> = StorageMap<_, Blake2_128Concat, T::Hash, (), OptionQuery>;
+
+	/// Pending submissions indexed by the block at which their voting window closes.
+	#[pallet::storage]
+	pub type VotingDeadlines<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		BlockNumberFor<T>,
+		BoundedVec<T::Hash, T::MaxSubmissionsPerBlock>,
+		ValueQuery
+	>;
+
+	/// Identifier of the next whitelist proposal.
+	#[pallet::storage]
+	pub type NextProposalId<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	/// Whitelist proposals that are still open for voting.
+	#[pallet::storage]
+	#[pallet::getter(fn whitelist_proposals)]
+	pub type WhitelistProposals<T: Config> =
+		StorageMap<_, Twox64Concat, u32, WhitelistProposalOf<T>, OptionQuery>;
+
+	/// Votes cast on whitelist proposals. The stake behind a vote stays reserved until its
+	/// voter unlocks it after voting closed.
+	#[pallet::storage]
+	#[pallet::getter(fn proposal_votes)]
+	pub type ProposalVotes<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		u32,
+		Blake2_128Concat,
+		T::AccountId,
+		ProposalVote<BalanceOf<T>>,
+		OptionQuery
+	>;
+
+	/// Open whitelist proposals indexed by the block at which their voting closes.
+	#[pallet::storage]
+	pub type ProposalDeadlines<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		BlockNumberFor<T>,
+		BoundedVec<u32, T::MaxProposalsPerBlock>,
+		ValueQuery
+	>;
+
     /// Events emitted by the pallet.
     #[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		MinerRegistered { miner: T::AccountId, deposit: BalanceOf<T> },
-		SubmissionAccepted { miner: T::AccountId, url: Vec<u8>, hash: T::Hash },
+		MinerBonded { miner: T::AccountId, amount: BalanceOf<T> },
+		MinerChilled { miner: T::AccountId },
+		MinerUnbonded { miner: T::AccountId, amount: BalanceOf<T>, unlock_at: BlockNumberFor<T> },
+		MinerWithdrawn { miner: T::AccountId, amount: BalanceOf<T> },
+		/// The miner withdrew its whole deposit and is no longer registered.
+		MinerDeregistered { miner: T::AccountId },
+		/// Part of the miner's deposit was moved to the reward pool because of submission `id`.
+		MinerSlashed { miner: T::AccountId, id: T::Hash, amount: BalanceOf<T> },
+		/// The miner's deposit fell below `MinMinerDeposit` and it was removed from mining. Its
+		/// remaining deposit is unbonding.
+		MinerKicked { miner: T::AccountId },
+		/// The content `hash` of `url` is pending validation as submission `id`.
+		SubmissionAccepted { miner: T::AccountId, url: Vec<u8>, hash: T::Hash, id: T::Hash },
+		VoteCast { validator: T::AccountId, id: T::Hash, approve: bool },
+		SubmissionApproved { id: T::Hash },
+		SubmissionRejected { id: T::Hash },
+		SubmissionExpired { id: T::Hash },
+		SubmissionCommitted { miner: T::AccountId, commitment: T::Hash },
+		/// `to` committed to the pending submission `id` before `from` did and took it over.
+		SubmissionReassigned { id: T::Hash, from: T::AccountId, to: T::AccountId },
+		/// Unrevealed commitments whose reveal period ended were removed.
+		CommitmentsExpired { count: u32 },
+		/// `url` still has the content `hash` of its latest approved snapshot, as confirmed by
+		/// the committee of the attestation `id`.
+		SubmissionUnchanged { miner: T::AccountId, url: Vec<u8>, hash: T::Hash, id: T::Hash },
+		/// `miner` confirmed that `url` still has the content `hash`, pending validation as the
+		/// attestation `id`.
+		UnchangedContentAttested { miner: T::AccountId, url: Vec<u8>, hash: T::Hash, id: T::Hash },
+		SubmissionFeePaid { miner: T::AccountId, id: T::Hash, fee: BalanceOf<T> },
+		SubmissionFeeRefunded { miner: T::AccountId, id: T::Hash, fee: BalanceOf<T> },
+		/// A finalized submission's fee was split between the validators that voted with the
+		/// outcome and the reward pool.
+		SubmissionFeeDistributed {
+			id: T::Hash,
+			validators_share: BalanceOf<T>,
+			reward_pool_share: BalanceOf<T>,
+		},
 
 		//Embedded Whitelist
 		WhitelistUpdated { url: Vec<u8>, added: bool }, // Added for whitelist changes
+		WhitelistProposed {
+			proposal_id: u32,
+			proposer: T::AccountId,
+			url: Vec<u8>,
+			action: WhitelistAction,
+		},
+		WhitelistVoted { proposal_id: u32, voter: T::AccountId, aye: bool, stake: BalanceOf<T> },
+		/// The proposal reached the turnout and approval thresholds, and it was enacted.
+		WhitelistProposalApproved { proposal_id: u32 },
+		/// The proposal did not pass and its deposit was moved to the reward pool.
+		WhitelistProposalRejected { proposal_id: u32, deposit_slashed: BalanceOf<T> },
+		/// The stake behind `voter`'s vote on a closed proposal was released.
+		WhitelistVoteUnlocked { proposal_id: u32, voter: T::AccountId, stake: BalanceOf<T> },
+		/// The metadata of the whitelist rule `url` changed to `entry`.
+		WhitelistEntryUpdated { url: Vec<u8>, entry: WhitelistEntryOf<T> },
+		/// `url` should be archived from block `due_at` on.
+		TaskScheduled { url: Vec<u8>, due_at: BlockNumberFor<T> },
+		/// Only `miner` is rewarded for archiving `url` until block `expires_at`.
+		TaskClaimed { miner: T::AccountId, url: Vec<u8>, expires_at: BlockNumberFor<T> },
+		/// Only `validators` may vote on the pending submission `id`.
+		CommitteeAssigned { id: T::Hash, validators: Vec<T::AccountId> },
+		/// `validator` committed to the content hash it found for the pending submission `id`.
+		VoteCommitted { validator: T::AccountId, id: T::Hash },
+		/// `challenger` disputed the approved record `id`, which is validated again.
+		SubmissionChallenged { id: T::Hash, challenger: T::AccountId, bond: BalanceOf<T> },
+		/// The disputed record `id` was rejected by the new validator round and is no longer
+		/// canonical.
+		SubmissionOverturned { id: T::Hash, challenger: T::AccountId },
+		/// The disputed record `id` was approved again and `slashed` of the challenger's bond
+		/// was forfeited.
+		ChallengeFailed { id: T::Hash, challenger: T::AccountId, slashed: BalanceOf<T> },
+		/// The new validator round on the disputed record `id` reached no quorum. The record
+		/// stands and the challenger's bond is returned.
+		ChallengeExpired { id: T::Hash, challenger: T::AccountId },
 	}
 
     /// Errors that can occur in the pallet.
@@ -83,13 +705,1223 @@ pub mod pallet {
 		//Embedded Whitelist 
 		UrlAlreadyWhitelisted, // New error
     	UrlNotWhitelisted, // New error for removal
+
+		/// The content is already pending or disputed for this URL.
+		DuplicateSubmission,
+		/// Too many submissions already close their voting window in the same block.
+		TooManySubmissions,
+		/// No pending submission has this id.
+		SubmissionNotPending,
+		/// The caller is not a registered validator.
+		NotValidator,
+		/// The validator has already voted on this submission.
+		AlreadyVoted,
+		/// Validators cannot vote on their own submissions.
+		CannotVoteOwnSubmission,
+
+		/// The bonded deposit would fall below `MinMinerDeposit`.
+		DepositTooLow,
+		/// The miner has chilled and cannot submit.
+		MinerChilled,
+		/// Cannot unbond more than the bonded deposit.
+		InsufficientBond,
+		/// The miner already has `MaxUnbondingChunks` chunks unbonding.
+		TooManyUnbondingChunks,
+		/// Deposits are held while the miner has submissions awaiting votes.
+		PendingSubmissionsOutstanding,
+		/// Nothing has finished unbonding yet.
+		NothingToWithdraw,
+		/// No canonical archive record has this id.
+		SubmissionNotFound,
+		/// The off-chain storage CID exceeds `MaxCidLength`.
+		CidTooLong,
+		/// The MIME type exceeds `MaxMimeTypeLength`.
+		MimeTypeTooLong,
+		/// No open whitelist proposal has this id.
+		ProposalNotFound,
+		/// Too many proposals already close their voting in the same block.
+		TooManyProposals,
+		/// The proposal already has `MaxProposalVoters` votes.
+		TooManyProposalVoters,
+		/// The account has already voted on this proposal.
+		AlreadyVotedOnProposal,
+		/// Votes must be backed by a non-zero stake.
+		ZeroStake,
+		/// Votes can only be unlocked once voting on the proposal has closed.
+		ProposalStillOpen,
+		/// The account has no vote on this proposal.
+		ProposalVoteNotFound,
+		/// The URL does not start with a `scheme://` prefix.
+		MissingScheme,
+		/// Only `http` and `https` URLs are supported.
+		UnsupportedScheme,
+		/// The URL has no host.
+		MissingHost,
+		/// The host is malformed, or a wildcard outside of a whitelist rule.
+		InvalidHost,
+		/// The port is not a valid number.
+		InvalidPort,
+		/// The URL contains whitespace, control or non-ASCII characters.
+		InvalidCharacter,
+		/// A `%` is not followed by two hex digits.
+		InvalidPercentEncoding,
+		/// Whitelist rules cannot have a query, a wildcard host with more than four labels or a
+		/// path prefix with more than four segments.
+		InvalidWhitelistRule,
+		/// The most specific whitelist rule matching the URL is paused.
+		WhitelistPaused,
+		/// The URL was archived less than its rule's re-archive interval ago.
+		TaskNotDue,
+		/// Another miner holds a claim on the task.
+		TaskAlreadyClaimed,
+		/// The content is that of the URL's latest approved snapshot, and the URL is not due
+		/// for re-archiving.
+		UnchangedContent,
+		/// The commitment was already made.
+		DuplicateCommitment,
+		/// Too many commitments were already made in this block.
+		TooManyCommitments,
+		/// No unrevealed commitment matches the revealed submission.
+		CommitmentNotFound,
+		/// The commitment's reveal period has ended.
+		CommitmentExpired,
+		/// The validator is not on the submission's committee.
+		NotAssigned,
+		/// The committee is full and its members can still vote.
+		CommitteeActive,
+		/// No validator is available to join the committee.
+		NoValidatorsAvailable,
+		/// Votes are being revealed and can no longer be committed.
+		VoteCommitClosed,
+		/// Committee members can still commit their votes.
+		VoteRevealNotOpen,
+		/// The validator has no vote commitment to reveal.
+		VoteNotCommitted,
+		/// The revealed content hash and salt do not match the vote commitment.
+		InvalidVoteReveal,
+		/// Fingerprints cannot differ in all of their bits and still count as similar.
+		FingerprintDistanceTooLarge,
+		/// Only approved records can be challenged.
+		NotChallengeable,
+		/// The record was approved more than `ChallengePeriod` blocks ago.
+		ChallengePeriodOver,
+		/// The record is being disputed.
+		SubmissionDisputed,
+		/// Votes on the submission were revealed, so its committee can no longer change.
+		VotesRevealed,
+		/// The miner has approved records that can still be challenged.
+		RecordsChallengeable,
+		/// No randomness is available to sample a committee with.
+		RandomnessUnavailable,
     }
 
+	impl<T> From<UrlError> for Error<T> {
+		fn from(error: UrlError) -> Self {
+			match error {
+				UrlError::MissingScheme => Error::<T>::MissingScheme,
+				UrlError::UnsupportedScheme => Error::<T>::UnsupportedScheme,
+				UrlError::MissingHost => Error::<T>::MissingHost,
+				UrlError::InvalidHost => Error::<T>::InvalidHost,
+				UrlError::InvalidPort => Error::<T>::InvalidPort,
+				UrlError::InvalidCharacter => Error::<T>::InvalidCharacter,
+				UrlError::InvalidPercentEncoding => Error::<T>::InvalidPercentEncoding,
+				UrlError::InvalidRule => Error::<T>::InvalidWhitelistRule,
+			}
+		}
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Expire every submission whose voting window closes at block `n` and close the
+		/// whitelist proposals that end at `n`.
+		fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+			let mut weight = T::DbWeight::get().reads_writes(2, 2);
+			for proposal_id in ProposalDeadlines::<T>::take(n) {
+				if let Some(proposal) = WhitelistProposals::<T>::take(proposal_id) {
+					Self::close_proposal(proposal_id, proposal);
+					weight = weight.saturating_add(T::DbWeight::get().reads_writes(4, 4));
+				}
+			}
+			for id in VotingDeadlines::<T>::take(n) {
+				// Submissions whose committee revealed every vote are no longer pending, and a
+				// resubmission of the same content has a later deadline.
+				let expired = PendingSubmissions::<T>::get(id).filter(|pending| {
+					pending.submitted_at.saturating_add(T::VotingPeriod::get()) <= n
+				});
+				if let Some(pending) = expired {
+					let status = Self::tally(&pending).unwrap_or(SubmissionStatus::Expired);
+					Self::finalize(id, pending, status);
+					weight = weight.saturating_add(<T as Config>::WeightInfo::finalize_submission());
+				} else {
+					weight = weight.saturating_add(T::DbWeight::get().reads(1));
+				}
+			}
+			weight
+		}
+
+		/// Remove the unrevealed commitments whose reveal period has ended, as far as
+		/// `remaining_weight` allows.
+		fn on_idle(n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			let db = T::DbWeight::get();
+			let max_commitments = T::MaxCommitmentsPerBlock::get() as u64;
+			let visit = db.reads(1);
+			let clear = db.reads_writes(max_commitments.saturating_add(1), max_commitments.saturating_add(1));
+			let mut weight = Weight::zero();
+			let mut expired = Vec::new();
+			for block in CommitmentExpiries::<T>::iter_keys() {
+				// Leave room to clear the block in case its reveal period has ended.
+				if remaining_weight.any_lt(weight.saturating_add(visit).saturating_add(clear)) {
+					break
+				}
+				weight = weight.saturating_add(visit);
+				if block <= n {
+					expired.push(block);
+					weight = weight.saturating_add(clear);
+				}
+			}
+
+			let mut count = 0u32;
+			for block in expired {
+				for commitment in CommitmentExpiries::<T>::take(block) {
+					// Revealed commitments are already gone, and may have been made again since.
+					let ended = Commitments::<T>::get(commitment).is_some_and(|committed_at| {
+						committed_at.saturating_add(T::RevealPeriod::get()) <= n
+					});
+					if ended {
+						Commitments::<T>::remove(commitment);
+						count.saturating_inc();
+					}
+				}
+			}
+			if count > 0 {
+				Self::deposit_event(Event::CommitmentsExpired { count });
+			}
+			weight
+		}
+
+		/// Vote on the pending submissions that the validator keys in the node's keystore are
+		/// assigned to, scraping their pages.
+		fn offchain_worker(n: BlockNumberFor<T>) {
+			let signer = Signer::<T, T::AuthorityId>::all_accounts();
+			if !signer.can_sign() {
+				return
+			}
+			let accounts: Vec<Account<T>> = signer.accounts_from_keys().collect();
+			let mut assigned: Vec<_> = Committees::<T>::iter()
+				.filter(|(_, committee)| accounts.iter().any(|account| committee.members.contains(&account.id)))
+				.collect();
+			// Start at a different submission each run so that pages which keep failing to fetch
+			// cannot take up every fetch of every run.
+			if !assigned.is_empty() {
+				let run: u64 = n.unique_saturated_into();
+				let start = (run % assigned.len() as u64) as usize;
+				assigned.rotate_left(start);
+			}
+			let mut fetches = 0u32;
+			for (id, committee) in assigned {
+				for account in accounts.iter().filter(|account| committee.members.contains(&account.id)) {
+					Self::offchain_vote(n, id, &committee, account, &mut fetches);
+				}
+			}
+		}
+	}
+
+	#[pallet::genesis_config]
+	#[derive(frame_support::DefaultNoBound)]
+	pub struct GenesisConfig<T: Config> {
+		/// URLs that are whitelisted from genesis.
+		pub whitelist: Vec<Vec<u8>>,
+		#[serde(skip)]
+		pub _config: core::marker::PhantomData<T>,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+		fn build(&self) {
+			for url in &self.whitelist {
+				let (host, path_prefix) =
+					Pallet::<T>::whitelist_keys(url).expect("invalid genesis whitelist rule");
+				Whitelist::<T>::insert(&host, &path_prefix, Pallet::<T>::default_whitelist_entry(None));
+				Pallet::<T>::schedule_rule(url);
+			}
+		}
+	}
+
 	impl<T: Config> Pallet<T> {
 		/// Returns the account ID for the pallet
 		pub fn account_id() -> T::AccountId {
 			T::PalletId::get().into_account_truncating()
 		}
+
+		/// Parse a whitelist rule such as `https://*.wikipedia.org/wiki` into its `Whitelist`
+		/// keys.
+		pub fn whitelist_keys(
+			rule: &[u8],
+		) -> Result<(BoundedUrlOf<T>, BoundedUrlOf<T>), Error<T>> {
+			ensure!(rule.len() <= T::MaxUrlLength::get() as usize, Error::<T>::UrlTooLong);
+			let Rule { host, path_prefix } = Rule::parse(rule)?;
+			Ok((
+				host.try_into().map_err(|_| Error::<T>::UrlTooLong)?,
+				path_prefix.try_into().map_err(|_| Error::<T>::UrlTooLong)?,
+			))
+		}
+
+		/// Parse a submitted URL and return it along with its canonical form.
+		pub fn canonicalize_url(url: &[u8]) -> Result<(Url, BoundedUrlOf<T>), Error<T>> {
+			ensure!(url.len() <= T::MaxUrlLength::get() as usize, Error::<T>::UrlTooLong);
+			let parsed = Url::parse(url)?;
+			ensure!(!parsed.has_wildcard_host(), Error::<T>::InvalidHost);
+			let canonical = parsed.to_bytes().try_into().map_err(|_| Error::<T>::UrlTooLong)?;
+			Ok((parsed, canonical))
+		}
+
+		/// Metadata of a rule added by `added_by`, before it is updated.
+		pub fn default_whitelist_entry(added_by: Option<T::AccountId>) -> WhitelistEntryOf<T> {
+			WhitelistEntry {
+				status: WhitelistStatus::Enabled,
+				priority: Priority::Normal,
+				rearchive_interval: T::DefaultRearchiveInterval::get(),
+				respect_robots: true,
+				max_fingerprint_distance: 0,
+				added_by,
+			}
+		}
+
+		/// The most specific whitelist rule matching `url`: the one with the most specific host,
+		/// and of those the one with the longest path prefix.
+		pub fn whitelist_entry(url: &Url) -> Option<WhitelistEntryOf<T>> {
+			let mut matched: Option<(Vec<u8>, WhitelistEntryOf<T>)> = None;
+			// Candidates come grouped by host, most specific first, with growing path prefixes.
+			for Rule { host, path_prefix } in url.candidate_rules() {
+				if matched.as_ref().is_some_and(|(matched_host, _)| *matched_host != host) {
+					break
+				}
+				let keys = (
+					BoundedVec::<u8, T::MaxUrlLength>::try_from(host.clone()),
+					BoundedVec::<u8, T::MaxUrlLength>::try_from(path_prefix),
+				);
+				// Longer keys than any that can be stored cannot match.
+				if let (Ok(bounded_host), Ok(bounded_path_prefix)) = keys {
+					if let Some(entry) = Whitelist::<T>::get(bounded_host, bounded_path_prefix) {
+						matched = Some((host, entry));
+					}
+				}
+			}
+			matched.map(|(_, entry)| entry)
+		}
+
+		/// Whether the most specific whitelist rule matching `url` is enabled.
+		pub fn is_whitelisted(url: &Url) -> bool {
+			Self::whitelist_entry(url).is_some_and(|entry| entry.status == WhitelistStatus::Enabled)
+		}
+
+		/// Share of the full reward paid for an approved page of `url`, by the priority of the
+		/// rule matching it now.
+		fn reward_share(url: &[u8]) -> Perbill {
+			let priority = Url::parse(url)
+				.ok()
+				.and_then(|url| Self::whitelist_entry(&url))
+				.map_or(Priority::Normal, |entry| entry.priority);
+			match priority {
+				Priority::Low => T::LowPriorityReward::get(),
+				Priority::Normal => T::NormalPriorityReward::get(),
+				Priority::High => Perbill::one(),
+			}
+		}
+
+		/// Queue the page named by a newly whitelisted `rule`, due now. Rules with a wildcard
+		/// host do not name a page and are skipped.
+		pub(crate) fn schedule_rule(rule: &[u8]) {
+			let Ok((_, url)) = Self::canonicalize_url(rule) else { return };
+			if !CrawlTasks::<T>::contains_key(&url) {
+				let due_at = frame_system::Pallet::<T>::block_number();
+				CrawlTasks::<T>::insert(&url, CrawlTask { due_at, claim: None });
+				Self::deposit_event(Event::TaskScheduled { url: url.into(), due_at });
+			}
+		}
+
+		/// The miner whose claim on `task` still holds at block `now`.
+		fn claimant(task: &CrawlTaskOf<T>, now: BlockNumberFor<T>) -> Option<&T::AccountId> {
+			task.claim.as_ref().filter(|claim| claim.expires_at > now).map(|claim| &claim.miner)
+		}
+
+		/// Take on `url`'s task for a submission by `miner`, if it is due and not claimed by
+		/// another miner. The claim is held until the submission's voting window closes, and only
+		/// the claimant is rewarded once approved.
+		fn start_task(url: &BoundedVec<u8, T::MaxUrlLength>, miner: &T::AccountId) {
+			let now = frame_system::Pallet::<T>::block_number();
+			CrawlTasks::<T>::mutate(url, |task| {
+				let open = task.as_ref().is_none_or(|task| {
+					task.due_at <= now &&
+						Self::claimant(task, now).is_none_or(|claimant| claimant == miner)
+				});
+				if open {
+					*task = Some(CrawlTask {
+						due_at: task.as_ref().map_or(now, |task| task.due_at),
+						claim: Some(TaskClaim {
+							miner: miner.clone(),
+							expires_at: now.saturating_add(T::VotingPeriod::get()),
+						}),
+					});
+				}
+			})
+		}
+
+		/// Schedule the next archive of `url` after `miner`'s submission for its task was
+		/// approved, by the re-archive interval of the rule matching it now.
+		///
+		/// Returns whether `miner` held the task, i.e. whether the submission is rewarded.
+		fn complete_task(url: &BoundedVec<u8, T::MaxUrlLength>, miner: &T::AccountId) -> bool {
+			let Some(mut task) = CrawlTasks::<T>::get(url) else { return false };
+			if task.claim.as_ref().is_none_or(|claim| claim.miner != *miner) {
+				return false
+			}
+			let interval = Url::parse(url)
+				.ok()
+				.and_then(|url| Self::whitelist_entry(&url))
+				.map_or(T::DefaultRearchiveInterval::get(), |entry| entry.rearchive_interval);
+			task.due_at = frame_system::Pallet::<T>::block_number().saturating_add(interval);
+			task.claim = None;
+			CrawlTasks::<T>::insert(url, &task);
+			Self::deposit_event(Event::TaskScheduled { url: url.to_vec(), due_at: task.due_at });
+			true
+		}
+
+		/// Put `url`'s task back in the queue after `miner`'s submission for it failed.
+		fn release_task(url: &BoundedVec<u8, T::MaxUrlLength>, miner: &T::AccountId) {
+			CrawlTasks::<T>::mutate_extant(url, |task| {
+				if task.claim.as_ref().is_some_and(|claim| claim.miner == *miner) {
+					task.claim = None;
+				}
+			});
+		}
+
+		/// The id of the submission of the content `hash` for the canonical `url`. Storage about
+		/// submissions is keyed by it, so the same content can be archived from several URLs and
+		/// a URL can return to content it had before.
+		pub fn submission_id(url: &[u8], hash: &T::Hash) -> T::Hash {
+			T::Hashing::hash_of(&(url, hash))
+		}
+
+		/// The commitment `miner` makes to submitting `hash` for `url`, hidden by `salt`.
+		pub fn commitment_of(
+			url: &[u8],
+			hash: &T::Hash,
+			salt: &[u8; 32],
+			miner: &T::AccountId,
+		) -> T::Hash {
+			T::Hashing::hash_of(&(url, hash, salt, miner))
+		}
+
+		/// The commitment `validator` makes to having found `content_hash`, with the content's
+		/// `fingerprint`, for a submission, hidden by `salt`.
+		pub fn vote_commitment_of(
+			content_hash: &T::Hash,
+			fingerprint: &Option<u64>,
+			salt: &[u8; 32],
+			validator: &T::AccountId,
+		) -> T::Hash {
+			T::Hashing::hash_of(&(content_hash, fingerprint, salt, validator))
+		}
+
+		/// How similar the content a validator found, `content_hash` with `fingerprint`, is to
+		/// `record`. `None` if it is too different for the rule matching the record's URL.
+		pub fn vote_similarity(
+			record: &ArchiveRecordOf<T>,
+			content_hash: &T::Hash,
+			fingerprint: Option<u64>,
+		) -> Option<Perbill> {
+			if *content_hash == record.content_hash {
+				return Some(Perbill::one())
+			}
+			let distance = (record.fingerprint? ^ fingerprint?).count_ones();
+			let max_distance = Url::parse(&record.url)
+				.ok()
+				.and_then(|url| Self::whitelist_entry(&url))
+				.map_or(0, |entry| entry.max_fingerprint_distance);
+			(distance <= max_distance as u32).then(|| {
+				let bits = FINGERPRINT_BITS as u32;
+				Perbill::from_rational(bits.saturating_sub(distance), bits)
+			})
+		}
+
+		/// Whether votes on submission `id` can be revealed, which is once every member of
+		/// `committee` committed or `AssignmentTimeout` blocks after the last member joined. Votes
+		/// can only be committed before.
+		fn reveal_open(id: T::Hash, committee: &CommitteeOf<T>) -> bool {
+			let now = frame_system::Pallet::<T>::block_number();
+			now >= committee.assigned_at.saturating_add(T::AssignmentTimeout::get()) ||
+				committee.members.iter().all(|member| Self::has_voted(id, member))
+		}
+
+		/// The outcome the revealed votes on `pending` decide: the majority of them, once at least
+		/// `ApprovalQuorum` were revealed. A tie decides nothing.
+		fn tally(pending: &PendingSubmissionOf<T>) -> Option<SubmissionStatus> {
+			if pending.ayes.saturating_add(pending.nays) < T::ApprovalQuorum::get() {
+				return None
+			}
+			match pending.ayes.cmp(&pending.nays) {
+				Ordering::Greater => Some(SubmissionStatus::Approved),
+				Ordering::Less => Some(SubmissionStatus::Rejected),
+				Ordering::Equal => None,
+			}
+		}
+
+		/// Whether `validator` committed or revealed a vote on submission `id`.
+		fn has_voted(id: T::Hash, validator: &T::AccountId) -> bool {
+			VoteCommitments::<T>::contains_key(id, validator) || Votes::<T>::contains_key(id, validator)
+		}
+
+		/// Scrape the page of the pending submission `id` and commit the vote of the local
+		/// validator `account` on it, then reveal it once the votes of `committee` can be
+		/// revealed. The vote is kept in the offchain database in between, and transactions that
+		/// were not included are sent again after `offchain::RESEND_AFTER` blocks.
+		fn offchain_vote(
+			n: BlockNumberFor<T>,
+			id: T::Hash,
+			committee: &CommitteeOf<T>,
+			account: &Account<T>,
+			fetches: &mut u32,
+		) {
+			let key = (b"pallet-miner::vote", id, &account.id).encode();
+			let mut storage = StorageValueRef::persistent(&key);
+			if Votes::<T>::contains_key(id, &account.id) {
+				storage.clear();
+				return
+			}
+			let Ok(vote) = storage.get::<offchain::LocalVote<T::Hash, BlockNumberFor<T>>>() else {
+				log::warn!("Undecodable local vote on submission {:?}", id);
+				return
+			};
+			let due = |sent_at: BlockNumberFor<T>| {
+				n >= sent_at.saturating_add(offchain::RESEND_AFTER.into())
+			};
+			let reveal_open = Self::reveal_open(id, committee);
+
+			let (call, vote) = if !VoteCommitments::<T>::contains_key(id, &account.id) {
+				// Commitments are only accepted until the votes can be revealed.
+				if reveal_open || vote.as_ref().is_some_and(|vote| !due(vote.committed_at)) {
+					return
+				}
+				let mut vote = match vote {
+					Some(vote) => vote,
+					None => match Self::scrape(id, fetches) {
+						Some((content_hash, fingerprint)) => offchain::LocalVote {
+							content_hash,
+							fingerprint: Some(fingerprint),
+							salt: sp_io::offchain::random_seed(),
+							committed_at: n,
+							revealed_at: None,
+						},
+						None => return,
+					},
+				};
+				vote.committed_at = n;
+				let commitment =
+					Self::vote_commitment_of(&vote.content_hash, &vote.fingerprint, &vote.salt, &account.id);
+				(Call::commit_vote { id, commitment }, vote)
+			} else {
+				// A vote committed from elsewhere cannot be revealed here.
+				let Some(mut vote) = vote else { return };
+				if !reveal_open || vote.revealed_at.is_some_and(|revealed_at| !due(revealed_at)) {
+					return
+				}
+				vote.revealed_at = Some(n);
+				let call = Call::reveal_vote {
+					id,
+					content_hash: vote.content_hash,
+					fingerprint: vote.fingerprint,
+					salt: vote.salt,
+				};
+				(call, vote)
+			};
+
+			let results = Signer::<T, T::AuthorityId>::all_accounts()
+				.with_filter(vec![account.public.clone()])
+				.send_signed_transaction(|_| call.clone());
+			if results.iter().any(|(_, result)| result.is_err()) {
+				log::warn!("Failed to send the vote of {:?} on submission {:?}", account.id, id);
+			} else {
+				storage.set(&vote);
+			}
+		}
+
+		/// Fetch the page of the pending submission `id` and return the content hash and
+		/// fingerprint of its canonical text. Pages whose whitelist entry respects robots.txt are
+		/// only fetched if the host's robots.txt allows it, otherwise the validator abstains.
+		/// Nothing is fetched once `fetches`, the fetches of the current run, would exceed
+		/// `MAX_FETCHES_PER_RUN`.
+		fn scrape(id: T::Hash, fetches: &mut u32) -> Option<(T::Hash, u64)> {
+			let pending = PendingSubmissions::<T>::get(id)?;
+			let url = Url::parse(&pending.record.url).ok()?;
+			let respect_robots = Self::whitelist_entry(&url).is_some_and(|entry| entry.respect_robots);
+			let needed = if respect_robots { 2 } else { 1 };
+			if fetches.saturating_add(needed) > offchain::MAX_FETCHES_PER_RUN {
+				log::debug!("Fetch limit of the run reached, scraping submission {:?} later", id);
+				return None
+			}
+			// Failed fetches take their time too, so they count as well.
+			fetches.saturating_accrue(needed);
+			if respect_robots {
+				match offchain::robots_allow(&url) {
+					Ok(true) => {},
+					Ok(false) => {
+						log::info!("robots.txt disallows the page of submission {:?}, not voting", id);
+						return None
+					},
+					Err(err) => {
+						log::warn!("Failed to fetch the robots.txt of submission {:?}: {:?}", id, err);
+						return None
+					},
+				}
+			}
+			let page = offchain::fetch(&pending.record.url)
+				.map_err(|err| log::warn!("Failed to fetch the page of submission {:?}: {:?}", id, err))
+				.ok()?;
+			let text = offchain::canonicalize(&page);
+			let content_hash = T::Hash::decode(&mut &offchain::content_hash(&text)[..]).ok()?;
+			Some((content_hash, offchain::fingerprint(&text)))
+		}
+
+		/// Submit `hash` for validation on behalf of `miner`, who committed to it at block
+		/// `committed_at`.
+		#[allow(clippy::too_many_arguments)]
+		fn do_submit(
+			miner: T::AccountId,
+			url: Vec<u8>,
+			hash: T::Hash,
+			cid: Vec<u8>,
+			content_length: u64,
+			mime_type: Vec<u8>,
+			fingerprint: Option<u64>,
+			committed_at: BlockNumberFor<T>,
+		) -> DispatchResult {
+			ensure!(Miners::<T>::contains_key(&miner), Error::<T>::MinerNotRegistered);
+			ensure!(!Chilled::<T>::contains_key(&miner), Error::<T>::MinerChilled);
+
+			let (parsed_url, bounded_url) = Self::canonicalize_url(&url)?;
+			let url_bytes = bounded_url.to_vec();
+			let cid: BoundedVec<u8, T::MaxCidLength> =
+				cid.try_into().map_err(|_| Error::<T>::CidTooLong)?;
+			let mime_type: BoundedVec<u8, T::MaxMimeTypeLength> =
+				mime_type.try_into().map_err(|_| Error::<T>::MimeTypeTooLong)?;
+	
+			// Ensure the URL is whitelisted
+			let entry = Self::whitelist_entry(&parsed_url).ok_or(Error::<T>::NotWhitelisted)?;
+			ensure!(entry.status == WhitelistStatus::Enabled, Error::<T>::WhitelistPaused);
+
+			let now = frame_system::Pallet::<T>::block_number();
+			// Content that did not change since the last approved snapshot is only attested
+			let unchanged = Self::snapshot_at(&bounded_url, now)
+				.is_some_and(|snapshot| snapshot.content_hash == hash);
+
+			let record = ArchiveRecord {
+				miner: miner.clone(),
+				url: bounded_url,
+				timestamp: pallet_timestamp::Pallet::<T>::get(),
+				content_hash: hash,
+				cid,
+				content_length,
+				mime_type,
+				canonicalization_version: CANONICALIZATION_VERSION,
+				fingerprint,
+				similarity: Perbill::one(),
+			};
+			if unchanged {
+				return Self::attest_unchanged(record, committed_at)
+			}
+			let id = Self::submission_id(&url_bytes, &hash);
+			// Rejected or expired content may be submitted again, and so may approved content
+			// the URL has changed from since. An approved record stands until the new submission
+			// is approved in its place, see `finalize`.
+			match SubmissionStatuses::<T>::get(id) {
+				Some(SubmissionStatus::Pending) =>
+					return Self::reassign_submission(id, record, committed_at),
+				Some(SubmissionStatus::Disputed) =>
+					return Err(Error::<T>::DuplicateSubmission.into()),
+				_ => {},
+			}
+
+			// Open the voting window for the submission
+			VotingDeadlines::<T>::try_mutate(now.saturating_add(T::VotingPeriod::get()), |ids| {
+				ids.try_push(id)
+			})
+			.map_err(|_| Error::<T>::TooManySubmissions)?;
+
+			// Claim the URL's task if it is due and free, otherwise the submission is archived
+			// without a reward
+			Self::start_task(&record.url, &miner);
+
+			// Hold the validation fee in the pallet account until the submission is finalized
+			let fee = T::SubmissionFee::get();
+			T::Currency::transfer(&miner, &Self::account_id(), fee, ExistenceRequirement::KeepAlive)
+				.map_err(|_| Error::<T>::InsufficientFunds)?;
+			Self::deposit_event(Event::SubmissionFeePaid { miner: miner.clone(), id, fee });
+
+			Self::record_snapshot(
+				&record.url,
+				Snapshot {
+					block_number: now,
+					content_hash: hash,
+					miner: miner.clone(),
+					status: SubmissionStatus::Pending,
+				},
+			);
+
+			let pending = PendingSubmission {
+				record,
+				fee,
+				submitted_at: now,
+				committed_at,
+				ayes: 0,
+				nays: 0,
+			};
+			PendingSubmissions::<T>::insert(id, pending);
+			PendingSubmissionCount::<T>::mutate(&miner, |count| *count = count.saturating_add(1));
+			SubmissionStatuses::<T>::insert(id, SubmissionStatus::Pending);
+			let committee = Committee { members: BoundedVec::new(), assigned_at: now };
+			Self::fill_committee(id, committee, core::slice::from_ref(&miner))?;
+	
+			Self::deposit_event(Event::SubmissionAccepted { miner, url: url_bytes, hash, id });
+	
+			Ok(())
+		}
+
+		/// Hand the pending submission `id` over to the miner of `record`, if they committed to
+		/// the same content for the same URL before its current miner did. The current miner's
+		/// fee is refunded.
+		fn reassign_submission(
+			id: T::Hash,
+			record: ArchiveRecordOf<T>,
+			committed_at: BlockNumberFor<T>,
+		) -> DispatchResult {
+			let mut pending =
+				PendingSubmissions::<T>::get(id).ok_or(Error::<T>::SubmissionNotPending)?;
+			ensure!(
+				pending.record.miner != record.miner && committed_at < pending.committed_at,
+				Error::<T>::DuplicateSubmission
+			);
+
+			let fee = T::SubmissionFee::get();
+			T::Currency::transfer(&record.miner, &Self::account_id(), fee, ExistenceRequirement::KeepAlive)
+				.map_err(|_| Error::<T>::InsufficientFunds)?;
+			Self::deposit_event(Event::SubmissionFeePaid { miner: record.miner.clone(), id, fee });
+
+			let from = pending.record.miner.clone();
+			let to = record.miner.clone();
+			Self::refund_fee(id, &from, pending.fee);
+			PendingSubmissionCount::<T>::mutate(&from, |count| *count = count.saturating_sub(1));
+			PendingSubmissionCount::<T>::mutate(&to, |count| *count = count.saturating_add(1));
+			Self::release_task(&record.url, &from);
+			Self::start_task(&record.url, &to);
+			// Votes on the content still count, except the new miner's own.
+			VoteCommitments::<T>::remove(id, &to);
+			match Votes::<T>::take(id, &to) {
+				Some(true) => pending.ayes.saturating_dec(),
+				Some(false) => pending.nays.saturating_dec(),
+				None => {},
+			}
+			History::<T>::mutate(&record.url, |snapshots| {
+				if let Some(snapshot) = snapshots
+					.iter_mut()
+					.rev()
+					.find(|snapshot| snapshot.content_hash == record.content_hash)
+				{
+					snapshot.miner = to.clone();
+				}
+			});
+
+			// The new miner cannot vote on its own submission, so its seat is given to another
+			// validator that submitted neither.
+			if let Some(mut committee) = Committees::<T>::get(id) {
+				if committee.members.contains(&to) {
+					let mut exclude = committee.members.to_vec();
+					exclude.push(from.clone());
+					committee.members.retain(|member| *member != to);
+					Self::fill_committee(id, committee, &exclude)?;
+				}
+			}
+
+			pending.record = ArchiveRecord { similarity: pending.record.similarity, ..record };
+			pending.fee = fee;
+			pending.committed_at = committed_at;
+			PendingSubmissions::<T>::insert(id, pending);
+			Self::deposit_event(Event::SubmissionReassigned { id, from, to });
+			Ok(())
+		}
+
+		/// Fill the empty seats of `committee` with validators sampled by `T::Validators`, other
+		/// than `exclude`, and store it as the committee of `id`. Returns the number of
+		/// validators that joined, or `RandomnessUnavailable` rather than sampling with a seed
+		/// that can be predicted.
+		fn fill_committee(
+			id: T::Hash,
+			mut committee: CommitteeOf<T>,
+			exclude: &[T::AccountId],
+		) -> Result<u32, DispatchError> {
+			let now = frame_system::Pallet::<T>::block_number();
+			let vacancies = T::CommitteeSize::get().saturating_sub(committee.members.len() as u32);
+			let (seed, _) = T::Randomness::random(&(b"committee", id, now).encode());
+			let Some(seed) = seed else {
+				log::warn!("No randomness to assign a committee to submission {:?}", id);
+				return Err(Error::<T>::RandomnessUnavailable.into())
+			};
+			let mut joined = 0u32;
+			for validator in T::Validators::select(seed.as_ref(), vacancies, exclude) {
+				if committee.members.try_push(validator).is_ok() {
+					joined.saturating_inc();
+				}
+			}
+			if joined > 0 {
+				committee.assigned_at = now;
+			}
+			Self::deposit_event(Event::CommitteeAssigned { id, validators: committee.members.to_vec() });
+			Committees::<T>::insert(id, committee);
+			Ok(joined)
+		}
+
+		/// The id of the attestation, made at block `at`, that the URL of the submission `id`
+		/// still has its content.
+		pub fn attestation_id(id: &T::Hash, at: BlockNumberFor<T>) -> T::Hash {
+			T::Hashing::hash_of(&(id, at))
+		}
+
+		/// Have a committee confirm that the URL of `record` still has the content of its latest
+		/// approved snapshot, in place of a new archive record. Only miners taking on the URL's
+		/// task, once it is due, may do so, for `UnchangedFee`. They are paid `UnchangedReward`
+		/// of the page's reward once the committee approves.
+		fn attest_unchanged(record: ArchiveRecordOf<T>, committed_at: BlockNumberFor<T>) -> DispatchResult {
+			let miner = record.miner.clone();
+			let now = frame_system::Pallet::<T>::block_number();
+			Self::start_task(&record.url, &miner);
+			let claimant = CrawlTasks::<T>::get(&record.url)
+				.and_then(|task| Self::claimant(&task, now).cloned());
+			ensure!(claimant.as_ref() == Some(&miner), Error::<T>::UnchangedContent);
+
+			let hash = record.content_hash;
+			let id = Self::attestation_id(&Self::submission_id(&record.url, &hash), now);
+			ensure!(!PendingSubmissions::<T>::contains_key(id), Error::<T>::DuplicateSubmission);
+			VotingDeadlines::<T>::try_mutate(now.saturating_add(T::VotingPeriod::get()), |ids| {
+				ids.try_push(id)
+			})
+			.map_err(|_| Error::<T>::TooManySubmissions)?;
+
+			let fee = T::UnchangedFee::get();
+			T::Currency::transfer(&miner, &Self::account_id(), fee, ExistenceRequirement::KeepAlive)
+				.map_err(|_| Error::<T>::InsufficientFunds)?;
+			Self::deposit_event(Event::SubmissionFeePaid { miner: miner.clone(), id, fee });
+
+			Self::record_snapshot(
+				&record.url,
+				Snapshot {
+					block_number: now,
+					content_hash: hash,
+					miner: miner.clone(),
+					status: SubmissionStatus::Pending,
+				},
+			);
+			let url = record.url.to_vec();
+			let pending =
+				PendingSubmission { record, fee, submitted_at: now, committed_at, ayes: 0, nays: 0 };
+			PendingSubmissions::<T>::insert(id, pending);
+			PendingSubmissionCount::<T>::mutate(&miner, |count| *count = count.saturating_add(1));
+			SubmissionStatuses::<T>::insert(id, SubmissionStatus::Pending);
+			Attestations::<T>::insert(id, ());
+			let committee = Committee { members: BoundedVec::new(), assigned_at: now };
+			Self::fill_committee(id, committee, core::slice::from_ref(&miner))?;
+
+			Self::deposit_event(Event::UnchangedContentAttested { miner, url, hash, id });
+			Ok(())
+		}
+
+		/// Settle the attestation `id` that `record`'s URL is unchanged after its validator
+		/// round ended in `status`. Approved attestations only add an `Unchanged` snapshot to the
+		/// URL's history, and are paid `UnchangedReward` of the page's reward.
+		fn settle_attestation(
+			id: T::Hash,
+			record: ArchiveRecordOf<T>,
+			fee: BalanceOf<T>,
+			status: SubmissionStatus,
+			rewarded: &[T::AccountId],
+		) {
+			let status = match status {
+				SubmissionStatus::Approved => SubmissionStatus::Unchanged,
+				status => status,
+			};
+			SubmissionStatuses::<T>::insert(id, status);
+			Self::set_snapshot_status(&record.url, record.content_hash, status);
+			match status {
+				SubmissionStatus::Unchanged | SubmissionStatus::Rejected =>
+					Self::distribute_fee(id, fee, rewarded),
+				_ => Self::refund_fee(id, &record.miner, fee),
+			}
+
+			match status {
+				SubmissionStatus::Unchanged => {
+					if Self::complete_task(&record.url, &record.miner) {
+						let share = Self::reward_share(&record.url) * T::UnchangedReward::get();
+						if let Err(err) = T::Rewards::payout(&record.miner, rewarded, share) {
+							log::warn!("Failed to pay reward for unchanged content {:?}: {:?}", id, err);
+						}
+					}
+					Self::deposit_event(Event::SubmissionUnchanged {
+						miner: record.miner,
+						url: record.url.into(),
+						hash: record.content_hash,
+						id,
+					});
+				},
+				SubmissionStatus::Rejected => {
+					Self::release_task(&record.url, &record.miner);
+					Self::slash_miner(&record.miner, id, None, &[]);
+					Self::deposit_event(Event::SubmissionRejected { id });
+				},
+				_ => {
+					Self::release_task(&record.url, &record.miner);
+					Self::deposit_event(Event::SubmissionExpired { id });
+				},
+			}
+		}
+
+		/// Move a pending submission into its final `status`, promoting it to a canonical archive
+		/// record if it was approved. That replaces the record of an earlier approval of the same
+		/// content, which otherwise stands.
+		///
+		/// The held fee is refunded if the submission expired, otherwise it is split between the
+		/// validators whose vote matched the outcome and the reward pool.
+		pub(crate) fn finalize(
+			id: T::Hash,
+			pending: PendingSubmissionOf<T>,
+			status: SubmissionStatus,
+		) {
+			PendingSubmissions::<T>::remove(id);
+			let committee = Committees::<T>::take(id);
+			// Committee members that did not reveal in time have no say in the outcome.
+			let _ = VoteCommitments::<T>::clear_prefix(id, T::CommitteeSize::get(), None);
+			let record = pending.record;
+			PendingSubmissionCount::<T>::mutate(&record.miner, |count| {
+				*count = count.saturating_sub(1)
+			});
+
+			let winning_vote = status == SubmissionStatus::Approved;
+			let decided = matches!(status, SubmissionStatus::Approved | SubmissionStatus::Rejected);
+			let mut rewarded: Vec<T::AccountId> = Vec::new();
+			let mut voters: Vec<T::AccountId> = Vec::new();
+			for (validator, approve) in Votes::<T>::drain_prefix(id) {
+				// Votes on expired submissions are neither right nor wrong.
+				if decided {
+					T::Validators::note_outcome(&validator, approve == winning_vote);
+				}
+				if approve == winning_vote {
+					rewarded.push(validator.clone());
+				}
+				voters.push(validator);
+			}
+			for member in committee.iter().flat_map(|committee| committee.members.iter()) {
+				if !voters.contains(member) {
+					T::Validators::note_missed(member);
+				}
+			}
+			if let Some(dispute) = Disputes::<T>::take(id) {
+				return Self::settle_dispute(id, record, dispute, status, &rewarded)
+			}
+			if Attestations::<T>::take(id).is_some() {
+				return Self::settle_attestation(id, record, pending.fee, status, &rewarded)
+			}
+
+			// A resubmission of approved content that is not approved again leaves the earlier
+			// record in place, only its own snapshot takes the outcome.
+			let stands = status != SubmissionStatus::Approved && Submissions::<T>::contains_key(id);
+			SubmissionStatuses::<T>::insert(id, if stands { SubmissionStatus::Approved } else { status });
+			Self::set_snapshot_status(&record.url, record.content_hash, status);
+			match status {
+				SubmissionStatus::Approved | SubmissionStatus::Rejected =>
+					Self::distribute_fee(id, pending.fee, &rewarded),
+				_ => Self::refund_fee(id, &record.miner, pending.fee),
+			}
+
+			match status {
+				SubmissionStatus::Approved => {
+					// Only submissions for a due or claimed task are paid. The record is canonical
+					// either way, and even if the treasury cannot pay for it right now.
+					if Self::complete_task(&record.url, &record.miner) {
+						let share = Self::reward_share(&record.url);
+						if let Err(err) = T::Rewards::payout(&record.miner, &rewarded, share) {
+							log::warn!("Failed to pay reward for submission {:?}: {:?}", id, err);
+						}
+					}
+					let deadline = frame_system::Pallet::<T>::block_number()
+						.saturating_add(T::ChallengePeriod::get());
+					ChallengeDeadlines::<T>::insert(id, deadline);
+					ChallengeableUntil::<T>::mutate(&record.miner, |until| {
+						*until = Some(until.map_or(deadline, |until| until.max(deadline)))
+					});
+					Submissions::<T>::insert(id, record);
+					if let Some(committee) = committee {
+						ApprovingCommittees::<T>::insert(id, committee.members);
+					}
+					Self::deposit_event(Event::SubmissionApproved { id });
+				},
+				SubmissionStatus::Rejected => {
+					Self::release_task(&record.url, &record.miner);
+					Self::slash_miner(&record.miner, id, None, &[]);
+					Self::deposit_event(Event::SubmissionRejected { id });
+				},
+				SubmissionStatus::Expired => {
+					Self::release_task(&record.url, &record.miner);
+					Self::deposit_event(Event::SubmissionExpired { id });
+				},
+				SubmissionStatus::Pending |
+				SubmissionStatus::Unchanged |
+				SubmissionStatus::Disputed |
+				SubmissionStatus::Overturned => {},
+			}
+		}
+
+		/// Settle the dispute over the approved record `id` after its new validator round ended
+		/// in `status`. A rejected record is overturned and its miner slashed, an approved one
+		/// stands and the challenger's bond is slashed. Either way `ChallengeReward` of the slash
+		/// goes to the winner and the rest to the `rewarded` validators. Without a decision the
+		/// record stands, the bond is returned and the validators go unpaid.
+		fn settle_dispute(
+			id: T::Hash,
+			record: ArchiveRecordOf<T>,
+			dispute: DisputeOf<T>,
+			status: SubmissionStatus,
+			rewarded: &[T::AccountId],
+		) {
+			ApprovingCommittees::<T>::remove(id);
+			let challenger = dispute.challenger;
+			let status = match status {
+				SubmissionStatus::Rejected => {
+					T::Currency::unreserve(&challenger, dispute.bond);
+					Submissions::<T>::remove(id);
+					// The page needs a trustworthy archive again.
+					let now = frame_system::Pallet::<T>::block_number();
+					CrawlTasks::<T>::mutate_extant(&record.url, |task| task.due_at = task.due_at.min(now));
+					Self::slash_miner(&record.miner, id, Some(&challenger), rewarded);
+					Self::deposit_event(Event::SubmissionOverturned { id, challenger });
+					SubmissionStatus::Overturned
+				},
+				SubmissionStatus::Approved => {
+					let (imbalance, _) = T::Currency::slash_reserved(&challenger, dispute.bond);
+					let slashed = imbalance.peek();
+					Self::pay_slash(imbalance, Some(&record.miner), rewarded);
+					Self::deposit_event(Event::ChallengeFailed { id, challenger, slashed });
+					SubmissionStatus::Approved
+				},
+				_ => {
+					T::Currency::unreserve(&challenger, dispute.bond);
+					Self::deposit_event(Event::ChallengeExpired { id, challenger });
+					SubmissionStatus::Approved
+				},
+			};
+			SubmissionStatuses::<T>::insert(id, status);
+			Self::set_snapshot_status(&record.url, record.content_hash, status);
+		}
+
+		/// Pay `ChallengeReward` of a slash to the `winner` of a dispute, if any, and split the
+		/// rest evenly between `validators`. What is left goes to the reward pool.
+		fn pay_slash(
+			mut imbalance: NegativeImbalanceOf<T>,
+			winner: Option<&T::AccountId>,
+			validators: &[T::AccountId],
+		) {
+			if let Some(winner) = winner {
+				let reward = T::ChallengeReward::get();
+				let (to_winner, rest) =
+					imbalance.ration(reward.deconstruct(), reward.left_from_one().deconstruct());
+				T::Currency::resolve_creating(winner, to_winner);
+				imbalance = rest;
+			}
+			if !validators.is_empty() {
+				let per_validator = imbalance.peek() / BalanceOf::<T>::from(validators.len() as u32);
+				for validator in validators {
+					let (to_validator, rest) = imbalance.split(per_validator);
+					T::Currency::resolve_creating(validator, to_validator);
+					imbalance = rest;
+				}
+			}
+			T::Currency::resolve_creating(&T::RewardPool::get(), imbalance);
+		}
+
+		/// Enact a closed proposal if enough stake voted on it and enough of that stake was in
+		/// favour, and settle the proposer's deposit. Voters unlock their own stake with
+		/// `unlock_proposal_vote`.
+		fn close_proposal(proposal_id: u32, proposal: WhitelistProposalOf<T>) {
+			let turnout = proposal.ayes.saturating_add(proposal.nays);
+			let passed = turnout >= T::ProposalMinTurnout::get() &&
+				proposal.ayes > T::ProposalApprovalThreshold::get() * turnout;
+			if passed {
+				T::Currency::unreserve(&proposal.proposer, proposal.deposit);
+				let added = proposal.action == WhitelistAction::Add;
+				// The rule was validated when it was proposed.
+				if let Ok((host, path_prefix)) = Self::whitelist_keys(&proposal.url) {
+					// The whitelist may have been edited directly while voting was open.
+					if added != Whitelist::<T>::contains_key(&host, &path_prefix) {
+						if added {
+							let entry = Self::default_whitelist_entry(Some(proposal.proposer.clone()));
+							Whitelist::<T>::insert(&host, &path_prefix, entry);
+							Self::schedule_rule(&proposal.url);
+						} else {
+							Whitelist::<T>::remove(&host, &path_prefix);
+						}
+						Self::deposit_event(Event::WhitelistUpdated { url: proposal.url.into(), added });
+					}
+				}
+				Self::deposit_event(Event::WhitelistProposalApproved { proposal_id });
+			} else {
+				let (imbalance, _) =
+					T::Currency::slash_reserved(&proposal.proposer, proposal.deposit);
+				let deposit_slashed = imbalance.peek();
+				T::Currency::resolve_creating(&T::RewardPool::get(), imbalance);
+				Self::deposit_event(Event::WhitelistProposalRejected { proposal_id, deposit_slashed });
+			}
+		}
+
+		/// Append a snapshot to `url`'s history, dropping the oldest one if the history is full.
+		fn record_snapshot(url: &BoundedVec<u8, T::MaxUrlLength>, snapshot: SnapshotOf<T>) {
+			History::<T>::mutate(url, |snapshots| {
+				if snapshots.is_full() {
+					snapshots.remove(0);
+				}
+				// Cannot fail, there is room after the removal above.
+				let _ = snapshots.try_push(snapshot);
+			});
+		}
+
+		/// Update the latest snapshot of `hash` in `url`'s history.
+		fn set_snapshot_status(
+			url: &BoundedVec<u8, T::MaxUrlLength>,
+			hash: T::Hash,
+			status: SubmissionStatus,
+		) {
+			History::<T>::mutate(url, |snapshots| {
+				if let Some(snapshot) =
+					snapshots.iter_mut().rev().find(|snapshot| snapshot.content_hash == hash)
+				{
+					snapshot.status = status;
+				}
+			});
+		}
+
+		/// The approved snapshot of `url` that was current at block `at`, if any.
+		pub fn snapshot_at(
+			url: &BoundedVec<u8, T::MaxUrlLength>,
+			at: BlockNumberFor<T>,
+		) -> Option<SnapshotOf<T>> {
+			History::<T>::get(url).into_iter().rev().find(|snapshot| {
+				snapshot.block_number <= at && snapshot.status == SubmissionStatus::Approved
+			})
+		}
+
+		/// Slash `SlashFraction` of everything `miner` has reserved, bonded and unbonding, into
+		/// the reward pool, or partly to the `winner` and `validators` of a dispute over `id`, see
+		/// `pay_slash`. Miners left below `MinMinerDeposit` are chilled and their remaining
+		/// deposit starts unbonding.
+		pub(crate) fn slash_miner(
+			miner: &T::AccountId,
+			id: T::Hash,
+			winner: Option<&T::AccountId>,
+			validators: &[T::AccountId],
+		) {
+			let Some(mut bonded) = Miners::<T>::get(miner) else { return };
+			let mut chunks = Unbonding::<T>::get(miner);
+			let total = chunks
+				.iter()
+				.fold(bonded, |total, chunk| total.saturating_add(chunk.value));
+
+			let (imbalance, _) = T::Currency::slash_reserved(miner, T::SlashFraction::get() * total);
+			let slashed = imbalance.peek();
+			Self::pay_slash(imbalance, winner, validators);
+
+			// Take the slash out of the bonded deposit first, then the most recent chunks.
+			let mut remaining = slashed;
+			let from_bond = remaining.min(bonded);
+			bonded = bonded.saturating_sub(from_bond);
+			remaining = remaining.saturating_sub(from_bond);
+			while !remaining.is_zero() {
+				let Some(chunk) = chunks.last_mut() else { break };
+				let from_chunk = remaining.min(chunk.value);
+				chunk.value = chunk.value.saturating_sub(from_chunk);
+				remaining = remaining.saturating_sub(from_chunk);
+				if chunk.value.is_zero() {
+					chunks.pop();
+				}
+			}
+			Self::deposit_event(Event::MinerSlashed { miner: miner.clone(), id, amount: slashed });
+
+			if bonded < T::MinMinerDeposit::get() {
+				if !bonded.is_zero() {
+					let unlock_at = frame_system::Pallet::<T>::block_number()
+						.saturating_add(T::UnbondingPeriod::get());
+					let chunk = UnlockChunk { value: bonded, unlock_at };
+					if let Err(chunk) = chunks.try_push(chunk) {
+						// Out of chunk slots, fold it into the latest chunk instead.
+						if let Some(last) = chunks.last_mut() {
+							last.value = last.value.saturating_add(chunk.value);
+							last.unlock_at = last.unlock_at.max(chunk.unlock_at);
+						}
+					}
+					bonded = Zero::zero();
+				}
+				Chilled::<T>::insert(miner, ());
+				Self::deposit_event(Event::MinerKicked { miner: miner.clone() });
+			}
+
+			Miners::<T>::insert(miner, bonded);
+			Unbonding::<T>::insert(miner, chunks);
+		}
+
+		/// Pay half of `fee` out evenly to `validators` and the rest to the reward pool.
+		fn distribute_fee(id: T::Hash, fee: BalanceOf<T>, validators: &[T::AccountId]) {
+			let mut validators_share = BalanceOf::<T>::zero();
+			if !validators.is_empty() {
+				let per_validator = fee / BalanceOf::<T>::from(2u32) /
+					BalanceOf::<T>::from(validators.len() as u32);
+				for validator in validators {
+					// A failed payout is left to the reward pool.
+					if T::Currency::transfer(
+						&Self::account_id(),
+						validator,
+						per_validator,
+						ExistenceRequirement::AllowDeath,
+					)
+					.is_ok()
+					{
+						validators_share = validators_share.saturating_add(per_validator);
+					}
+				}
+			}
+
+			let reward_pool_share = fee.saturating_sub(validators_share);
+			if T::Currency::transfer(
+				&Self::account_id(),
+				&T::RewardPool::get(),
+				reward_pool_share,
+				ExistenceRequirement::AllowDeath,
+			)
+			.is_err()
+			{
+				log::warn!("Failed to move submission fee for {:?} to the reward pool", id);
+			}
+			Self::deposit_event(Event::SubmissionFeeDistributed {
+				id,
+				validators_share,
+				reward_pool_share,
+			});
+		}
+
+		/// Return a held submission fee to the miner.
+		fn refund_fee(id: T::Hash, miner: &T::AccountId, fee: BalanceOf<T>) {
+			if T::Currency::transfer(&Self::account_id(), miner, fee, ExistenceRequirement::AllowDeath)
+				.is_err()
+			{
+				log::warn!("Failed to refund submission fee for {:?}", id);
+				return;
+			}
+			Self::deposit_event(Event::SubmissionFeeRefunded { miner: miner.clone(), id, fee });
+		}
 	}
 
     /// Pallet calls.
@@ -97,79 +1929,662 @@ pub mod pallet {
     impl<T: Config> Pallet<T> {
         /// Register a new miner with a deposit.
         #[pallet::call_index(0)]
-        #[pallet::weight(10_000)]
+        #[pallet::weight(<T as Config>::WeightInfo::register_miner())]
         pub fn register_miner(origin: OriginFor<T>, deposit: BalanceOf<T>) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
             ensure!(!Miners::<T>::contains_key(&who), Error::<T>::MinerAlreadyRegistered);
+			ensure!(deposit >= T::MinMinerDeposit::get(), Error::<T>::DepositTooLow);
 
             T::Currency::reserve(&who, deposit)?;
             Miners::<T>::insert(&who, deposit);
-			log::info!("About to deposit event for miner registration");
             Self::deposit_event(Event::MinerRegistered { miner: who.clone(), deposit });
-			log::info!("Event deposited");
             Ok(())
         }
 
-        /// Submit a hash for validation.
+        /// Submit a hash for validation, along with where and how the content is stored
+        /// off-chain.
+        ///
+        /// Content identical to the URL's latest approved snapshot does not become a new record.
+        /// Its committee only confirms that it is unchanged, for the lower `UnchangedFee`, see
+        /// `Event::UnchangedContentAttested`.
+        ///
+        /// The submission counts as committed to in the current block. Use `commit_submission`
+        /// and `reveal_submission` to keep other miners from copying it before it is included.
+        ///
+        /// An optional `fingerprint` of the content lets validators approve it when their own
+        /// scrape is similar enough, see `WhitelistEntry::max_fingerprint_distance`.
         #[pallet::call_index(1)]
-        #[pallet::weight(10_000)]
-        pub fn submit_hash(origin: OriginFor<T>, url: Vec<u8>, hash: T::Hash) -> DispatchResult {
+        #[pallet::weight(<T as Config>::WeightInfo::submit_hash(url.len() as u32, T::Validators::max_validators()))]
+        pub fn submit_hash(
+			origin: OriginFor<T>,
+			url: Vec<u8>,
+			hash: T::Hash,
+			cid: Vec<u8>,
+			content_length: u64,
+			mime_type: Vec<u8>,
+			fingerprint: Option<u64>,
+		) -> DispatchResult {
 			let miner = ensure_signed(origin)?;
-
-			// Convert `url` to `BoundedVec`
-			let bounded_url: BoundedVec<u8, T::MaxUrlLength> =
-				url.clone().try_into().map_err(|_| Error::<T>::UrlTooLong)?;
-	
-			// Ensure the URL is whitelisted
-			ensure!(Whitelist::<T>::contains_key(&bounded_url), Error::<T>::NotWhitelisted);
-	
-			// Insert the submission
-			Submissions::<T>::insert(hash, (&miner, bounded_url));
-	
-			Self::deposit_event(Event::SubmissionAccepted { miner, url, hash });
-	
-			Ok(())
+			let now = frame_system::Pallet::<T>::block_number();
+			Self::do_submit(miner, url, hash, cid, content_length, mime_type, fingerprint, now)
         }
 
 		///Embedded Whitelist placeholders
-		 /// Add a URL to the whitelist.
+		 /// Add a rule to the whitelist. Rules are URLs whose host may start with `*.` to also
+		 /// cover subdomains, and whose path is matched as a prefix of submitted URLs.
 		 #[pallet::call_index(2)]
-		 #[pallet::weight(10_000)]
+		 #[pallet::weight(<T as Config>::WeightInfo::add_to_whitelist(url.len() as u32))]
 		 pub fn add_to_whitelist(origin: OriginFor<T>, url: Vec<u8>) -> DispatchResult {
-			 ensure_root(origin)?; // Only root can modify the whitelist
+			 T::WhitelistOrigin::ensure_origin(origin)?;
 	 
-			 // Convert URL to a bounded vector
-			 let bounded_url: BoundedVec<u8, T::MaxUrlLength> =
-				 url.clone().try_into().map_err(|_| Error::<T>::UrlTooLong)?;
+			 let (host, path_prefix) = Self::whitelist_keys(&url)?;
 	 
 			 // Ensure the URL is not already whitelisted
-			 ensure!(!Whitelist::<T>::contains_key(&bounded_url), Error::<T>::UrlAlreadyWhitelisted);
+			 ensure!(
+				 !Whitelist::<T>::contains_key(&host, &path_prefix),
+				 Error::<T>::UrlAlreadyWhitelisted
+			 );
 	 
 			 // Add to the whitelist
-			 Whitelist::<T>::insert(&bounded_url, ());
+			 Whitelist::<T>::insert(&host, &path_prefix, Self::default_whitelist_entry(None));
+			 Self::schedule_rule(&url);
 			 Self::deposit_event(Event::WhitelistUpdated { url, added: true });
 			 Ok(())
 		 }
 	 
-		 /// Remove a URL from the whitelist.
+		 /// Remove a rule from the whitelist.
 		 #[pallet::call_index(3)]
-		 #[pallet::weight(10_000)]
+		 #[pallet::weight(<T as Config>::WeightInfo::remove_from_whitelist(url.len() as u32))]
 		 pub fn remove_from_whitelist(origin: OriginFor<T>, url: Vec<u8>) -> DispatchResult {
-			 ensure_root(origin)?; // Only root can modify the whitelist
+			 T::WhitelistOrigin::ensure_origin(origin)?;
 	 
-			 // Convert URL to a bounded vector
-			 let bounded_url: BoundedVec<u8, T::MaxUrlLength> =
-				 url.clone().try_into().map_err(|_| Error::<T>::UrlTooLong)?;
+			 let (host, path_prefix) = Self::whitelist_keys(&url)?;
 	 
 			 // Ensure the URL is already whitelisted
-			 ensure!(Whitelist::<T>::contains_key(&bounded_url), Error::<T>::UrlNotWhitelisted);
+			 ensure!(
+				 Whitelist::<T>::contains_key(&host, &path_prefix),
+				 Error::<T>::UrlNotWhitelisted
+			 );
 	 
 			 // Remove from the whitelist
-			 Whitelist::<T>::remove(&bounded_url);
+			 Whitelist::<T>::remove(&host, &path_prefix);
 			 Self::deposit_event(Event::WhitelistUpdated { url, added: false });
 			 Ok(())
 		 }
+
+		/// Add several whitelist rules at once. Rules that are already whitelisted are skipped.
+		#[pallet::call_index(14)]
+		#[pallet::weight(<T as Config>::WeightInfo::batch_add_to_whitelist(urls.len() as u32))]
+		pub fn batch_add_to_whitelist(
+			origin: OriginFor<T>,
+			urls: BoundedVec<BoundedVec<u8, T::MaxUrlLength>, T::MaxWhitelistBatch>,
+		) -> DispatchResult {
+			T::WhitelistOrigin::ensure_origin(origin)?;
+
+			for url in urls {
+				let (host, path_prefix) = Self::whitelist_keys(&url)?;
+				if !Whitelist::<T>::contains_key(&host, &path_prefix) {
+					Whitelist::<T>::insert(&host, &path_prefix, Self::default_whitelist_entry(None));
+					Self::schedule_rule(&url);
+					Self::deposit_event(Event::WhitelistUpdated { url: url.into(), added: true });
+				}
+			}
+			Ok(())
+		}
+
+		/// Remove several whitelist rules at once. Rules that are not whitelisted are skipped.
+		#[pallet::call_index(15)]
+		#[pallet::weight(<T as Config>::WeightInfo::batch_remove_from_whitelist(urls.len() as u32))]
+		pub fn batch_remove_from_whitelist(
+			origin: OriginFor<T>,
+			urls: BoundedVec<BoundedVec<u8, T::MaxUrlLength>, T::MaxWhitelistBatch>,
+		) -> DispatchResult {
+			T::WhitelistOrigin::ensure_origin(origin)?;
+
+			for url in urls {
+				let (host, path_prefix) = Self::whitelist_keys(&url)?;
+				if Whitelist::<T>::take(&host, &path_prefix).is_some() {
+					Self::deposit_event(Event::WhitelistUpdated { url: url.into(), added: false });
+				}
+			}
+			Ok(())
+		}
+
+		/// Update the metadata of a whitelist rule. Fields left as `None` keep their value.
+		#[pallet::call_index(16)]
+		#[pallet::weight(<T as Config>::WeightInfo::update_whitelist_entry(url.len() as u32))]
+		pub fn update_whitelist_entry(
+			origin: OriginFor<T>,
+			url: Vec<u8>,
+			status: Option<WhitelistStatus>,
+			priority: Option<Priority>,
+			rearchive_interval: Option<BlockNumberFor<T>>,
+			respect_robots: Option<bool>,
+			max_fingerprint_distance: Option<u8>,
+		) -> DispatchResult {
+			T::WhitelistOrigin::ensure_origin(origin)?;
+			let (host, path_prefix) = Self::whitelist_keys(&url)?;
+			ensure!(
+				max_fingerprint_distance.is_none_or(|distance| distance < FINGERPRINT_BITS),
+				Error::<T>::FingerprintDistanceTooLarge
+			);
+
+			let entry = Whitelist::<T>::try_mutate(&host, &path_prefix, |entry| {
+				let entry = entry.as_mut().ok_or(Error::<T>::UrlNotWhitelisted)?;
+				if let Some(status) = status {
+					entry.status = status;
+				}
+				if let Some(priority) = priority {
+					entry.priority = priority;
+				}
+				if let Some(rearchive_interval) = rearchive_interval {
+					entry.rearchive_interval = rearchive_interval;
+				}
+				if let Some(respect_robots) = respect_robots {
+					entry.respect_robots = respect_robots;
+				}
+				if let Some(max_fingerprint_distance) = max_fingerprint_distance {
+					entry.max_fingerprint_distance = max_fingerprint_distance;
+				}
+				Ok::<_, Error<T>>(entry.clone())
+			})?;
+			Self::deposit_event(Event::WhitelistEntryUpdated { url, entry });
+			Ok(())
+		}
+
+		/// Commit to the content hash the caller found for the pending submission `id` it was
+		/// assigned to, as `vote_commitment_of(content_hash, fingerprint, salt, caller)`. Keeping it
+		/// hidden until every member committed stops validators from copying each other's votes.
+		#[pallet::call_index(21)]
+		#[pallet::weight(<T as Config>::WeightInfo::commit_vote())]
+		pub fn commit_vote(origin: OriginFor<T>, id: T::Hash, commitment: T::Hash) -> DispatchResult {
+			let validator = ensure_signed(origin)?;
+			ensure!(T::Validators::is_validator(&validator), Error::<T>::NotValidator);
+
+			let pending =
+				PendingSubmissions::<T>::get(id).ok_or(Error::<T>::SubmissionNotPending)?;
+			ensure!(pending.record.miner != validator, Error::<T>::CannotVoteOwnSubmission);
+			let committee = Committees::<T>::get(id)
+				.filter(|committee| committee.members.contains(&validator))
+				.ok_or(Error::<T>::NotAssigned)?;
+			ensure!(!Self::has_voted(id, &validator), Error::<T>::AlreadyVoted);
+			ensure!(!Self::reveal_open(id, &committee), Error::<T>::VoteCommitClosed);
+
+			VoteCommitments::<T>::insert(id, &validator, commitment);
+			T::Validators::note_vote(&validator);
+			Self::deposit_event(Event::VoteCommitted { validator, id });
+			Ok(())
+		}
+
+		/// Reveal the content hash, fingerprint and salt behind the caller's vote commitment. The
+		/// vote approves the submission if the content hash is the miner's, or if both carry a
+		/// fingerprint and these are within the `max_fingerprint_distance` of the URL's rule.
+		///
+		/// Once every committed vote is revealed the submission is decided by the majority of the
+		/// votes, if at least `ApprovalQuorum` were revealed. Otherwise the votes revealed by the
+		/// end of the `VotingPeriod` are tallied then.
+		#[pallet::call_index(22)]
+		#[pallet::weight(<T as Config>::WeightInfo::reveal_vote())]
+		pub fn reveal_vote(
+			origin: OriginFor<T>,
+			id: T::Hash,
+			content_hash: T::Hash,
+			fingerprint: Option<u64>,
+			salt: [u8; 32],
+		) -> DispatchResult {
+			let validator = ensure_signed(origin)?;
+			let mut pending =
+				PendingSubmissions::<T>::get(id).ok_or(Error::<T>::SubmissionNotPending)?;
+			let commitment =
+				VoteCommitments::<T>::get(id, &validator).ok_or(Error::<T>::VoteNotCommitted)?;
+			ensure!(
+				commitment == Self::vote_commitment_of(&content_hash, &fingerprint, &salt, &validator),
+				Error::<T>::InvalidVoteReveal
+			);
+			let committee = Committees::<T>::get(id).ok_or(Error::<T>::NotAssigned)?;
+			ensure!(Self::reveal_open(id, &committee), Error::<T>::VoteRevealNotOpen);
+
+			let similarity = Self::vote_similarity(&pending.record, &content_hash, fingerprint);
+			let approve = similarity.is_some();
+			VoteCommitments::<T>::remove(id, &validator);
+			Votes::<T>::insert(id, &validator, approve);
+			if let Some(similarity) = similarity {
+				pending.record.similarity = pending.record.similarity.min(similarity);
+			}
+			if approve {
+				pending.ayes = pending.ayes.saturating_add(1);
+			} else {
+				pending.nays = pending.nays.saturating_add(1);
+			}
+			Self::deposit_event(Event::VoteCast { validator, id, approve });
+
+			// Members that did not commit by now can no longer vote.
+			let revealed = VoteCommitments::<T>::iter_prefix_values(id).next().is_none();
+			match Self::tally(&pending) {
+				Some(status) if revealed => Self::finalize(id, pending, status),
+				_ => PendingSubmissions::<T>::insert(id, pending),
+			}
+			Ok(())
+		}
+
+		/// Reject a canonical archive record that was proven not to match the page it claims
+		/// to archive, slashing the miner that submitted it.
+		#[pallet::call_index(11)]
+		#[pallet::weight(<T as Config>::WeightInfo::report_mismatch())]
+		pub fn report_mismatch(origin: OriginFor<T>, id: T::Hash) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(!Disputes::<T>::contains_key(id), Error::<T>::SubmissionDisputed);
+			let record = Submissions::<T>::take(id).ok_or(Error::<T>::SubmissionNotFound)?;
+
+			SubmissionStatuses::<T>::insert(id, SubmissionStatus::Rejected);
+			Self::set_snapshot_status(&record.url, record.content_hash, SubmissionStatus::Rejected);
+			// The page needs a trustworthy archive again.
+			let now = frame_system::Pallet::<T>::block_number();
+			CrawlTasks::<T>::mutate_extant(&record.url, |task| task.due_at = task.due_at.min(now));
+			ChallengeDeadlines::<T>::remove(id);
+			ApprovingCommittees::<T>::remove(id);
+			Self::slash_miner(&record.miner, id, None, &[]);
+			Self::deposit_event(Event::SubmissionRejected { id });
+			Ok(())
+		}
+
+		/// Reserve additional funds on top of a registered miner's deposit.
+		#[pallet::call_index(7)]
+		#[pallet::weight(<T as Config>::WeightInfo::bond_extra())]
+		pub fn bond_extra(origin: OriginFor<T>, amount: BalanceOf<T>) -> DispatchResult {
+			let miner = ensure_signed(origin)?;
+			let bonded = Miners::<T>::get(&miner).ok_or(Error::<T>::MinerNotRegistered)?;
+
+			T::Currency::reserve(&miner, amount)?;
+			Miners::<T>::insert(&miner, bonded.saturating_add(amount));
+			Self::deposit_event(Event::MinerBonded { miner, amount });
+			Ok(())
+		}
+
+		/// Stop accepting new submissions from the caller. Submissions already pending are
+		/// still finalized as usual.
+		#[pallet::call_index(8)]
+		#[pallet::weight(<T as Config>::WeightInfo::chill())]
+		pub fn chill(origin: OriginFor<T>) -> DispatchResult {
+			let miner = ensure_signed(origin)?;
+			ensure!(Miners::<T>::contains_key(&miner), Error::<T>::MinerNotRegistered);
+
+			Chilled::<T>::insert(&miner, ());
+			Self::deposit_event(Event::MinerChilled { miner });
+			Ok(())
+		}
+
+		/// Schedule part of the deposit for release after `UnbondingPeriod` blocks.
+		///
+		/// What remains bonded must be zero or at least `MinMinerDeposit`. Unbonding everything
+		/// also chills the miner.
+		#[pallet::call_index(9)]
+		#[pallet::weight(<T as Config>::WeightInfo::unbond())]
+		pub fn unbond(origin: OriginFor<T>, amount: BalanceOf<T>) -> DispatchResult {
+			let miner = ensure_signed(origin)?;
+			let bonded = Miners::<T>::get(&miner).ok_or(Error::<T>::MinerNotRegistered)?;
+			ensure!(amount <= bonded, Error::<T>::InsufficientBond);
+
+			let remaining = bonded.saturating_sub(amount);
+			ensure!(
+				remaining.is_zero() || remaining >= T::MinMinerDeposit::get(),
+				Error::<T>::DepositTooLow
+			);
+
+			let unlock_at =
+				frame_system::Pallet::<T>::block_number().saturating_add(T::UnbondingPeriod::get());
+			Unbonding::<T>::try_mutate(&miner, |chunks| {
+				chunks.try_push(UnlockChunk { value: amount, unlock_at })
+			})
+			.map_err(|_| Error::<T>::TooManyUnbondingChunks)?;
+
+			Miners::<T>::insert(&miner, remaining);
+			if remaining.is_zero() && !Chilled::<T>::contains_key(&miner) {
+				Chilled::<T>::insert(&miner, ());
+				Self::deposit_event(Event::MinerChilled { miner: miner.clone() });
+			}
+			Self::deposit_event(Event::MinerUnbonded { miner, amount, unlock_at });
+			Ok(())
+		}
+
+		/// Release every unbonding chunk whose period has elapsed. Once nothing is bonded or
+		/// unbonding anymore the miner is deregistered. Nothing is released while a submission
+		/// of the miner is pending or disputed, or one of its records can still be challenged.
+		#[pallet::call_index(10)]
+		#[pallet::weight(<T as Config>::WeightInfo::withdraw_unbonded())]
+		pub fn withdraw_unbonded(origin: OriginFor<T>) -> DispatchResult {
+			let miner = ensure_signed(origin)?;
+			let bonded = Miners::<T>::get(&miner).ok_or(Error::<T>::MinerNotRegistered)?;
+			ensure!(
+				PendingSubmissionCount::<T>::get(&miner) == 0,
+				Error::<T>::PendingSubmissionsOutstanding
+			);
+			let now = frame_system::Pallet::<T>::block_number();
+			ensure!(
+				ChallengeableUntil::<T>::get(&miner).is_none_or(|until| until < now),
+				Error::<T>::RecordsChallengeable
+			);
+
+			let mut chunks = Unbonding::<T>::get(&miner);
+			let mut amount = BalanceOf::<T>::zero();
+			chunks.retain(|chunk| {
+				if chunk.unlock_at <= now {
+					amount = amount.saturating_add(chunk.value);
+					false
+				} else {
+					true
+				}
+			});
+			let deregister = bonded.is_zero() && chunks.is_empty();
+			// A slashed miner may have nothing left to withdraw but still needs to deregister.
+			ensure!(!amount.is_zero() || deregister, Error::<T>::NothingToWithdraw);
+
+			if !amount.is_zero() {
+				T::Currency::unreserve(&miner, amount);
+				Self::deposit_event(Event::MinerWithdrawn { miner: miner.clone(), amount });
+			}
+
+			if deregister {
+				Miners::<T>::remove(&miner);
+				Unbonding::<T>::remove(&miner);
+				Chilled::<T>::remove(&miner);
+				ChallengeableUntil::<T>::remove(&miner);
+				Self::deposit_event(Event::MinerDeregistered { miner });
+			} else {
+				Unbonding::<T>::insert(&miner, chunks);
+			}
+			Ok(())
+		}
+
+		/// Propose adding a URL to or removing it from the whitelist, reserving
+		/// `ProposalDeposit` from the caller. Voting is open for `ProposalVotingPeriod` blocks.
+		#[pallet::call_index(12)]
+		#[pallet::weight(<T as Config>::WeightInfo::propose_whitelist_change(url.len() as u32))]
+		pub fn propose_whitelist_change(
+			origin: OriginFor<T>,
+			url: Vec<u8>,
+			action: WhitelistAction,
+		) -> DispatchResult {
+			let proposer = ensure_signed(origin)?;
+			let (host, path_prefix) = Self::whitelist_keys(&url)?;
+			match action {
+				WhitelistAction::Add => ensure!(
+					!Whitelist::<T>::contains_key(&host, &path_prefix),
+					Error::<T>::UrlAlreadyWhitelisted
+				),
+				WhitelistAction::Remove => ensure!(
+					Whitelist::<T>::contains_key(&host, &path_prefix),
+					Error::<T>::UrlNotWhitelisted
+				),
+			}
+			let bounded_url: BoundedVec<u8, T::MaxUrlLength> =
+				url.clone().try_into().map_err(|_| Error::<T>::UrlTooLong)?;
+
+			let proposal_id = NextProposalId::<T>::get();
+			let end = frame_system::Pallet::<T>::block_number()
+				.saturating_add(T::ProposalVotingPeriod::get());
+			ProposalDeadlines::<T>::try_mutate(end, |ids| ids.try_push(proposal_id))
+				.map_err(|_| Error::<T>::TooManyProposals)?;
+			let deposit = T::ProposalDeposit::get();
+			T::Currency::reserve(&proposer, deposit)?;
+
+			NextProposalId::<T>::put(proposal_id.wrapping_add(1));
+			WhitelistProposals::<T>::insert(
+				proposal_id,
+				WhitelistProposal {
+					proposer: proposer.clone(),
+					url: bounded_url,
+					action,
+					deposit,
+					end,
+					ayes: Zero::zero(),
+					nays: Zero::zero(),
+					voters: 0,
+				},
+			);
+			Self::deposit_event(Event::WhitelistProposed { proposal_id, proposer, url, action });
+			Ok(())
+		}
+
+		/// Vote on an open whitelist proposal, weighted by `stake`. The stake stays reserved
+		/// until it is unlocked with `unlock_proposal_vote` once voting closed.
+		#[pallet::call_index(13)]
+		#[pallet::weight(<T as Config>::WeightInfo::vote_whitelist_proposal())]
+		pub fn vote_whitelist_proposal(
+			origin: OriginFor<T>,
+			proposal_id: u32,
+			aye: bool,
+			stake: BalanceOf<T>,
+		) -> DispatchResult {
+			let voter = ensure_signed(origin)?;
+			ensure!(!stake.is_zero(), Error::<T>::ZeroStake);
+			let mut proposal =
+				WhitelistProposals::<T>::get(proposal_id).ok_or(Error::<T>::ProposalNotFound)?;
+			ensure!(
+				!ProposalVotes::<T>::contains_key(proposal_id, &voter),
+				Error::<T>::AlreadyVotedOnProposal
+			);
+			ensure!(proposal.voters < T::MaxProposalVoters::get(), Error::<T>::TooManyProposalVoters);
+
+			T::Currency::reserve(&voter, stake)?;
+			if aye {
+				proposal.ayes = proposal.ayes.saturating_add(stake);
+			} else {
+				proposal.nays = proposal.nays.saturating_add(stake);
+			}
+			proposal.voters.saturating_inc();
+			WhitelistProposals::<T>::insert(proposal_id, proposal);
+			ProposalVotes::<T>::insert(proposal_id, &voter, ProposalVote { aye, stake });
+
+			Self::deposit_event(Event::WhitelistVoted { proposal_id, voter, aye, stake });
+			Ok(())
+		}
+
+		/// Release the stake behind the caller's vote on a whitelist proposal whose voting has
+		/// closed.
+		#[pallet::call_index(24)]
+		#[pallet::weight(<T as Config>::WeightInfo::unlock_proposal_vote())]
+		pub fn unlock_proposal_vote(origin: OriginFor<T>, proposal_id: u32) -> DispatchResult {
+			let voter = ensure_signed(origin)?;
+			ensure!(
+				!WhitelistProposals::<T>::contains_key(proposal_id),
+				Error::<T>::ProposalStillOpen
+			);
+			let vote = ProposalVotes::<T>::take(proposal_id, &voter)
+				.ok_or(Error::<T>::ProposalVoteNotFound)?;
+			T::Currency::unreserve(&voter, vote.stake);
+
+			Self::deposit_event(Event::WhitelistVoteUnlocked { proposal_id, voter, stake: vote.stake });
+			Ok(())
+		}
+
+		/// Claim a due URL of the re-archive queue for `TaskClaimDuration` blocks. Until then only
+		/// the caller is rewarded for archiving it.
+		#[pallet::call_index(17)]
+		#[pallet::weight(<T as Config>::WeightInfo::claim_task(url.len() as u32))]
+		pub fn claim_task(origin: OriginFor<T>, url: Vec<u8>) -> DispatchResult {
+			let miner = ensure_signed(origin)?;
+			ensure!(Miners::<T>::contains_key(&miner), Error::<T>::MinerNotRegistered);
+			ensure!(!Chilled::<T>::contains_key(&miner), Error::<T>::MinerChilled);
+
+			let (parsed_url, bounded_url) = Self::canonicalize_url(&url)?;
+			let entry = Self::whitelist_entry(&parsed_url).ok_or(Error::<T>::NotWhitelisted)?;
+			ensure!(entry.status == WhitelistStatus::Enabled, Error::<T>::WhitelistPaused);
+
+			let now = frame_system::Pallet::<T>::block_number();
+			let expires_at = now.saturating_add(T::TaskClaimDuration::get());
+			CrawlTasks::<T>::try_mutate(&bounded_url, |task| {
+				let due_at = task.as_ref().map_or(now, |task| task.due_at);
+				ensure!(due_at <= now, Error::<T>::TaskNotDue);
+				ensure!(
+					task.as_ref().and_then(|task| Self::claimant(task, now)).is_none(),
+					Error::<T>::TaskAlreadyClaimed
+				);
+				*task = Some(CrawlTask {
+					due_at,
+					claim: Some(TaskClaim { miner: miner.clone(), expires_at }),
+				});
+				Ok::<_, Error<T>>(())
+			})?;
+
+			Self::deposit_event(Event::TaskClaimed { miner, url: bounded_url.into(), expires_at });
+			Ok(())
+		}
+
+		/// Commit to a submission without publishing it. `commitment` is
+		/// `commitment_of(url, hash, salt, miner)`, to be revealed with `reveal_submission` within
+		/// `RevealPeriod` blocks.
+		#[pallet::call_index(18)]
+		#[pallet::weight(<T as Config>::WeightInfo::commit_submission())]
+		pub fn commit_submission(origin: OriginFor<T>, commitment: T::Hash) -> DispatchResult {
+			let miner = ensure_signed(origin)?;
+			ensure!(Miners::<T>::contains_key(&miner), Error::<T>::MinerNotRegistered);
+			ensure!(!Chilled::<T>::contains_key(&miner), Error::<T>::MinerChilled);
+			ensure!(!Commitments::<T>::contains_key(commitment), Error::<T>::DuplicateCommitment);
+
+			let now = frame_system::Pallet::<T>::block_number();
+			CommitmentExpiries::<T>::try_mutate(now.saturating_add(T::RevealPeriod::get()), |commitments| {
+				commitments.try_push(commitment)
+			})
+			.map_err(|_| Error::<T>::TooManyCommitments)?;
+			Commitments::<T>::insert(commitment, now);
+
+			Self::deposit_event(Event::SubmissionCommitted { miner, commitment });
+			Ok(())
+		}
+
+		/// Reveal and submit a submission committed to with `commit_submission`, see
+		/// `submit_hash`. Of the miners submitting the same content for the same URL, the one that
+		/// committed first keeps the submission.
+		#[pallet::call_index(19)]
+		#[pallet::weight(<T as Config>::WeightInfo::reveal_submission(url.len() as u32, T::Validators::max_validators()))]
+		#[allow(clippy::too_many_arguments)]
+		pub fn reveal_submission(
+			origin: OriginFor<T>,
+			url: Vec<u8>,
+			hash: T::Hash,
+			cid: Vec<u8>,
+			content_length: u64,
+			mime_type: Vec<u8>,
+			fingerprint: Option<u64>,
+			salt: [u8; 32],
+		) -> DispatchResult {
+			let miner = ensure_signed(origin)?;
+			let commitment = Self::commitment_of(&url, &hash, &salt, &miner);
+			let committed_at =
+				Commitments::<T>::take(commitment).ok_or(Error::<T>::CommitmentNotFound)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() <=
+					committed_at.saturating_add(T::RevealPeriod::get()),
+				Error::<T>::CommitmentExpired
+			);
+			Self::do_submit(miner, url, hash, cid, content_length, mime_type, fingerprint, committed_at)
+		}
+
+		/// Replace the committee members of a pending submission that have not committed a vote
+		/// within `AssignmentTimeout` blocks of joining with newly sampled validators. Seats left
+		/// empty because too few validators were available can be filled at any time, until the
+		/// first vote is revealed.
+		#[pallet::call_index(20)]
+		#[pallet::weight(<T as Config>::WeightInfo::reassign_committee(T::Validators::max_validators()))]
+		pub fn reassign_committee(origin: OriginFor<T>, id: T::Hash) -> DispatchResult {
+			ensure_signed(origin)?;
+			let pending =
+				PendingSubmissions::<T>::get(id).ok_or(Error::<T>::SubmissionNotPending)?;
+			// New members would commit after votes were revealed, and could copy them.
+			ensure!(Votes::<T>::iter_prefix_values(id).next().is_none(), Error::<T>::VotesRevealed);
+			// Submissions made before committees existed start out without one.
+			let mut committee = Committees::<T>::get(id).unwrap_or(Committee {
+				members: BoundedVec::new(),
+				assigned_at: pending.submitted_at,
+			});
+
+			// Validators that timed out are not sampled again.
+			let mut exclude = committee.members.to_vec();
+			exclude.push(pending.record.miner);
+			if let Some(dispute) = Disputes::<T>::get(id) {
+				exclude.push(dispute.challenger);
+				exclude.extend(ApprovingCommittees::<T>::get(id).unwrap_or_default());
+			}
+			let now = frame_system::Pallet::<T>::block_number();
+			if now >= committee.assigned_at.saturating_add(T::AssignmentTimeout::get()) {
+				committee.members.retain(|member| {
+					let voted = Self::has_voted(id, member);
+					if !voted {
+						T::Validators::note_missed(member);
+					}
+					voted
+				});
+			}
+			ensure!(
+				(committee.members.len() as u32) < T::CommitteeSize::get(),
+				Error::<T>::CommitteeActive
+			);
+			ensure!(
+				Self::fill_committee(id, committee, &exclude)? > 0,
+				Error::<T>::NoValidatorsAvailable
+			);
+			Ok(())
+		}
+
+		/// Dispute an approved archive record within `ChallengePeriod` blocks of its approval,
+		/// reserving `ChallengeBond` from the caller. The record is validated again by a fresh
+		/// committee that excludes its miner, the challenger and the committee that approved it,
+		/// and the loser's bond or deposit is slashed once the vote is finalized.
+		///
+		/// Disputes carry no submission fee. Their validators are paid from the slash instead:
+		/// what the winner's `ChallengeReward` leaves is split between the validators that voted
+		/// with the outcome. A dispute that is not decided slashes nobody and pays nobody.
+		#[pallet::call_index(23)]
+		#[pallet::weight(<T as Config>::WeightInfo::challenge_submission(T::Validators::max_validators()))]
+		pub fn challenge_submission(origin: OriginFor<T>, id: T::Hash) -> DispatchResult {
+			let challenger = ensure_signed(origin)?;
+			ensure!(
+				SubmissionStatuses::<T>::get(id) == Some(SubmissionStatus::Approved),
+				Error::<T>::NotChallengeable
+			);
+			let now = frame_system::Pallet::<T>::block_number();
+			let deadline = ChallengeDeadlines::<T>::get(id).ok_or(Error::<T>::ChallengePeriodOver)?;
+			ensure!(now <= deadline, Error::<T>::ChallengePeriodOver);
+			let record = Submissions::<T>::get(id).ok_or(Error::<T>::SubmissionNotFound)?;
+
+			let bond = T::ChallengeBond::get();
+			T::Currency::reserve(&challenger, bond).map_err(|_| Error::<T>::InsufficientFunds)?;
+			VotingDeadlines::<T>::try_mutate(now.saturating_add(T::VotingPeriod::get()), |ids| {
+				ids.try_push(id)
+			})
+			.map_err(|_| Error::<T>::TooManySubmissions)?;
+
+			let miner = record.miner.clone();
+			Self::set_snapshot_status(&record.url, record.content_hash, SubmissionStatus::Disputed);
+			PendingSubmissions::<T>::insert(
+				id,
+				PendingSubmission {
+					record,
+					fee: Zero::zero(),
+					submitted_at: now,
+					committed_at: now,
+					ayes: 0,
+					nays: 0,
+				},
+			);
+			PendingSubmissionCount::<T>::mutate(&miner, |count| *count = count.saturating_add(1));
+			SubmissionStatuses::<T>::insert(id, SubmissionStatus::Disputed);
+			ChallengeDeadlines::<T>::remove(id);
+			Disputes::<T>::insert(
+				id,
+				Dispute { challenger: challenger.clone(), bond, opened_at: now },
+			);
+			let mut exclude = ApprovingCommittees::<T>::get(id).unwrap_or_default().into_inner();
+			exclude.push(miner);
+			exclude.push(challenger.clone());
+			let committee = Committee { members: BoundedVec::new(), assigned_at: now };
+			Self::fill_committee(id, committee, &exclude)?;
+
+			Self::deposit_event(Event::SubmissionChallenged { id, challenger, bond });
+			Ok(())
+		}
     }
 }
 
@@ -177,4 +2592,5 @@ pub mod pallet {
 mod mock;
 
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests;
\ No newline at end of file
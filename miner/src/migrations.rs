@@ -0,0 +1,122 @@
+//! Storage migrations for the miner pallet.
+
+use crate::pallet::*;
+#[cfg(feature = "try-runtime")]
+use codec::{Decode, Encode};
+use frame_support::{
+    migrations::VersionedMigration, pallet_prelude::*, traits::UncheckedOnRuntimeUpgrade,
+    BoundedVec,
+};
+use sp_runtime::{traits::Zero, Perbill};
+use sp_std::vec::Vec;
+
+pub mod v2 {
+    use super::*;
+
+    /// `Submissions` values of version 1, the miner and URL of the content hash they are keyed
+    /// by.
+    pub type OldSubmission<T> =
+        (<T as frame_system::Config>::AccountId, BoundedVec<u8, <T as Config>::MaxUrlLength>);
+
+    /// `Submissions` of version 1, keyed by content hash.
+    #[frame_support::storage_alias]
+    pub type Submissions<T: Config> = StorageMap<
+        Pallet<T>,
+        Blake2_128Concat,
+        <T as frame_system::Config>::Hash,
+        OldSubmission<T>,
+        OptionQuery,
+    >;
+
+    /// `Whitelist` of version 1, with exact URLs as keys.
+    #[frame_support::storage_alias]
+    pub type Whitelist<T: Config> = StorageMap<
+        Pallet<T>,
+        Blake2_128Concat,
+        BoundedVec<u8, <T as Config>::MaxUrlLength>,
+        (),
+        OptionQuery,
+    >;
+
+    /// A record for content archived before its metadata was tracked. Only the miner, URL and
+    /// hash are known, and without a fingerprint it is only matched exactly.
+    fn legacy_record<T: Config>(
+        miner: T::AccountId,
+        url: BoundedVec<u8, T::MaxUrlLength>,
+        content_hash: T::Hash,
+    ) -> ArchiveRecordOf<T> {
+        ArchiveRecord {
+            miner,
+            url,
+            timestamp: Zero::zero(),
+            content_hash,
+            cid: BoundedVec::new(),
+            content_length: 0,
+            mime_type: BoundedVec::new(),
+            canonicalization_version: 0,
+            fingerprint: None,
+            similarity: Perbill::one(),
+        }
+    }
+
+    pub struct InnerMigrateToV2<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateToV2<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let submissions: Vec<_> = Submissions::<T>::drain().collect();
+            let urls: Vec<_> = Whitelist::<T>::drain().map(|(url, _)| url).collect();
+            let migrated = (submissions.len() + urls.len()) as u64;
+            for (hash, (miner, url)) in submissions {
+                // Records are keyed by URL and content hash, so the same content can be archived
+                // from several URLs.
+                let id = Pallet::<T>::submission_id(&url, &hash);
+                crate::pallet::Submissions::<T>::insert(id, legacy_record::<T>(miner, url, hash));
+            }
+            for url in urls {
+                // Each whitelisted URL becomes a rule covering it and its subpages.
+                match Pallet::<T>::whitelist_keys(&url) {
+                    Ok((host, path_prefix)) => crate::pallet::Whitelist::<T>::insert(
+                        host,
+                        path_prefix,
+                        Pallet::<T>::default_whitelist_entry(None),
+                    ),
+                    Err(err) => log::warn!("Dropping invalid whitelist URL {:?}: {:?}", url, err),
+                }
+            }
+            T::DbWeight::get().reads_writes(migrated, migrated.saturating_mul(2))
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+            let submissions = Submissions::<T>::iter_keys().count() as u32;
+            let urls = Whitelist::<T>::iter_keys().count() as u32;
+            Ok((submissions, urls).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+            let (submissions, urls) = <(u32, u32)>::decode(&mut &state[..])
+                .map_err(|_| "invalid pre-upgrade state")?;
+            ensure!(
+                crate::pallet::Submissions::<T>::iter_values().count() as u32 == submissions,
+                "Submissions failed to decode after the migration"
+            );
+            let rules = crate::pallet::Whitelist::<T>::iter_values().count() as u32;
+            if rules != urls {
+                log::warn!("{} of {} whitelisted URLs were dropped", urls.saturating_sub(rules), urls);
+            }
+            Ok(())
+        }
+    }
+
+    /// Turns every `Submissions` entry into an archive record keyed by its submission id and
+    /// every whitelisted URL into a host and path prefix rule with the default metadata, and
+    /// bumps the storage version from 1 to 2.
+    pub type MigrateToV2<T> = VersionedMigration<
+        1,
+        2,
+        InnerMigrateToV2<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
@@ -4,24 +4,25 @@ use crate as pallet_miner;
 use frame_support::{parameter_types, traits::ConstU128, traits::ConstU64};
 use sp_core::H256;
 use sp_runtime::{
-    testing::Header,
-    traits::{BlakeTwo256, IdentityLookup},
+    traits::{BlakeTwo256, Extrinsic as ExtrinsicT, Hash, IdentityLookup},
     BuildStorage,
     AccountId32,
+    DispatchResult,
+    MultiSignature,
+    MultiSigner,
+    Perbill,
 };
+use std::cell::RefCell;
 
 
-type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+pub type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
 
 frame_support::construct_runtime!(
-    pub enum Test where
-        Block = Block,
-        NodeBlock = Block,
-        UncheckedExtrinsic = UncheckedExtrinsic,
-    {
+    pub enum Test {
         System: frame_system,
         Balances: pallet_balances,
+        Timestamp: pallet_timestamp,
         Miner: pallet_miner,
     }
 );
@@ -30,8 +31,149 @@ parameter_types! {
     pub const BlockHashCount: u64 = 250;
     pub const MaxLocks: u32 = 50;
     pub const MaxUrlLength: u32 = 256;
-    pub const SubmissionFee: u64 = 10;
+    pub const SubmissionFee: u128 = 10;
+    pub RewardPool: AccountId32 = AccountId32::new([9; 32]);
     pub const MinerPalletId: frame_support::PalletId = frame_support::PalletId(*b"py/miner");
+    pub const ApprovalQuorum: u32 = 2;
+    pub const VotingPeriod: u64 = 10;
+    pub const MaxSubmissionsPerBlock: u32 = 16;
+    pub const MinMinerDeposit: u128 = 50;
+    pub const UnbondingPeriod: u64 = 5;
+    pub const MaxUnbondingChunks: u32 = 4;
+    pub const SlashFraction: Perbill = Perbill::from_percent(20);
+    pub const MaxSnapshotsPerUrl: u32 = 3;
+    pub const MaxCidLength: u32 = 64;
+    pub const MaxMimeTypeLength: u32 = 32;
+    pub const ProposalDeposit: u128 = 20;
+    pub const ProposalVotingPeriod: u64 = 5;
+    pub const ProposalMinTurnout: u128 = 50;
+    pub const ProposalApprovalThreshold: Perbill = Perbill::from_percent(50);
+    pub const MaxProposalsPerBlock: u32 = 4;
+    pub const MaxProposalVoters: u32 = 3;
+    pub const MaxWhitelistBatch: u32 = 4;
+    pub const DefaultRearchiveInterval: u64 = 100;
+    pub const NormalPriorityReward: Perbill = Perbill::from_percent(50);
+    pub const LowPriorityReward: Perbill = Perbill::from_percent(25);
+    pub const TaskClaimDuration: u64 = 5;
+    pub const UnchangedReward: Perbill = Perbill::from_percent(10);
+    pub const UnchangedFee: u128 = 2;
+    pub const RevealPeriod: u64 = 3;
+    pub const MaxCommitmentsPerBlock: u32 = 4;
+    pub const CommitteeSize: u32 = 3;
+    pub const AssignmentTimeout: u64 = 4;
+    pub const ChallengeBond: u128 = 50;
+    pub const ChallengePeriod: u64 = 20;
+    pub const ChallengeReward: Perbill = Perbill::from_percent(50);
+}
+
+thread_local! {
+    /// Every reward paid out, as the miner and the share of the full reward.
+    pub static PAYOUTS: RefCell<Vec<(AccountId32, Perbill)>> = const { RefCell::new(Vec::new()) };
+    /// The registered validators.
+    pub static VALIDATORS: RefCell<Vec<AccountId32>> = const { RefCell::new(Vec::new()) };
+    /// Every vote outcome reported to the validator set, as the validator and whether it agreed.
+    pub static OUTCOMES: RefCell<Vec<(AccountId32, bool)>> = const { RefCell::new(Vec::new()) };
+    /// Every validator reported to have missed an assigned vote, once per missed vote.
+    pub static MISSED: RefCell<Vec<AccountId32>> = const { RefCell::new(Vec::new()) };
+    /// Whether `MockRandomness` has randomness to offer.
+    pub static RANDOMNESS: RefCell<bool> = const { RefCell::new(true) };
+}
+
+/// Records payouts in `PAYOUTS` instead of moving funds.
+pub struct MockRewards;
+
+impl pallet_treasury::RewardPayout<AccountId32> for MockRewards {
+    fn payout(miner: &AccountId32, _validators: &[AccountId32], share: Perbill) -> DispatchResult {
+        PAYOUTS.with(|payouts| payouts.borrow_mut().push((miner.clone(), share)));
+        Ok(())
+    }
+}
+
+/// Validator set backed by `VALIDATORS`, recording vote outcomes in `OUTCOMES` and missed votes
+/// in `MISSED`. Committees are the first validators in registration order.
+pub struct MockValidators;
+
+impl pallet_validator::ValidatorSet<AccountId32> for MockValidators {
+    fn is_validator(who: &AccountId32) -> bool {
+        VALIDATORS.with(|validators| validators.borrow().contains(who))
+    }
+
+    fn validators() -> Vec<AccountId32> {
+        VALIDATORS.with(|validators| validators.borrow().clone())
+    }
+
+    fn reputation(_who: &AccountId32) -> Perbill {
+        Perbill::from_percent(50)
+    }
+
+    fn max_validators() -> u32 {
+        10
+    }
+
+    fn select(_seed: &[u8], count: u32, exclude: &[AccountId32]) -> Vec<AccountId32> {
+        VALIDATORS.with(|validators| {
+            validators
+                .borrow()
+                .iter()
+                .filter(|validator| !exclude.contains(validator))
+                .take(count as usize)
+                .cloned()
+                .collect()
+        })
+    }
+
+    fn note_vote(_who: &AccountId32) {}
+
+    fn note_missed(who: &AccountId32) {
+        MISSED.with(|missed| missed.borrow_mut().push(who.clone()));
+    }
+
+    fn note_outcome(who: &AccountId32, agreed: bool) {
+        OUTCOMES.with(|outcomes| outcomes.borrow_mut().push((who.clone(), agreed)));
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn register_for_benchmarks(who: &AccountId32) {
+        VALIDATORS.with(|validators| validators.borrow_mut().push(who.clone()));
+    }
+}
+
+/// Randomness that only depends on the subject, unless `RANDOMNESS` is switched off.
+pub struct MockRandomness;
+
+impl frame_support::traits::Randomness<Option<H256>, u64> for MockRandomness {
+    fn random(subject: &[u8]) -> (Option<H256>, u64) {
+        let available = RANDOMNESS.with(|randomness| *randomness.borrow());
+        (available.then(|| BlakeTwo256::hash(subject)), System::block_number())
+    }
+}
+
+impl frame_system::offchain::SigningTypes for Test {
+    type Public = MultiSigner;
+    type Signature = MultiSignature;
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Test
+where
+    RuntimeCall: From<C>,
+{
+    type OverarchingCall = RuntimeCall;
+    type Extrinsic = UncheckedExtrinsic;
+}
+
+/// Transactions carry the account of their signer, without a signature.
+impl<C> frame_system::offchain::CreateSignedTransaction<C> for Test
+where
+    RuntimeCall: From<C>,
+{
+    fn create_transaction<A: frame_system::offchain::AppCrypto<MultiSigner, MultiSignature>>(
+        call: RuntimeCall,
+        _public: MultiSigner,
+        account: AccountId32,
+        _nonce: u64,
+    ) -> Option<(RuntimeCall, <UncheckedExtrinsic as ExtrinsicT>::SignaturePayload)> {
+        Some((call, (account, (), ())))
+    }
 }
 
 // Frame System Config
@@ -83,14 +225,56 @@ impl pallet_balances::Config for Test {
     type MaxFreezes = frame_support::traits::ConstU32<1>;
 }
 
+impl pallet_timestamp::Config for Test {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = ConstU64<1>;
+    type WeightInfo = ();
+}
 
 impl pallet_miner::Config for Test {
     type Currency = Balances;
     type SubmissionFee = SubmissionFee;
+    type RewardPool = RewardPool;
     type RuntimeEvent = RuntimeEvent;
     type PalletId = MinerPalletId;
     type MaxUrlLength = MaxUrlLength;
-    //type WeightInfo = ();
+    type ApprovalQuorum = ApprovalQuorum;
+    type VotingPeriod = VotingPeriod;
+    type MaxSubmissionsPerBlock = MaxSubmissionsPerBlock;
+    type MinMinerDeposit = MinMinerDeposit;
+    type UnbondingPeriod = UnbondingPeriod;
+    type MaxUnbondingChunks = MaxUnbondingChunks;
+    type SlashFraction = SlashFraction;
+    type Rewards = MockRewards;
+    type Validators = MockValidators;
+    type Randomness = MockRandomness;
+    type CommitteeSize = CommitteeSize;
+    type AssignmentTimeout = AssignmentTimeout;
+    type MaxSnapshotsPerUrl = MaxSnapshotsPerUrl;
+    type MaxCidLength = MaxCidLength;
+    type MaxMimeTypeLength = MaxMimeTypeLength;
+    type WhitelistOrigin = frame_system::EnsureRoot<AccountId32>;
+    type MaxWhitelistBatch = MaxWhitelistBatch;
+    type DefaultRearchiveInterval = DefaultRearchiveInterval;
+    type NormalPriorityReward = NormalPriorityReward;
+    type LowPriorityReward = LowPriorityReward;
+    type UnchangedReward = UnchangedReward;
+    type UnchangedFee = UnchangedFee;
+    type ProposalDeposit = ProposalDeposit;
+    type ProposalVotingPeriod = ProposalVotingPeriod;
+    type ProposalMinTurnout = ProposalMinTurnout;
+    type ProposalApprovalThreshold = ProposalApprovalThreshold;
+    type MaxProposalsPerBlock = MaxProposalsPerBlock;
+    type MaxProposalVoters = MaxProposalVoters;
+    type TaskClaimDuration = TaskClaimDuration;
+    type RevealPeriod = RevealPeriod;
+    type MaxCommitmentsPerBlock = MaxCommitmentsPerBlock;
+    type ChallengeBond = ChallengeBond;
+    type ChallengePeriod = ChallengePeriod;
+    type ChallengeReward = ChallengeReward;
+    type AuthorityId = pallet_miner::offchain::crypto::ValidatorAuthId;
+    type WeightInfo = ();
 }
 
 pub fn new_test_ext() -> sp_io::TestExternalities {
@@ -106,6 +290,12 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
     .assimilate_storage(&mut storage)
     .unwrap();
 
+    PAYOUTS.with(|payouts| payouts.borrow_mut().clear());
+    VALIDATORS.with(|validators| validators.borrow_mut().clear());
+    OUTCOMES.with(|outcomes| outcomes.borrow_mut().clear());
+    MISSED.with(|missed| missed.borrow_mut().clear());
+    RANDOMNESS.with(|randomness| *randomness.borrow_mut() = true);
+
     // Convert the storage into TestExternalities
     let mut ext = sp_io::TestExternalities::new(storage);
     ext.execute_with(|| System::set_block_number(1)); // Ensure a block number is set
@@ -0,0 +1,490 @@
+//! Page validation for the offchain worker of the miner pallet.
+//!
+//! Validators running a node with offchain workers enabled scrape the pages of the submissions
+//! they are assigned to and vote on them without any external tooling. A page is validated like
+//! the reference Python tooling does it:
+//!
+//! - the page is fetched with a plain `GET` request, after its host's robots.txt is checked if
+//!   the whitelist entry of the URL asks to respect it, see [`robots_allow`],
+//! - its HTML is reduced to canonical text by [`canonicalize`],
+//! - the content hash is the SHA-256 of that text, see [`content_hash`],
+//! - the fingerprint is the 64-bit SimHash of its words, see [`fingerprint`].
+//!
+//! Votes are signed with a validator key of type [`KEY_TYPE`] in the node's keystore, which has
+//! to belong to a registered validator account.
+//!
+//! Each run fetches at most [`MAX_FETCHES_PER_RUN`] pages and robots.txt files, so a run cannot
+//! take much longer than a few fetch timeouts. Submissions left over are scraped by later runs.
+//!
+//! The worker is part of the miner pallet rather than the validator pallet because everything it
+//! works with belongs here: the committees it votes in, the pending submissions and whitelist
+//! rules it scrapes by, and the `commit_vote` and `reveal_vote` calls it sends. Of the validator
+//! pallet it only needs the validator keys, which live in the node's keystore.
+
+use crate::url::Url;
+use alloc::string::String;
+use codec::{Decode, Encode};
+use sp_core::{crypto::KeyTypeId, offchain::Duration};
+use sp_runtime::offchain::http;
+use sp_std::vec::Vec;
+
+/// Key type of the keys validators sign the votes of their offchain worker with.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"evwv");
+
+/// Time a page has to respond within, in milliseconds.
+pub const FETCH_TIMEOUT: u64 = 10_000;
+
+/// Largest page that is fetched, in bytes.
+pub const MAX_PAGE_SIZE: usize = 8 * 1024 * 1024;
+
+/// Pages and robots.txt files fetched per offchain worker run at most.
+pub const MAX_FETCHES_PER_RUN: u32 = 4;
+
+/// Blocks after which a vote transaction that was not included yet is sent again.
+pub const RESEND_AFTER: u32 = 5;
+
+/// The vote of a local validator key on a submission, kept in the offchain database until it
+/// is revealed.
+#[derive(Clone, Encode, Decode, Debug, Eq, PartialEq)]
+pub struct LocalVote<Hash, BlockNumber> {
+	pub content_hash: Hash,
+	pub fingerprint: Option<u64>,
+	/// Salt hiding the vote in its commitment.
+	pub salt: [u8; 32],
+	/// Block at which the commitment was last sent.
+	pub committed_at: BlockNumber,
+	/// Block at which the reveal was last sent, if it was.
+	pub revealed_at: Option<BlockNumber>,
+}
+
+/// Sr25519 validator keys, signing for `MultiSigner` accounts.
+pub mod crypto {
+	use super::KEY_TYPE;
+	use sp_runtime::{
+		app_crypto::{app_crypto, sr25519},
+		MultiSignature, MultiSigner,
+	};
+
+	app_crypto!(sr25519, KEY_TYPE);
+
+	pub struct ValidatorAuthId;
+
+	impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for ValidatorAuthId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+}
+
+/// Why a page could not be fetched.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FetchError {
+	/// The stored URL is not valid UTF-8.
+	InvalidUrl,
+	/// The request failed or did not complete within `FETCH_TIMEOUT`.
+	Http(http::Error),
+	/// The page responded with a status other than `200 OK`.
+	Status(u16),
+	/// The page is larger than `MAX_PAGE_SIZE`.
+	TooLarge,
+}
+
+/// Fetch the body of `url`.
+pub fn fetch(url: &[u8]) -> Result<Vec<u8>, FetchError> {
+	let url = core::str::from_utf8(url).map_err(|_| FetchError::InvalidUrl)?;
+	let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(FETCH_TIMEOUT));
+	let pending = http::Request::get(url)
+		.deadline(deadline)
+		.send()
+		.map_err(|_| FetchError::Http(http::Error::IoError))?;
+	let response = pending
+		.try_wait(deadline)
+		.map_err(|_| FetchError::Http(http::Error::DeadlineReached))?
+		.map_err(FetchError::Http)?;
+	if response.code != 200 {
+		return Err(FetchError::Status(response.code))
+	}
+	let body: Vec<u8> = response.body().take(MAX_PAGE_SIZE + 1).collect();
+	if body.len() > MAX_PAGE_SIZE {
+		return Err(FetchError::TooLarge)
+	}
+	Ok(body)
+}
+
+/// Fetch the robots.txt of the host of `url` and tell whether it lets crawlers fetch `url`. A
+/// host without a robots.txt, which answers with a client error, allows every page.
+pub fn robots_allow(url: &Url) -> Result<bool, FetchError> {
+	let robots_url = Url { path: b"/robots.txt".to_vec(), query: None, ..url.clone() };
+	let robots = match fetch(&robots_url.to_bytes()) {
+		Ok(robots) => robots,
+		Err(FetchError::Status(code)) if (400..500).contains(&code) => return Ok(true),
+		Err(err) => return Err(err),
+	};
+	let mut path = if url.path.is_empty() { b"/".to_vec() } else { url.path.clone() };
+	if let Some(query) = &url.query {
+		path.push(b'?');
+		path.extend_from_slice(query);
+	}
+	Ok(robots_allows(&robots, &path))
+}
+
+/// Whether the rules a robots.txt has for every crawler, under `User-agent: *`, allow fetching
+/// `path`. The longest matching `Allow` or `Disallow` rule decides, `Allow` on a tie, and a path
+/// no rule matches is allowed. Rules may contain `*` for any characters and end with `$` to only
+/// match whole paths.
+pub fn robots_allows(robots: &[u8], path: &[u8]) -> bool {
+	// Length of the longest matching rule so far and whether it allows the path.
+	let mut decision: Option<(usize, bool)> = None;
+	// Whether the rules being read apply to every crawler.
+	let mut applies = false;
+	// Whether the previous line named a user agent, so that the next one joins its group.
+	let mut naming_agents = false;
+	for line in robots.split(|&byte| byte == b'\n') {
+		let line = line.split(|&byte| byte == b'#').next().unwrap_or_default();
+		let Some(colon) = line.iter().position(|&byte| byte == b':') else { continue };
+		let field = trim(&line[..colon]).to_ascii_lowercase();
+		let value = trim(&line[colon + 1..]);
+		if field.as_slice() == b"user-agent" {
+			if !naming_agents {
+				applies = false;
+			}
+			applies |= value == b"*";
+			naming_agents = true;
+			continue
+		}
+		naming_agents = false;
+		let allow = match field.as_slice() {
+			b"allow" => true,
+			b"disallow" => false,
+			_ => continue,
+		};
+		// An empty `Disallow` allows everything, like no rule at all.
+		if !applies || value.is_empty() || !matches_robots_rule(value, path) {
+			continue
+		}
+		let longer = decision.is_none_or(|(longest, allowed)| {
+			value.len() > longest || (value.len() == longest && allow && !allowed)
+		});
+		if longer {
+			decision = Some((value.len(), allow));
+		}
+	}
+	decision.is_none_or(|(_, allow)| allow)
+}
+
+/// Whether the robots.txt `rule` matches `path`, see [`robots_allows`].
+fn matches_robots_rule(rule: &[u8], path: &[u8]) -> bool {
+	let (rule, anchored) = match rule.strip_suffix(b"$") {
+		Some(rule) => (rule, true),
+		None => (rule, false),
+	};
+	let mut parts = rule.split(|&byte| byte == b'*');
+	let Some(mut rest) = path.strip_prefix(parts.next().unwrap_or_default()) else { return false };
+	let parts: Vec<&[u8]> = parts.collect();
+	for (index, part) in parts.iter().enumerate() {
+		if anchored && index + 1 == parts.len() {
+			return rest.ends_with(part)
+		}
+		if part.is_empty() {
+			continue
+		}
+		let Some(position) = find(rest, 0, part) else { return false };
+		rest = &rest[position + part.len()..];
+	}
+	!anchored || rest.is_empty()
+}
+
+/// `bytes` without leading and trailing ASCII whitespace.
+fn trim(bytes: &[u8]) -> &[u8] {
+	let start = bytes.iter().position(|byte| !byte.is_ascii_whitespace()).unwrap_or(bytes.len());
+	let end = bytes.iter().rposition(|byte| !byte.is_ascii_whitespace()).map_or(start, |end| end + 1);
+	&bytes[start..end]
+}
+
+/// SHA-256 of the canonical text of a page, the content hash miners submit.
+pub fn content_hash(text: &[u8]) -> [u8; 32] {
+	sp_io::hashing::sha2_256(text)
+}
+
+/// 64-bit SimHash of the whitespace separated words of `text`. Pages that differ in a few words
+/// have fingerprints that differ in a few bits.
+pub fn fingerprint(text: &[u8]) -> u64 {
+	let mut weights = [0i64; 64];
+	for word in text.split(|byte| byte.is_ascii_whitespace()).filter(|word| !word.is_empty()) {
+		let hash = u64::from_le_bytes(sp_io::hashing::twox_64(word));
+		for (bit, weight) in weights.iter_mut().enumerate() {
+			if (hash >> bit) & 1 == 1 {
+				*weight += 1;
+			} else {
+				*weight -= 1;
+			}
+		}
+	}
+	weights
+		.iter()
+		.enumerate()
+		.filter(|(_, weight)| **weight > 0)
+		.fold(0, |fingerprint, (bit, _)| fingerprint | (1 << bit))
+}
+
+/// Elements that never have content or a closing tag.
+const VOID_ELEMENTS: &[&[u8]] = &[
+	b"area", b"base", b"br", b"col", b"embed", b"hr", b"img", b"input", b"link", b"meta",
+	b"source", b"track", b"wbr",
+];
+
+/// Elements whose content is not HTML and never part of the text.
+const RAW_TEXT_ELEMENTS: &[&[u8]] = &[b"script", b"style"];
+
+/// A start or end tag.
+struct Tag {
+	/// Lowercase element name.
+	name: Vec<u8>,
+	closing: bool,
+	self_closing: bool,
+	/// Lowercase value of the `class` attribute.
+	class: Option<Vec<u8>>,
+}
+
+/// Reduce an HTML page to the text a reader sees, to remove noise from dynamic content:
+///
+/// - `<script>` and `<style>` elements, comments and the doctype are dropped,
+/// - so are elements with a class containing `ad`, such as ad banners,
+/// - only the text of the first `<main>` element is kept if the page has one,
+/// - character references are decoded,
+/// - every text between two tags is trimmed, and non-empty ones are joined by single spaces.
+///
+/// This matches `canonicalize_html` of the reference tooling.
+pub fn canonicalize(html: &[u8]) -> Vec<u8> {
+	let mut all = Vec::new();
+	let mut main: Option<Vec<u8>> = None;
+	// Nesting depth of `<main>` elements while inside the first one.
+	let mut main_depth = 0usize;
+	// Name and nesting depth of the element being dropped, if any.
+	let mut dropped: Option<(Vec<u8>, usize)> = None;
+	let mut text = Vec::new();
+	let mut pos = 0;
+
+	while pos < html.len() {
+		if html[pos] != b'<' || !starts_markup(&html[pos..]) {
+			text.push(html[pos]);
+			pos += 1;
+			continue
+		}
+		if dropped.is_none() {
+			push_text(&mut all, &text);
+			if main_depth > 0 {
+				push_text(main.get_or_insert_with(Vec::new), &text);
+			}
+		}
+		text.clear();
+
+		if html[pos..].starts_with(b"<!--") {
+			pos = find(html, pos + 4, b"-->").map_or(html.len(), |end| end + 3);
+			continue
+		}
+		if matches!(html.get(pos + 1), Some(b'!') | Some(b'?')) {
+			pos = find(html, pos, b">").map_or(html.len(), |end| end + 1);
+			continue
+		}
+		let (tag, end) = parse_tag(html, pos);
+		pos = end;
+
+		if !tag.closing && RAW_TEXT_ELEMENTS.contains(&tag.name.as_slice()) {
+			let mut close = b"</".to_vec();
+			close.extend_from_slice(&tag.name);
+			pos = find_ignore_case(html, pos, &close)
+				.and_then(|start| find(html, start, b">"))
+				.map_or(html.len(), |end| end + 1);
+			continue
+		}
+		let void = tag.self_closing || VOID_ELEMENTS.contains(&tag.name.as_slice());
+		if let Some((name, depth)) = dropped.as_mut() {
+			if *name == tag.name && !void {
+				if tag.closing {
+					*depth -= 1;
+				} else {
+					*depth += 1;
+				}
+				if *depth == 0 {
+					dropped = None;
+				}
+			}
+			continue
+		}
+		if !tag.closing && !void && tag.class.as_ref().is_some_and(|class| find(class, 0, b"ad").is_some()) {
+			dropped = Some((tag.name, 1));
+			continue
+		}
+		if tag.name.as_slice() == b"main" && !void {
+			if tag.closing && main_depth > 0 {
+				main_depth -= 1;
+			} else if !tag.closing && (main_depth > 0 || main.is_none()) {
+				main_depth += 1;
+				main.get_or_insert_with(Vec::new);
+			}
+		}
+	}
+	if dropped.is_none() {
+		push_text(&mut all, &text);
+		if main_depth > 0 {
+			push_text(main.get_or_insert_with(Vec::new), &text);
+		}
+	}
+	main.unwrap_or(all)
+}
+
+/// Whether `html`, starting with `<`, starts a tag, comment or declaration rather than text.
+fn starts_markup(html: &[u8]) -> bool {
+	match html.get(1) {
+		Some(b'/') => html.get(2).is_some_and(|byte| byte.is_ascii_alphabetic()),
+		Some(b'!') | Some(b'?') => true,
+		Some(byte) => byte.is_ascii_alphabetic(),
+		None => false,
+	}
+}
+
+/// Parse the tag starting at `html[pos]`, returning it and the position after it.
+fn parse_tag(html: &[u8], mut pos: usize) -> (Tag, usize) {
+	pos += 1;
+	let closing = html.get(pos) == Some(&b'/');
+	if closing {
+		pos += 1;
+	}
+	let name_start = pos;
+	while pos < html.len() && !html[pos].is_ascii_whitespace() && !matches!(html[pos], b'>' | b'/') {
+		pos += 1;
+	}
+	let name = html[name_start..pos].to_ascii_lowercase();
+
+	let mut class = None;
+	let mut self_closing = false;
+	while pos < html.len() {
+		match html[pos] {
+			b'>' => return (Tag { name, closing, self_closing, class }, pos + 1),
+			b'/' => {
+				self_closing = true;
+				pos += 1;
+			},
+			byte if byte.is_ascii_whitespace() => pos += 1,
+			_ => {
+				self_closing = false;
+				let attr_start = pos;
+				while pos < html.len() &&
+					!html[pos].is_ascii_whitespace() &&
+					!matches!(html[pos], b'=' | b'>' | b'/')
+				{
+					pos += 1;
+				}
+				let attr = html[attr_start..pos].to_ascii_lowercase();
+				while pos < html.len() && html[pos].is_ascii_whitespace() {
+					pos += 1;
+				}
+				if html.get(pos) != Some(&b'=') {
+					continue
+				}
+				pos += 1;
+				while pos < html.len() && html[pos].is_ascii_whitespace() {
+					pos += 1;
+				}
+				let value = match html.get(pos) {
+					Some(&quote) if quote == b'"' || quote == b'\'' => {
+						let end = find(html, pos + 1, &[quote]).unwrap_or(html.len());
+						let value = &html[pos + 1..end];
+						pos = (end + 1).min(html.len());
+						value
+					},
+					_ => {
+						let start = pos;
+						while pos < html.len() && !html[pos].is_ascii_whitespace() && html[pos] != b'>' {
+							pos += 1;
+						}
+						&html[start..pos]
+					},
+				};
+				if attr.as_slice() == b"class" {
+					class = Some(value.to_ascii_lowercase());
+				}
+			},
+		}
+	}
+	(Tag { name, closing, self_closing, class }, pos)
+}
+
+/// Decode and trim `raw` text and append it to `out`, separated by a space, unless it is empty.
+fn push_text(out: &mut Vec<u8>, raw: &[u8]) {
+	let text = decode_references(raw);
+	let text = text.trim();
+	if text.is_empty() {
+		return
+	}
+	if !out.is_empty() {
+		out.push(b' ');
+	}
+	out.extend_from_slice(text.as_bytes());
+}
+
+/// Decode the numeric and common named character references in `raw`.
+fn decode_references(raw: &[u8]) -> String {
+	let raw = String::from_utf8_lossy(raw);
+	if !raw.contains('&') {
+		return raw.into_owned()
+	}
+	let mut decoded = String::with_capacity(raw.len());
+	let mut rest: &str = &raw;
+	while let Some(start) = rest.find('&') {
+		decoded.push_str(&rest[..start]);
+		rest = &rest[start..];
+		let reference = rest.find(';').filter(|end| *end <= 10).and_then(|end| {
+			let name = &rest[1..end];
+			let decoded = match name {
+				"amp" => Some('&'),
+				"lt" => Some('<'),
+				"gt" => Some('>'),
+				"quot" => Some('"'),
+				"apos" => Some('\''),
+				"nbsp" => Some('\u{a0}'),
+				_ => name
+					.strip_prefix("#x")
+					.or_else(|| name.strip_prefix("#X"))
+					.map(|hex| u32::from_str_radix(hex, 16))
+					.or_else(|| name.strip_prefix('#').map(|decimal| decimal.parse::<u32>()))
+					.and_then(|code| code.ok())
+					.and_then(char::from_u32),
+			};
+			decoded.map(|decoded| (decoded, end + 1))
+		});
+		match reference {
+			Some((character, len)) => {
+				decoded.push(character);
+				rest = &rest[len..];
+			},
+			None => {
+				decoded.push('&');
+				rest = &rest[1..];
+			},
+		}
+	}
+	decoded.push_str(rest);
+	decoded
+}
+
+/// Position of the first `needle` in `haystack` at or after `from`.
+fn find(haystack: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+	haystack
+		.get(from..)?
+		.windows(needle.len())
+		.position(|window| window == needle)
+		.map(|position| from + position)
+}
+
+/// Position of the first `needle`, which is lowercase, in `haystack` at or after `from`,
+/// ignoring ASCII case.
+fn find_ignore_case(haystack: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+	haystack
+		.get(from..)?
+		.windows(needle.len())
+		.position(|window| window.eq_ignore_ascii_case(needle))
+		.map(|position| from + position)
+}
+
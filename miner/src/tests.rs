@@ -1,28 +1,20 @@
 #[cfg(test)]
 mod tests {
-    use super::*;
-    use crate::mock::{new_test_ext, RuntimeOrigin, RuntimeEvent, MaxUrlLength, System, Miner};
+    use crate::mock::{new_test_ext, RuntimeOrigin, RuntimeEvent, MaxUrlLength, System, Miner, Balances, RewardPool, Timestamp};
+    use crate::mock::{MISSED, OUTCOMES, RANDOMNESS, VALIDATORS};
     use crate::mock::Test;
     use crate::Whitelist;
     use crate::Pallet as MinerPallet;
-    use crate::Error;
-    use frame_support::{assert_noop, assert_ok, BoundedVec};
+    use crate::{Error, Priority, SubmissionStatus, WhitelistAction, WhitelistStatus};
+    use frame_support::{assert_noop, assert_ok, traits::{Currency, Hooks}, BoundedVec};
     use sp_core::H256;
-    use sp_runtime::AccountId32;
+    use sp_runtime::{AccountId32, Perbill};
 
 
 
     #[test]
     fn register_miner_works() {
         new_test_ext().execute_with(|| {
-
-            System::deposit_event(RuntimeEvent::Miner(crate::Event::MinerRegistered {
-                miner: AccountId32::new([1; 32]),
-                deposit: 100,
-            }));
-            
-            println!("System events: {:?}", System::events());
-
             let miner_id = AccountId32::new([1; 32]);
             let deposit = 100;
 
@@ -30,19 +22,10 @@ mod tests {
             assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner_id.clone()), deposit));
 
             // Check storage
-            let stored_deposit = Miner::miners(&miner_id);
-            println!("Stored Deposit: {:?}", stored_deposit);
-            assert_eq!(stored_deposit, Some(deposit));
+            assert_eq!(Miner::miners(&miner_id), Some(deposit));
 
             // Check emitted events
-            let events = System::events();
-            println!("Events: {:?}", events);
-
-            //Assert that the correct event was emitted
-            assert!(events.iter().any(|record| matches!(
-               &record.event,
-               RuntimeEvent::Miner(crate::Event::MinerRegistered { miner, deposit: dep }) if *miner == miner_id && *dep == deposit
-            )));
+            System::assert_last_event(RuntimeEvent::Miner(crate::Event::MinerRegistered { miner: miner_id, deposit }));
         });
     }
 
@@ -52,14 +35,11 @@ mod tests {
             let miner_id = AccountId32::new([1; 32]);
             let url = b"http://example.com".to_vec();
             let hash = H256::random();
+            assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner_id.clone()), 100));
             assert_noop!(
-                Miner::submit_hash(RuntimeOrigin::signed(miner_id), url, hash),
+                Miner::submit_hash(RuntimeOrigin::signed(miner_id), url, hash, b"bafkqaaa".to_vec(), 1_024, b"text/html".to_vec(), None),
                 Error::<Test>::NotWhitelisted
             );
-
-            // Check emitted events
-            let events = System::events();
-            println!("Events: {:?}", events);
         });
     }
 
@@ -71,14 +51,17 @@ mod tests {
             let hash = H256::random();
 
             // Insert into Whitelist
-            Whitelist::<Test>::insert(&url, ());
+            whitelist(b"http://example.com");
+            assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner_id.clone()), 100));
 
-            assert_ok!(Miner::submit_hash(RuntimeOrigin::signed(miner_id.clone()), url.clone().into(), hash));
-            assert_eq!(Miner::submissions(hash), Some((miner_id, url)));
+            assert_ok!(Miner::submit_hash(RuntimeOrigin::signed(miner_id.clone()), url.clone().into(), hash, b"bafkqaaa".to_vec(), 1_024, b"text/html".to_vec(), None));
 
-            // Check emitted events
-            let events = System::events();
-            println!("Events: {:?}", events);
+            // The submission waits for validator votes before it becomes canonical
+            let id = submission_id(&url, hash);
+            let pending = Miner::pending_submissions(id).unwrap();
+            assert_eq!((pending.record.miner, pending.record.url, pending.record.content_hash), (miner_id, url, hash));
+            assert_eq!(Miner::submission_status(id), Some(SubmissionStatus::Pending));
+            assert_eq!(Miner::submissions(id), None);
         });
     }
 
@@ -89,12 +72,7 @@ mod tests {
 
             assert_ok!(MinerPallet::<Test>::add_to_whitelist(RuntimeOrigin::root(), url.clone()));
 
-            let bounded_url: BoundedVec<u8, MaxUrlLength> = url.try_into().unwrap();
-            assert!(Whitelist::<Test>::contains_key(&bounded_url));
-
-            // Check emitted events
-            let events = System::events();
-            println!("Events: {:?}", events);
+            assert!(is_rule(&url));
         });
     }
 
@@ -102,19 +80,2090 @@ mod tests {
     fn remove_from_whitelist_works() {
         new_test_ext().execute_with(|| {
             let url = b"http://example.com".to_vec();
-            let bounded_url: BoundedVec<u8, MaxUrlLength> = url.clone().try_into().unwrap();
 
             // Insert into Whitelist
-            Whitelist::<Test>::insert(&bounded_url, ());
-            assert!(Whitelist::<Test>::contains_key(&bounded_url));
+            whitelist(&url);
+            assert!(is_rule(&url));
 
             // Remove from Whitelist
-            assert_ok!(MinerPallet::<Test>::remove_from_whitelist(RuntimeOrigin::root(), url));
-            assert!(!Whitelist::<Test>::contains_key(&bounded_url));
+            assert_ok!(MinerPallet::<Test>::remove_from_whitelist(RuntimeOrigin::root(), url.clone()));
+            assert!(!is_rule(&url));
+        });
+    }
 
-            // Check emitted events
-            let events = System::events();
-            println!("Events: {:?}", events);
+    fn whitelist(rule: &[u8]) {
+        let (host, path_prefix) = MinerPallet::<Test>::whitelist_keys(rule).unwrap();
+        Whitelist::<Test>::insert(host, path_prefix, MinerPallet::<Test>::default_whitelist_entry(None));
+    }
+
+    fn is_rule(rule: &[u8]) -> bool {
+        let (host, path_prefix) = MinerPallet::<Test>::whitelist_keys(rule).unwrap();
+        Whitelist::<Test>::contains_key(host, path_prefix)
+    }
+
+    fn entry(rule: &[u8]) -> crate::WhitelistEntryOf<Test> {
+        let (host, path_prefix) = MinerPallet::<Test>::whitelist_keys(rule).unwrap();
+        Whitelist::<Test>::get(host, path_prefix).unwrap()
+    }
+
+    /// Submit random content for `http://example.com`, returning the submission id.
+    fn submit_whitelisted(miner: &AccountId32) -> H256 {
+        let url = BoundedVec::<u8, MaxUrlLength>::try_from(b"http://example.com".to_vec()).unwrap();
+        let hash = H256::random();
+        whitelist(b"http://example.com");
+        if Miner::miners(miner).is_none() {
+            assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner.clone()), 100));
+        }
+        assert_ok!(Miner::submit_hash(RuntimeOrigin::signed(miner.clone()), url.clone().into(), hash, b"bafkqaaa".to_vec(), 1_024, b"text/html".to_vec(), None));
+        submission_id(&url, hash)
+    }
+
+    fn submission_id(url: &[u8], hash: H256) -> H256 {
+        MinerPallet::<Test>::submission_id(url, &hash)
+    }
+
+    /// The content hash a submission was made with.
+    fn content_hash(id: H256) -> H256 {
+        Miner::pending_submissions(id)
+            .map(|pending| pending.record.content_hash)
+            .or_else(|| Miner::submissions(id).map(|record| record.content_hash))
+            .unwrap_or_default()
+    }
+
+    fn add_validators(validators: &[AccountId32]) {
+        VALIDATORS.with(|registered| registered.borrow_mut().extend_from_slice(validators));
+    }
+
+    /// The content hash a validator found for the submission `id`, matching it or not.
+    fn content(id: H256, approve: bool) -> H256 {
+        if approve { content_hash(id) } else { H256::repeat_byte(0xee) }
+    }
+
+    fn commit_vote(validator: &AccountId32, id: H256, approve: bool) -> sp_runtime::DispatchResult {
+        let commitment = MinerPallet::<Test>::vote_commitment_of(&content(id, approve), &None, &[7; 32], validator);
+        Miner::commit_vote(RuntimeOrigin::signed(validator.clone()), id, commitment)
+    }
+
+    fn reveal_vote(validator: &AccountId32, id: H256, approve: bool) -> sp_runtime::DispatchResult {
+        Miner::reveal_vote(RuntimeOrigin::signed(validator.clone()), id, content(id, approve), None, [7; 32])
+    }
+
+    /// Have all of `validators` commit to the same vote, then reveal it.
+    fn vote(id: H256, validators: &[AccountId32], approve: bool) {
+        for validator in validators {
+            assert_ok!(commit_vote(validator, id, approve));
+        }
+        for validator in validators {
+            assert_ok!(reveal_vote(validator, id, approve));
+        }
+    }
+
+    fn outcomes() -> Vec<(AccountId32, bool)> {
+        let mut outcomes = OUTCOMES.with(|outcomes| outcomes.borrow().clone());
+        outcomes.sort();
+        outcomes
+    }
+
+    #[test]
+    fn vote_outcomes_are_reported_to_validators() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            let validators = [AccountId32::new([2; 32]), AccountId32::new([3; 32]), AccountId32::new([4; 32])];
+            add_validators(&validators);
+            let id = submit_whitelisted(&miner_id);
+
+            for (validator, approve) in validators.iter().zip([false, true, true]) {
+                assert_ok!(commit_vote(validator, id, approve));
+            }
+            assert_ok!(reveal_vote(&validators[0], id, false));
+            assert_ok!(reveal_vote(&validators[1], id, true));
+            assert!(outcomes().is_empty());
+
+            assert_ok!(reveal_vote(&validators[2], id, true));
+            assert_eq!(Miner::submission_status(id), Some(SubmissionStatus::Approved));
+            assert_eq!(
+                outcomes(),
+                vec![(validators[0].clone(), false), (validators[1].clone(), true), (validators[2].clone(), true)]
+            );
+
+            // Votes on a submission that expires have no outcome to agree with
+            OUTCOMES.with(|outcomes| outcomes.borrow_mut().clear());
+            let url = BoundedVec::<u8, MaxUrlLength>::try_from(b"http://example.com/other".to_vec()).unwrap();
+            let content = H256::random();
+            let expiring = submission_id(&url, content);
+            assert_ok!(Miner::submit_hash(RuntimeOrigin::signed(miner_id), url.into(), content, b"bafkqaaa".to_vec(), 1_024, b"text/html".to_vec(), None));
+            assert_ok!(commit_vote(&validators[0], expiring, true));
+            Miner::on_initialize(11);
+            assert_eq!(Miner::submission_status(expiring), Some(SubmissionStatus::Expired));
+            assert_eq!(Miner::vote_commitments(expiring, &validators[0]), None);
+            assert!(outcomes().is_empty());
+        });
+    }
+
+    #[test]
+    fn submission_is_approved_at_quorum() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            let validators = [AccountId32::new([2; 32]), AccountId32::new([3; 32])];
+            add_validators(&validators);
+            let id = submit_whitelisted(&miner_id);
+
+            for validator in &validators {
+                assert_ok!(commit_vote(validator, id, true));
+            }
+            assert_ok!(reveal_vote(&validators[0], id, true));
+            assert_eq!(Miner::pending_submissions(id).unwrap().ayes, 1);
+            assert_eq!(Miner::submissions(id), None);
+
+            assert_ok!(reveal_vote(&validators[1], id, true));
+            assert_eq!(Miner::submission_status(id), Some(SubmissionStatus::Approved));
+            assert!(Miner::pending_submissions(id).is_none());
+            assert_eq!(Miner::votes(id, &validators[0]), None);
+            assert_eq!(Miner::submissions(id).map(|record| record.miner), Some(miner_id.clone()));
+
+            // Half of the fee goes to the two validators, the rest to the reward pool
+            assert_eq!(Balances::free_balance(&miner_id), 890);
+            assert_eq!(Balances::free_balance(&validators[0]), 2);
+            assert_eq!(Balances::free_balance(&validators[1]), 2);
+            assert_eq!(Balances::free_balance(RewardPool::get()), 6);
+            assert_eq!(Balances::free_balance(MinerPallet::<Test>::account_id()), 0);
+
+            System::assert_last_event(RuntimeEvent::Miner(crate::Event::SubmissionApproved { id }));
+        });
+    }
+
+    #[test]
+    fn votes_are_tallied_once_every_member_revealed() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            let validators = [AccountId32::new([2; 32]), AccountId32::new([3; 32]), AccountId32::new([4; 32])];
+            add_validators(&validators);
+            let id = submit_whitelisted(&miner_id);
+            for (validator, approve) in validators.iter().zip([true, true, false]) {
+                assert_ok!(commit_vote(validator, id, approve));
+            }
+
+            // A quorum of revealed votes does not decide while members can still reveal
+            assert_ok!(reveal_vote(&validators[0], id, true));
+            assert_ok!(reveal_vote(&validators[1], id, true));
+            assert_eq!(Miner::submission_status(id), Some(SubmissionStatus::Pending));
+            assert_noop!(
+                Miner::reassign_committee(RuntimeOrigin::signed(miner_id.clone()), id),
+                Error::<Test>::VotesRevealed
+            );
+
+            // The last member is scored too
+            assert_ok!(reveal_vote(&validators[2], id, false));
+            assert_eq!(Miner::submission_status(id), Some(SubmissionStatus::Approved));
+            assert_eq!(
+                outcomes(),
+                vec![(validators[0].clone(), true), (validators[1].clone(), true), (validators[2].clone(), false)]
+            );
+        });
+    }
+
+    #[test]
+    fn revealed_votes_are_tallied_when_the_voting_period_ends() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            let validators = [AccountId32::new([2; 32]), AccountId32::new([3; 32]), AccountId32::new([4; 32])];
+            add_validators(&validators);
+            let id = submit_whitelisted(&miner_id);
+            let content = H256::random();
+            let tied = submission_id(b"http://example.com/tie", content);
+            assert_ok!(submit_url_with(&miner_id, b"http://example.com/tie", content));
+
+            for validator in &validators {
+                assert_ok!(commit_vote(validator, id, false));
+            }
+            assert_ok!(reveal_vote(&validators[0], id, false));
+            assert_ok!(reveal_vote(&validators[1], id, false));
+            for (validator, approve) in validators.iter().zip([true, false, true]) {
+                assert_ok!(commit_vote(validator, tied, approve));
+            }
+            assert_ok!(reveal_vote(&validators[0], tied, true));
+            assert_ok!(reveal_vote(&validators[1], tied, false));
+
+            // Both were submitted at block 1 and the voting period is 10 blocks
+            Miner::on_initialize(11);
+            assert_eq!(Miner::submission_status(id), Some(SubmissionStatus::Rejected));
+            assert_eq!(outcomes(), vec![(validators[0].clone(), true), (validators[1].clone(), true)]);
+            // A tie decides nothing
+            assert_eq!(Miner::submission_status(tied), Some(SubmissionStatus::Expired));
+            // The member that never revealed missed both votes
+            assert_eq!(MISSED.with(|missed| missed.borrow().clone()), vec![validators[2].clone(); 2]);
+        });
+    }
+
+    #[test]
+    fn submission_is_rejected_at_quorum() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            let validators = [AccountId32::new([2; 32]), AccountId32::new([3; 32])];
+            add_validators(&validators);
+            let id = submit_whitelisted(&miner_id);
+
+            vote(id, &validators, false);
+
+            assert_eq!(Miner::submission_status(id), Some(SubmissionStatus::Rejected));
+            assert_eq!(Miner::submissions(id), None);
+
+            // The fee is forfeited and 20% of the deposit slashed
+            assert_eq!(Balances::free_balance(&miner_id), 890);
+            assert_eq!(Balances::free_balance(RewardPool::get()), 26);
+            assert_eq!(Balances::reserved_balance(&miner_id), 80);
+            assert_eq!(Miner::miners(&miner_id), Some(80));
+            System::assert_last_event(RuntimeEvent::Miner(crate::Event::SubmissionRejected { id }));
+        });
+    }
+
+    #[test]
+    fn commit_vote_checks_voter() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            let validator = AccountId32::new([2; 32]);
+            add_validators(&[validator.clone(), miner_id.clone()]);
+            let id = submit_whitelisted(&miner_id);
+
+            assert_noop!(
+                commit_vote(&AccountId32::new([3; 32]), id, true),
+                Error::<Test>::NotValidator
+            );
+            assert_noop!(
+                commit_vote(&miner_id, id, true),
+                Error::<Test>::CannotVoteOwnSubmission
+            );
+
+            // Validators registered after the submission are not on its committee
+            let latecomer = AccountId32::new([3; 32]);
+            add_validators(core::slice::from_ref(&latecomer));
+            assert_noop!(
+                commit_vote(&latecomer, id, true),
+                Error::<Test>::NotAssigned
+            );
+
+            assert_ok!(commit_vote(&validator, id, true));
+            assert_noop!(commit_vote(&validator, id, false), Error::<Test>::AlreadyVoted);
+            assert_ok!(reveal_vote(&validator, id, true));
+            assert_noop!(commit_vote(&validator, id, false), Error::<Test>::AlreadyVoted);
+        });
+    }
+
+    #[test]
+    fn votes_are_revealed_once_the_committee_committed() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            let validators = [AccountId32::new([2; 32]), AccountId32::new([3; 32])];
+            add_validators(&validators);
+            let id = submit_whitelisted(&miner_id);
+
+            assert_noop!(reveal_vote(&validators[0], id, true), Error::<Test>::VoteNotCommitted);
+            assert_ok!(commit_vote(&validators[0], id, true));
+            System::assert_last_event(RuntimeEvent::Miner(crate::Event::VoteCommitted {
+                validator: validators[0].clone(),
+                id,
+            }));
+            assert_noop!(reveal_vote(&validators[0], id, true), Error::<Test>::VoteRevealNotOpen);
+
+            assert_ok!(commit_vote(&validators[1], id, false));
+            assert_noop!(reveal_vote(&validators[0], id, false), Error::<Test>::InvalidVoteReveal);
+            assert_noop!(
+                Miner::reveal_vote(RuntimeOrigin::signed(validators[0].clone()), id, id, None, [8; 32]),
+                Error::<Test>::InvalidVoteReveal
+            );
+            assert_ok!(reveal_vote(&validators[0], id, true));
+            assert_eq!(Miner::votes(id, &validators[0]), Some(true));
+            assert_eq!(Miner::vote_commitments(id, &validators[0]), None);
+            System::assert_last_event(RuntimeEvent::Miner(crate::Event::VoteCast {
+                validator: validators[0].clone(),
+                id,
+                approve: true,
+            }));
+
+            // Finding different content counts as a vote against the submission
+            assert_ok!(reveal_vote(&validators[1], id, false));
+            assert_eq!(Miner::votes(id, &validators[1]), Some(false));
+            let pending = Miner::pending_submissions(id).unwrap();
+            assert_eq!((pending.ayes, pending.nays), (1, 1));
+        });
+    }
+
+    #[test]
+    fn votes_are_revealed_after_the_assignment_timeout() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            let validators = [AccountId32::new([2; 32]), AccountId32::new([3; 32]), AccountId32::new([4; 32])];
+            add_validators(&validators);
+            let id = submit_whitelisted(&miner_id);
+            for validator in &validators[..2] {
+                assert_ok!(commit_vote(validator, id, true));
+            }
+
+            // The committee was assigned at block 1 and has 4 blocks to commit
+            System::set_block_number(4);
+            assert_noop!(reveal_vote(&validators[0], id, true), Error::<Test>::VoteRevealNotOpen);
+
+            System::set_block_number(5);
+            assert_noop!(commit_vote(&validators[2], id, true), Error::<Test>::VoteCommitClosed);
+            assert_ok!(reveal_vote(&validators[0], id, true));
+            assert_ok!(reveal_vote(&validators[1], id, true));
+            assert_eq!(Miner::submission_status(id), Some(SubmissionStatus::Approved));
+        });
+    }
+
+    #[test]
+    fn duplicate_submission_fails() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            let id = submit_whitelisted(&miner_id);
+
+            assert_noop!(
+                Miner::submit_hash(RuntimeOrigin::signed(miner_id), b"http://example.com".to_vec(), content_hash(id), b"bafkqaaa".to_vec(), 1_024, b"text/html".to_vec(), None),
+                Error::<Test>::DuplicateSubmission
+            );
+        });
+    }
+
+    #[test]
+    fn submission_expires_after_voting_period() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            let validator = AccountId32::new([2; 32]);
+            let silent = AccountId32::new([3; 32]);
+            add_validators(&[validator.clone(), silent.clone()]);
+            let id = submit_whitelisted(&miner_id);
+            assert_ok!(commit_vote(&validator, id, true));
+            assert_ok!(commit_vote(&silent, id, true));
+            assert_ok!(reveal_vote(&validator, id, true));
+
+            // Voting period is 10 blocks and the submission was made at block 1
+            Miner::on_initialize(10);
+            assert_eq!(Miner::submission_status(id), Some(SubmissionStatus::Pending));
+
+            Miner::on_initialize(11);
+            assert_eq!(Miner::submission_status(id), Some(SubmissionStatus::Expired));
+            assert!(Miner::pending_submissions(id).is_none());
+            assert_eq!(Miner::votes(id, &validator), None);
+            System::assert_last_event(RuntimeEvent::Miner(crate::Event::SubmissionExpired { id }));
+            // Only the member that never revealed missed its vote
+            assert_eq!(MISSED.with(|missed| missed.borrow().clone()), vec![silent]);
+
+            // Nobody validated the submission, so the miner gets the fee back
+            assert_eq!(Balances::free_balance(&miner_id), 900);
+            System::assert_has_event(RuntimeEvent::Miner(crate::Event::SubmissionFeeRefunded {
+                miner: miner_id.clone(),
+                id,
+                fee: 10,
+            }));
+
+            assert_noop!(commit_vote(&validator, id, true), Error::<Test>::SubmissionNotPending);
+        });
+    }
+
+    #[test]
+    fn submit_hash_holds_fee() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            let id = submit_whitelisted(&miner_id);
+
+            assert_eq!(Balances::free_balance(&miner_id), 890);
+            assert_eq!(Balances::free_balance(MinerPallet::<Test>::account_id()), 10);
+            assert_eq!(Miner::pending_submissions(id).unwrap().fee, 10);
+            System::assert_has_event(RuntimeEvent::Miner(crate::Event::SubmissionFeePaid {
+                miner: miner_id,
+                id,
+                fee: 10,
+            }));
+        });
+    }
+
+    #[test]
+    fn submit_hash_fails_with_insufficient_balance() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            let url = BoundedVec::<u8, MaxUrlLength>::try_from(b"http://example.com".to_vec()).unwrap();
+            whitelist(b"http://example.com");
+            // Leave less than the fee free
+            assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner_id.clone()), 995));
+
+            assert_noop!(
+                Miner::submit_hash(RuntimeOrigin::signed(miner_id), url.into(), H256::random(), b"bafkqaaa".to_vec(), 1_024, b"text/html".to_vec(), None),
+                Error::<Test>::InsufficientFunds
+            );
+        });
+    }
+
+    #[test]
+    fn register_miner_requires_min_deposit() {
+        new_test_ext().execute_with(|| {
+            assert_noop!(
+                Miner::register_miner(RuntimeOrigin::signed(AccountId32::new([1; 32])), 49),
+                Error::<Test>::DepositTooLow
+            );
+        });
+    }
+
+    #[test]
+    fn submit_hash_requires_active_miner() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            let url = BoundedVec::<u8, MaxUrlLength>::try_from(b"http://example.com".to_vec()).unwrap();
+            whitelist(b"http://example.com");
+
+            assert_noop!(
+                Miner::submit_hash(RuntimeOrigin::signed(miner_id.clone()), url.clone().into(), H256::random(), b"bafkqaaa".to_vec(), 1_024, b"text/html".to_vec(), None),
+                Error::<Test>::MinerNotRegistered
+            );
+
+            assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner_id.clone()), 100));
+            assert_ok!(Miner::chill(RuntimeOrigin::signed(miner_id.clone())));
+            assert_noop!(
+                Miner::submit_hash(RuntimeOrigin::signed(miner_id), url.into(), H256::random(), b"bafkqaaa".to_vec(), 1_024, b"text/html".to_vec(), None),
+                Error::<Test>::MinerChilled
+            );
+        });
+    }
+
+    #[test]
+    fn bond_extra_works() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner_id.clone()), 100));
+            assert_ok!(Miner::bond_extra(RuntimeOrigin::signed(miner_id.clone()), 50));
+
+            assert_eq!(Miner::miners(&miner_id), Some(150));
+            assert_eq!(Balances::reserved_balance(&miner_id), 150);
+        });
+    }
+
+    #[test]
+    fn unbond_keeps_min_deposit() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner_id.clone()), 100));
+
+            assert_noop!(
+                Miner::unbond(RuntimeOrigin::signed(miner_id.clone()), 101),
+                Error::<Test>::InsufficientBond
+            );
+            assert_noop!(
+                Miner::unbond(RuntimeOrigin::signed(miner_id.clone()), 60),
+                Error::<Test>::DepositTooLow
+            );
+
+            assert_ok!(Miner::unbond(RuntimeOrigin::signed(miner_id.clone()), 50));
+            assert_eq!(Miner::miners(&miner_id), Some(50));
+            assert!(Miner::chilled(&miner_id).is_none());
+        });
+    }
+
+    #[test]
+    fn unbond_and_withdraw_deregisters_miner() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner_id.clone()), 100));
+            assert_ok!(Miner::unbond(RuntimeOrigin::signed(miner_id.clone()), 100));
+
+            // Unbonding everything chills the miner but keeps the deposit reserved
+            assert!(Miner::chilled(&miner_id).is_some());
+            assert_eq!(Balances::reserved_balance(&miner_id), 100);
+            assert_noop!(
+                Miner::withdraw_unbonded(RuntimeOrigin::signed(miner_id.clone())),
+                Error::<Test>::NothingToWithdraw
+            );
+
+            System::set_block_number(6);
+            assert_ok!(Miner::withdraw_unbonded(RuntimeOrigin::signed(miner_id.clone())));
+            assert_eq!(Balances::reserved_balance(&miner_id), 0);
+            assert_eq!(Miner::miners(&miner_id), None);
+            assert!(Miner::chilled(&miner_id).is_none());
+            System::assert_last_event(RuntimeEvent::Miner(crate::Event::MinerDeregistered {
+                miner: miner_id,
+            }));
+        });
+    }
+
+    #[test]
+    fn withdraw_waits_for_pending_submissions() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            let id = submit_whitelisted(&miner_id);
+            assert_eq!(Miner::pending_submission_count(&miner_id), 1);
+            assert_ok!(Miner::unbond(RuntimeOrigin::signed(miner_id.clone()), 100));
+
+            System::set_block_number(6);
+            assert_noop!(
+                Miner::withdraw_unbonded(RuntimeOrigin::signed(miner_id.clone())),
+                Error::<Test>::PendingSubmissionsOutstanding
+            );
+
+            // Once the submission expires the deposit can be released
+            Miner::on_initialize(11);
+            assert_eq!(Miner::submission_status(id), Some(SubmissionStatus::Expired));
+            assert_eq!(Miner::pending_submission_count(&miner_id), 0);
+            assert_ok!(Miner::withdraw_unbonded(RuntimeOrigin::signed(miner_id.clone())));
+            assert_eq!(Miner::miners(&miner_id), None);
+        });
+    }
+
+    #[test]
+    fn withdraw_waits_for_the_challenge_period() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            let validators = [AccountId32::new([2; 32]), AccountId32::new([3; 32])];
+            add_validators(&validators);
+            let id = submit_whitelisted(&miner_id);
+            vote(id, &validators, true);
+            assert_eq!(Miner::challengeable_until(&miner_id), Some(21));
+            assert_ok!(Miner::unbond(RuntimeOrigin::signed(miner_id.clone()), 100));
+
+            // The record could still be challenged and the miner slashed
+            System::set_block_number(21);
+            assert_noop!(
+                Miner::withdraw_unbonded(RuntimeOrigin::signed(miner_id.clone())),
+                Error::<Test>::RecordsChallengeable
+            );
+
+            System::set_block_number(22);
+            assert_ok!(Miner::withdraw_unbonded(RuntimeOrigin::signed(miner_id.clone())));
+            assert_eq!(Miner::miners(&miner_id), None);
+            assert_eq!(Miner::challengeable_until(&miner_id), None);
+        });
+    }
+
+    #[test]
+    fn slash_takes_from_unbonding_chunks() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner_id.clone()), 200));
+            assert_ok!(Miner::unbond(RuntimeOrigin::signed(miner_id.clone()), 150));
+
+            // Slashing 20% of the 200 reserved leaves 10 bonded, below the minimum, so the
+            // miner is kicked and the rest starts unbonding.
+            MinerPallet::<Test>::slash_miner(&miner_id, H256::zero(), None, &[]);
+            assert_eq!(Balances::reserved_balance(&miner_id), 160);
+            assert_eq!(Miner::miners(&miner_id), Some(0));
+            let chunks = Miner::unbonding(&miner_id);
+            assert_eq!(chunks.iter().map(|chunk| chunk.value).sum::<u128>(), 160);
+            assert!(Miner::chilled(&miner_id).is_some());
+            System::assert_has_event(RuntimeEvent::Miner(crate::Event::MinerSlashed {
+                miner: miner_id.clone(),
+                id: H256::zero(),
+                amount: 40,
+            }));
+            System::assert_last_event(RuntimeEvent::Miner(crate::Event::MinerKicked { miner: miner_id }));
+        });
+    }
+
+    #[test]
+    fn kicked_miner_can_withdraw_remaining_deposit() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner_id.clone()), 60));
+
+            MinerPallet::<Test>::slash_miner(&miner_id, H256::zero(), None, &[]);
+            assert_eq!(Miner::miners(&miner_id), Some(0));
+            assert_eq!(Balances::reserved_balance(&miner_id), 48);
+
+            System::set_block_number(6);
+            assert_ok!(Miner::withdraw_unbonded(RuntimeOrigin::signed(miner_id.clone())));
+            assert_eq!(Balances::reserved_balance(&miner_id), 0);
+            assert_eq!(Balances::free_balance(&miner_id), 988);
+            assert_eq!(Miner::miners(&miner_id), None);
+        });
+    }
+
+    #[test]
+    fn report_mismatch_slashes_and_rejects_record() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            let validators = [AccountId32::new([2; 32]), AccountId32::new([3; 32])];
+            add_validators(&validators);
+            let id = submit_whitelisted(&miner_id);
+            vote(id, &validators, true);
+
+            assert_noop!(
+                Miner::report_mismatch(RuntimeOrigin::signed(miner_id.clone()), id),
+                sp_runtime::DispatchError::BadOrigin
+            );
+            assert_ok!(Miner::report_mismatch(RuntimeOrigin::root(), id));
+
+            assert_eq!(Miner::submissions(id), None);
+            assert_eq!(Miner::submission_status(id), Some(SubmissionStatus::Rejected));
+            assert_eq!(Balances::reserved_balance(&miner_id), 80);
+            assert_noop!(
+                Miner::report_mismatch(RuntimeOrigin::root(), id),
+                Error::<Test>::SubmissionNotFound
+            );
+        });
+    }
+
+    fn example_url() -> BoundedVec<u8, MaxUrlLength> {
+        BoundedVec::try_from(b"http://example.com".to_vec()).unwrap()
+    }
+
+    #[test]
+    fn history_tracks_every_snapshot_of_a_url() {
+        new_test_ext().execute_with(|| {
+            let miners = [AccountId32::new([1; 32]), AccountId32::new([5; 32])];
+            let validators = [AccountId32::new([2; 32]), AccountId32::new([3; 32])];
+            add_validators(&validators);
+            let _ = Balances::deposit_creating(&miners[1], 1_000);
+
+            let first = submit_whitelisted(&miners[0]);
+            vote(first, &validators, true);
+            System::set_block_number(5);
+            let second = submit_whitelisted(&miners[1]);
+            let (first, second) = (content_hash(first), content_hash(second));
+
+            let history = Miner::history(example_url());
+            assert_eq!(history.len(), 2);
+            assert_eq!(
+                (history[0].block_number, history[0].content_hash, &history[0].miner, history[0].status),
+                (1, first, &miners[0], SubmissionStatus::Approved)
+            );
+            assert_eq!(
+                (history[1].block_number, history[1].content_hash, &history[1].miner, history[1].status),
+                (5, second, &miners[1], SubmissionStatus::Pending)
+            );
+
+            // Point-in-time lookups only return approved snapshots
+            assert_eq!(MinerPallet::<Test>::snapshot_at(&example_url(), 0), None);
+            assert_eq!(MinerPallet::<Test>::snapshot_at(&example_url(), 10).map(|s| s.content_hash), Some(first));
+        });
+    }
+
+    #[test]
+    fn history_drops_oldest_snapshot() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            let hashes: Vec<H256> = (0..4).map(|_| content_hash(submit_whitelisted(&miner_id))).collect();
+
+            let history = Miner::history(example_url());
+            assert_eq!(
+                history.iter().map(|snapshot| snapshot.content_hash).collect::<Vec<_>>(),
+                hashes[1..].to_vec()
+            );
+        });
+    }
+
+    #[test]
+    fn expired_hash_can_be_resubmitted() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            let id = submit_whitelisted(&miner_id);
+            let hash = content_hash(id);
+            Miner::on_initialize(11);
+            assert_eq!(Miner::submission_status(id), Some(SubmissionStatus::Expired));
+
+            System::set_block_number(11);
+            assert_ok!(Miner::submit_hash(RuntimeOrigin::signed(miner_id), example_url().into(), hash, b"bafkqaaa".to_vec(), 1_024, b"text/html".to_vec(), None));
+            assert_eq!(Miner::submission_status(id), Some(SubmissionStatus::Pending));
+
+            let statuses: Vec<_> = Miner::history(example_url()).iter().map(|s| s.status).collect();
+            assert_eq!(statuses, vec![SubmissionStatus::Expired, SubmissionStatus::Pending]);
+        });
+    }
+
+    #[test]
+    fn approved_submission_stores_archive_record() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            let validators = [AccountId32::new([2; 32]), AccountId32::new([3; 32])];
+            add_validators(&validators);
+            Timestamp::set_timestamp(1_700_000_000_000);
+            let id = submit_whitelisted(&miner_id);
+            let hash = content_hash(id);
+            vote(id, &validators, true);
+
+            let record = Miner::submissions(id).unwrap();
+            assert_eq!(record.miner, miner_id);
+            assert_eq!(record.url, example_url());
+            assert_eq!(record.timestamp, 1_700_000_000_000);
+            assert_eq!(record.content_hash, hash);
+            assert_eq!(record.cid.to_vec(), b"bafkqaaa".to_vec());
+            assert_eq!(record.content_length, 1_024);
+            assert_eq!(record.mime_type.to_vec(), b"text/html".to_vec());
+            assert_eq!(record.canonicalization_version, crate::CANONICALIZATION_VERSION);
+        });
+    }
+
+    #[test]
+    fn submit_hash_fails_for_oversized_metadata() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            whitelist(b"http://example.com");
+            assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner_id.clone()), 100));
+
+            assert_noop!(
+                Miner::submit_hash(RuntimeOrigin::signed(miner_id.clone()), example_url().into(), H256::random(), vec![b'a'; 65], 1_024, b"text/html".to_vec(), None),
+                Error::<Test>::CidTooLong
+            );
+            assert_noop!(
+                Miner::submit_hash(RuntimeOrigin::signed(miner_id), example_url().into(), H256::random(), b"bafkqaaa".to_vec(), 1_024, vec![b'a'; 33], None),
+                Error::<Test>::MimeTypeTooLong
+            );
+        });
+    }
+
+    #[test]
+    fn migration_to_v2_builds_records_and_rules() {
+        use crate::migrations::v2::{MigrateToV2, OldSubmission, Submissions as OldSubmissions, Whitelist as OldWhitelist};
+        use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+
+        new_test_ext().execute_with(|| {
+            StorageVersion::new(1).put::<Miner>();
+            let miner_id = AccountId32::new([1; 32]);
+            let hash = H256::repeat_byte(1);
+            let old_submission: OldSubmission<Test> = (miner_id.clone(), example_url());
+            OldSubmissions::<Test>::insert(hash, old_submission);
+            for url in [b"https://www.bbc.com/news".as_slice(), b"not a url"] {
+                OldWhitelist::<Test>::insert(BoundedVec::<u8, MaxUrlLength>::try_from(url.to_vec()).unwrap(), ());
+            }
+
+            MigrateToV2::<Test>::on_runtime_upgrade();
+
+            let id = Miner::submission_id(&example_url(), &hash);
+            let record = crate::Submissions::<Test>::get(id).unwrap();
+            assert_eq!((record.miner, record.url, record.content_hash), (miner_id, example_url(), hash));
+            assert_eq!((record.timestamp, record.content_length, record.canonicalization_version), (0, 0, 0));
+            assert_eq!((record.fingerprint, record.similarity), (None, Perbill::one()));
+            assert!(is_rule(b"https://www.bbc.com/news"));
+            assert_eq!(Whitelist::<Test>::iter_keys().count(), 1);
+            let (host, path_prefix) = Miner::whitelist_keys(b"https://www.bbc.com/news").unwrap();
+            assert_eq!(Whitelist::<Test>::get(&host, &path_prefix), Some(Miner::default_whitelist_entry(None)));
+            assert_eq!(Miner::on_chain_storage_version(), StorageVersion::new(2));
+        });
+    }
+
+    #[test]
+    fn whitelist_edits_require_whitelist_origin() {
+        new_test_ext().execute_with(|| {
+            let caller = AccountId32::new([1; 32]);
+            assert_noop!(
+                Miner::add_to_whitelist(RuntimeOrigin::signed(caller), b"http://example.com".to_vec()),
+                sp_runtime::DispatchError::BadOrigin
+            );
+        });
+    }
+
+    #[test]
+    fn approved_whitelist_proposal_is_enacted() {
+        new_test_ext().execute_with(|| {
+            let proposer = AccountId32::new([1; 32]);
+            let voters = [AccountId32::new([2; 32]), AccountId32::new([3; 32])];
+            for voter in &voters {
+                let _ = Balances::deposit_creating(voter, 100);
+            }
+
+            assert_ok!(Miner::propose_whitelist_change(RuntimeOrigin::signed(proposer.clone()), example_url().into(), WhitelistAction::Add));
+            assert_eq!(Balances::reserved_balance(&proposer), 20);
+            assert_ok!(Miner::vote_whitelist_proposal(RuntimeOrigin::signed(voters[0].clone()), 0, true, 60));
+            assert_ok!(Miner::vote_whitelist_proposal(RuntimeOrigin::signed(voters[1].clone()), 0, false, 50));
+            assert_noop!(
+                Miner::vote_whitelist_proposal(RuntimeOrigin::signed(voters[1].clone()), 0, true, 10),
+                Error::<Test>::AlreadyVotedOnProposal
+            );
+            let proposal = Miner::whitelist_proposals(0).unwrap();
+            assert_eq!((proposal.ayes, proposal.nays, proposal.end), (60, 50, 6));
+
+            Miner::on_initialize(6);
+
+            assert!(is_rule(b"http://example.com"));
+            assert_eq!(entry(b"http://example.com").added_by, Some(proposer.clone()));
+            assert_eq!(Miner::whitelist_proposals(0), None);
+            assert_eq!(Balances::reserved_balance(&proposer), 0);
+            System::assert_has_event(RuntimeEvent::Miner(crate::Event::WhitelistProposalApproved { proposal_id: 0 }));
+
+            // Voters unlock their stake themselves
+            assert_eq!(Balances::reserved_balance(&voters[0]), 60);
+            for voter in &voters {
+                assert_ok!(Miner::unlock_proposal_vote(RuntimeOrigin::signed(voter.clone()), 0));
+                assert_eq!(Miner::proposal_votes(0, voter), None);
+                assert_eq!(Balances::free_balance(voter), 100);
+            }
+            System::assert_last_event(RuntimeEvent::Miner(crate::Event::WhitelistVoteUnlocked {
+                proposal_id: 0,
+                voter: voters[1].clone(),
+                stake: 50,
+            }));
+            assert_noop!(
+                Miner::unlock_proposal_vote(RuntimeOrigin::signed(voters[0].clone()), 0),
+                Error::<Test>::ProposalVoteNotFound
+            );
+        });
+    }
+
+    #[test]
+    fn rejected_whitelist_proposal_slashes_deposit() {
+        new_test_ext().execute_with(|| {
+            let proposer = AccountId32::new([1; 32]);
+            let voter = AccountId32::new([2; 32]);
+            let _ = Balances::deposit_creating(&voter, 100);
+            whitelist(b"http://example.com");
+
+            assert_noop!(
+                Miner::propose_whitelist_change(RuntimeOrigin::signed(proposer.clone()), example_url().into(), WhitelistAction::Add),
+                Error::<Test>::UrlAlreadyWhitelisted
+            );
+            assert_ok!(Miner::propose_whitelist_change(RuntimeOrigin::signed(proposer.clone()), example_url().into(), WhitelistAction::Remove));
+            assert_ok!(Miner::vote_whitelist_proposal(RuntimeOrigin::signed(voter.clone()), 0, false, 10));
+            assert_noop!(
+                Miner::unlock_proposal_vote(RuntimeOrigin::signed(voter.clone()), 0),
+                Error::<Test>::ProposalStillOpen
+            );
+
+            Miner::on_initialize(6);
+
+            assert!(is_rule(b"http://example.com"));
+            assert_eq!(Balances::free_balance(&proposer), 980);
+            assert_eq!(Balances::reserved_balance(&proposer), 0);
+            assert_eq!(Balances::free_balance(RewardPool::get()), 20);
+            assert_ok!(Miner::unlock_proposal_vote(RuntimeOrigin::signed(voter.clone()), 0));
+            assert_eq!(Balances::free_balance(&voter), 100);
+            assert_noop!(
+                Miner::vote_whitelist_proposal(RuntimeOrigin::signed(voter), 0, true, 10),
+                Error::<Test>::ProposalNotFound
+            );
+        });
+    }
+
+    #[test]
+    fn whitelist_proposals_need_turnout_and_approval() {
+        new_test_ext().execute_with(|| {
+            let proposer = AccountId32::new([1; 32]);
+            let voters = [AccountId32::new([2; 32]), AccountId32::new([3; 32])];
+            for voter in &voters {
+                let _ = Balances::deposit_creating(voter, 100);
+            }
+
+            // Unopposed, but less than the minimum turnout of 50
+            assert_ok!(Miner::propose_whitelist_change(RuntimeOrigin::signed(proposer.clone()), example_url().into(), WhitelistAction::Add));
+            assert_ok!(Miner::vote_whitelist_proposal(RuntimeOrigin::signed(voters[0].clone()), 0, true, 49));
+            Miner::on_initialize(6);
+            assert!(!is_rule(b"http://example.com"));
+            System::assert_has_event(RuntimeEvent::Miner(crate::Event::WhitelistProposalRejected { proposal_id: 0, deposit_slashed: 20 }));
+
+            // Exactly half of the stake in favour is not more than the approval threshold
+            assert_ok!(Miner::propose_whitelist_change(RuntimeOrigin::signed(proposer.clone()), example_url().into(), WhitelistAction::Add));
+            assert_ok!(Miner::vote_whitelist_proposal(RuntimeOrigin::signed(voters[0].clone()), 1, true, 30));
+            assert_ok!(Miner::vote_whitelist_proposal(RuntimeOrigin::signed(voters[1].clone()), 1, false, 30));
+            Miner::on_initialize(6);
+            assert!(!is_rule(b"http://example.com"));
+            System::assert_has_event(RuntimeEvent::Miner(crate::Event::WhitelistProposalRejected { proposal_id: 1, deposit_slashed: 20 }));
+        });
+    }
+
+    fn submit_url(miner: &AccountId32, url: &[u8]) -> sp_runtime::DispatchResult {
+        Miner::submit_hash(RuntimeOrigin::signed(miner.clone()), url.to_vec(), H256::random(), b"bafkqaaa".to_vec(), 1_024, b"text/html".to_vec(), None)
+    }
+
+    #[test]
+    fn whitelist_matches_subdomains_and_path_prefixes() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner_id.clone()), 100));
+            assert_ok!(Miner::add_to_whitelist(RuntimeOrigin::root(), b"https://*.wikipedia.org/wiki".to_vec()));
+            assert_ok!(Miner::add_to_whitelist(RuntimeOrigin::root(), b"https://www.bbc.com/news".to_vec()));
+
+            assert_ok!(submit_url(&miner_id, b"https://en.wikipedia.org/wiki/Rust"));
+            assert_ok!(submit_url(&miner_id, b"https://wikipedia.org/wiki"));
+            assert_ok!(submit_url(&miner_id, b"http://www.bbc.com/news/world"));
+            for url in [
+                b"https://en.wikipedia.org/wikis".as_slice(),
+                b"https://en.wikipedia.org/w/index.php",
+                b"https://wikipedia.org.evil.com/wiki/Rust",
+                b"https://sport.bbc.com/news/world",
+            ] {
+                assert_noop!(submit_url(&miner_id, url), Error::<Test>::NotWhitelisted);
+            }
+        });
+    }
+
+    #[test]
+    fn whitelist_lookups_are_bounded() {
+        new_test_ext().execute_with(|| {
+            for rule in [b"https://*.x.a.b.example.org".as_slice(), b"https://example.org/a/b/c/d/e"] {
+                assert_noop!(
+                    Miner::add_to_whitelist(RuntimeOrigin::root(), rule.to_vec()),
+                    Error::<Test>::InvalidWhitelistRule
+                );
+            }
+            // Exact hosts are a single candidate and can have any number of labels.
+            assert_ok!(Miner::add_to_whitelist(RuntimeOrigin::root(), b"https://x.a.b.example.org/a/b/c/d".to_vec()));
+
+            let url = crate::url::Url::parse(b"https://x.y.z.a.b.example.org/1/2/3/4/5/6/7/8").unwrap();
+            let candidates = url.candidate_rules().collect::<Vec<_>>();
+            assert_eq!(candidates.len(), crate::url::MAX_CANDIDATE_RULES);
+            assert_eq!(candidates[crate::url::MAX_RULE_SEGMENTS + 1].host, b"*.a.b.example.org".to_vec());
+            assert_eq!(candidates.last().unwrap().path_prefix, b"/1/2/3/4".to_vec());
+
+            let miner_id = AccountId32::new([1; 32]);
+            assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner_id.clone()), 100));
+            assert_ok!(Miner::add_to_whitelist(RuntimeOrigin::root(), b"https://*.b.example.org/1/2/3/4".to_vec()));
+            assert_ok!(submit_url(&miner_id, b"https://x.y.z.a.b.example.org/1/2/3/4/5/6/7/8"));
+        });
+    }
+
+    #[test]
+    fn submitted_urls_are_canonicalized() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner_id.clone()), 100));
+            whitelist(b"https://*.wikipedia.org");
+
+            assert_ok!(submit_url(&miner_id, b"HTTPS://EN.Wikipedia.ORG/wiki/Rust/"));
+            let canonical: BoundedVec<u8, MaxUrlLength> = b"https://en.wikipedia.org/wiki/Rust".to_vec().try_into().unwrap();
+            assert_eq!(Miner::history(&canonical).len(), 1);
+            assert_eq!(Miner::history(&canonical)[0].miner, miner_id);
+        });
+    }
+
+    #[test]
+    fn malformed_urls_are_rejected() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner_id.clone()), 100));
+            whitelist(b"https://*.example.com");
+
+            assert_noop!(submit_url(&miner_id, b"example.com/page"), Error::<Test>::MissingScheme);
+            assert_noop!(submit_url(&miner_id, b"ftp://example.com"), Error::<Test>::UnsupportedScheme);
+            assert_noop!(submit_url(&miner_id, b"https:///page"), Error::<Test>::MissingHost);
+            assert_noop!(submit_url(&miner_id, b"https://exa_mple.com"), Error::<Test>::InvalidHost);
+            assert_noop!(submit_url(&miner_id, b"https://user@example.com"), Error::<Test>::InvalidHost);
+            assert_noop!(submit_url(&miner_id, b"https://*.example.com/page"), Error::<Test>::InvalidHost);
+            assert_noop!(submit_url(&miner_id, b"https://example.com:99999"), Error::<Test>::InvalidPort);
+            assert_noop!(
+                Miner::add_to_whitelist(RuntimeOrigin::root(), b"https://example.org/?page=1".to_vec()),
+                Error::<Test>::InvalidWhitelistRule
+            );
+        });
+    }
+
+    fn canonical(url: &[u8]) -> Result<Vec<u8>, crate::url::UrlError> {
+        crate::url::Url::parse(url).map(|url| url.to_bytes())
+    }
+
+    #[test]
+    fn urls_are_canonicalized() {
+        let expected = Ok(b"http://example.com".to_vec());
+        assert_eq!(canonical(b"HTTP://Example.com:80/"), expected);
+        assert_eq!(canonical(b"http://example.com"), expected);
+        assert_eq!(canonical(b"http://example.com./#top"), expected);
+        assert_eq!(canonical(b"https://example.com:443/a/./b/../c/"), Ok(b"https://example.com/a/c".to_vec()));
+        assert_eq!(canonical(b"https://example.com:8443/%e2%82%ac"), Ok(b"https://example.com:8443/%E2%82%AC".to_vec()));
+        assert_eq!(
+            canonical(b"https://example.com/search?q=rust&utm_source=feed&fbclid=x&&lang=en"),
+            Ok(b"https://example.com/search?lang=en&q=rust".to_vec())
+        );
+        assert_eq!(canonical(b"https://example.com/?utm_medium=email"), Ok(b"https://example.com".to_vec()));
+    }
+
+    #[test]
+    fn malformed_urls_are_rejected_by_canonicalization() {
+        use crate::url::UrlError;
+
+        assert_eq!(canonical(b"https://example.com/a b"), Err(UrlError::InvalidCharacter));
+        assert_eq!(canonical(b"https://example.com/%zz"), Err(UrlError::InvalidPercentEncoding));
+        assert_eq!(canonical(b"https://example.com/?q=%2"), Err(UrlError::InvalidPercentEncoding));
+        assert_eq!(canonical(b"mailto://example.com"), Err(UrlError::UnsupportedScheme));
+    }
+
+    #[test]
+    fn whitelist_rules_are_canonicalized() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(Miner::add_to_whitelist(RuntimeOrigin::root(), b"HTTPS://WWW.BBC.COM/news/#latest".to_vec()));
+            assert!(is_rule(b"https://www.bbc.com/news"));
+            assert_noop!(
+                Miner::add_to_whitelist(RuntimeOrigin::root(), b"https://www.bbc.com:443/news/".to_vec()),
+                Error::<Test>::UrlAlreadyWhitelisted
+            );
+        });
+    }
+
+    fn batch(rules: &[&[u8]]) -> BoundedVec<BoundedVec<u8, MaxUrlLength>, crate::mock::MaxWhitelistBatch> {
+        let rules = rules.iter().map(|rule| BoundedVec::try_from(rule.to_vec()).unwrap()).collect::<Vec<_>>();
+        BoundedVec::try_from(rules).unwrap()
+    }
+
+    #[test]
+    fn batch_whitelist_calls_skip_unchanged_rules() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            whitelist(b"https://www.bbc.com/news");
+
+            assert_ok!(Miner::batch_add_to_whitelist(
+                RuntimeOrigin::root(),
+                batch(&[b"https://www.bbc.com/news", b"https://*.wikipedia.org", b"https://data.gov"]),
+            ));
+            assert!(is_rule(b"https://*.wikipedia.org"));
+            assert!(is_rule(b"https://data.gov"));
+            let updates = System::events()
+                .into_iter()
+                .filter(|record| matches!(record.event, RuntimeEvent::Miner(crate::Event::WhitelistUpdated { .. })))
+                .count();
+            assert_eq!(updates, 2);
+
+            assert_ok!(Miner::batch_remove_from_whitelist(
+                RuntimeOrigin::root(),
+                batch(&[b"https://data.gov", b"https://example.com", b"https://www.bbc.com/news"]),
+            ));
+            assert!(!is_rule(b"https://data.gov"));
+            assert!(!is_rule(b"https://www.bbc.com/news"));
+            assert!(is_rule(b"https://*.wikipedia.org"));
+            System::assert_last_event(RuntimeEvent::Miner(crate::Event::WhitelistUpdated {
+                url: b"https://www.bbc.com/news".to_vec(),
+                added: false,
+            }));
+        });
+    }
+
+    #[test]
+    fn batch_whitelist_calls_are_atomic_and_privileged() {
+        new_test_ext().execute_with(|| {
+            let rules = batch(&[b"https://data.gov", b"ftp://example.com"]);
+            assert_noop!(
+                Miner::batch_add_to_whitelist(RuntimeOrigin::signed(AccountId32::new([1; 32])), rules.clone()),
+                sp_runtime::DispatchError::BadOrigin
+            );
+            assert_noop!(Miner::batch_add_to_whitelist(RuntimeOrigin::root(), rules), Error::<Test>::UnsupportedScheme);
+            assert!(!is_rule(b"https://data.gov"));
+        });
+    }
+
+    fn update_entry(rule: &[u8], status: Option<WhitelistStatus>, priority: Option<Priority>) -> sp_runtime::DispatchResult {
+        Miner::update_whitelist_entry(RuntimeOrigin::root(), rule.to_vec(), status, priority, None, None, None)
+    }
+
+    #[test]
+    fn whitelist_entries_start_with_default_metadata() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(Miner::add_to_whitelist(RuntimeOrigin::root(), b"https://www.si.edu".to_vec()));
+
+            let entry = entry(b"https://www.si.edu");
+            assert_eq!(entry.status, WhitelistStatus::Enabled);
+            assert_eq!(entry.priority, Priority::Normal);
+            assert_eq!(entry.rearchive_interval, 100);
+            assert!(entry.respect_robots);
+            assert_eq!(entry.max_fingerprint_distance, 0);
+            assert_eq!(entry.added_by, None);
+        });
+    }
+
+    #[test]
+    fn whitelist_entry_metadata_can_be_updated() {
+        new_test_ext().execute_with(|| {
+            whitelist(b"https://www.si.edu");
+
+            assert_ok!(Miner::update_whitelist_entry(
+                RuntimeOrigin::root(),
+                b"https://WWW.SI.EDU/".to_vec(),
+                None,
+                Some(Priority::High),
+                Some(50),
+                Some(false),
+                Some(6),
+            ));
+
+            let entry = entry(b"https://www.si.edu");
+            assert_eq!(entry.status, WhitelistStatus::Enabled);
+            assert_eq!(entry.priority, Priority::High);
+            assert_eq!(entry.rearchive_interval, 50);
+            assert!(!entry.respect_robots);
+            assert_eq!(entry.max_fingerprint_distance, 6);
+            System::assert_last_event(RuntimeEvent::Miner(crate::Event::WhitelistEntryUpdated {
+                url: b"https://WWW.SI.EDU/".to_vec(),
+                entry,
+            }));
+
+            assert_noop!(update_entry(b"https://www.data.gov", None, None), Error::<Test>::UrlNotWhitelisted);
+            assert_noop!(
+                Miner::update_whitelist_entry(RuntimeOrigin::root(), b"https://www.si.edu".to_vec(), None, None, None, None, Some(64)),
+                Error::<Test>::FingerprintDistanceTooLarge
+            );
+            assert_noop!(
+                Miner::update_whitelist_entry(
+                    RuntimeOrigin::signed(AccountId32::new([1; 32])),
+                    b"https://www.si.edu".to_vec(),
+                    Some(WhitelistStatus::Paused),
+                    None,
+                    None,
+                    None,
+                    None,
+                ),
+                sp_runtime::DispatchError::BadOrigin
+            );
+        });
+    }
+
+    #[test]
+    fn paused_entries_refuse_submissions() {
+        new_test_ext().execute_with(|| {
+            let miner = AccountId32::new([1; 32]);
+            assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner.clone()), 100));
+            whitelist(b"https://www.bbc.com");
+            whitelist(b"https://www.bbc.com/sport");
+            assert_ok!(update_entry(b"https://www.bbc.com/sport", Some(WhitelistStatus::Paused), None));
+
+            // The most specific rule decides
+            assert_noop!(submit_url(&miner, b"https://www.bbc.com/sport/football"), Error::<Test>::WhitelistPaused);
+            assert_ok!(submit_url(&miner, b"https://www.bbc.com/news"));
+
+            assert_ok!(update_entry(b"https://www.bbc.com/sport", Some(WhitelistStatus::Enabled), None));
+            assert_ok!(submit_url(&miner, b"https://www.bbc.com/sport/football"));
+        });
+    }
+
+    #[test]
+    fn priority_scales_the_reward() {
+        use crate::mock::PAYOUTS;
+        use sp_runtime::Perbill;
+
+        new_test_ext().execute_with(|| {
+            let miner = AccountId32::new([1; 32]);
+            let validators = [AccountId32::new([2; 32]), AccountId32::new([3; 32])];
+            add_validators(&validators);
+            let id = submit_whitelisted(&miner);
+            // Due again as soon as it is archived
+            assert_ok!(Miner::update_whitelist_entry(
+                RuntimeOrigin::root(),
+                b"http://example.com".to_vec(),
+                None,
+                Some(Priority::High),
+                Some(0),
+                None,
+                None,
+            ));
+
+            vote(id, &validators, true);
+            let id = submit_whitelisted(&miner);
+            assert_ok!(update_entry(b"http://example.com", None, Some(Priority::Low)));
+            vote(id, &validators, true);
+
+            let payouts = PAYOUTS.with(|payouts| payouts.borrow().clone());
+            assert_eq!(payouts, vec![(miner.clone(), Perbill::one()), (miner, Perbill::from_percent(25))]);
+        });
+    }
+
+    fn payouts() -> Vec<AccountId32> {
+        crate::mock::PAYOUTS.with(|payouts| payouts.borrow().iter().map(|(miner, _)| miner.clone()).collect())
+    }
+
+    fn task(url: &[u8]) -> Option<crate::CrawlTaskOf<Test>> {
+        Miner::crawl_tasks(BoundedVec::<u8, MaxUrlLength>::try_from(url.to_vec()).unwrap())
+    }
+
+    /// The submission id of the latest snapshot of `url`.
+    fn latest_id(url: &[u8]) -> H256 {
+        let url = BoundedVec::<u8, MaxUrlLength>::try_from(url.to_vec()).unwrap();
+        submission_id(&url, Miner::history(&url).last().unwrap().content_hash)
+    }
+
+    #[test]
+    fn whitelisted_pages_are_queued() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(Miner::add_to_whitelist(RuntimeOrigin::root(), b"HTTPS://www.si.edu/".to_vec()));
+            assert_ok!(Miner::add_to_whitelist(RuntimeOrigin::root(), b"https://*.wikipedia.org/wiki".to_vec()));
+
+            assert_eq!(task(b"https://www.si.edu"), Some(crate::CrawlTask { due_at: 1, claim: None }));
+            System::assert_has_event(RuntimeEvent::Miner(crate::Event::TaskScheduled {
+                url: b"https://www.si.edu".to_vec(),
+                due_at: 1,
+            }));
+            // Wildcard rules do not name a page
+            assert_eq!(crate::CrawlTasks::<Test>::iter().count(), 1);
+        });
+    }
+
+    #[test]
+    fn claimed_tasks_are_only_rewarded_to_the_claimant() {
+        new_test_ext().execute_with(|| {
+            let (alice, bob) = (AccountId32::new([1; 32]), AccountId32::new([4; 32]));
+            let validators = [AccountId32::new([2; 32]), AccountId32::new([3; 32])];
+            let _ = Balances::deposit_creating(&bob, 1_000);
+            add_validators(&validators);
+            for miner in [&alice, &bob] {
+                assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner.clone()), 100));
+            }
+            assert_ok!(Miner::add_to_whitelist(RuntimeOrigin::root(), b"https://www.si.edu".to_vec()));
+
+            assert_ok!(Miner::claim_task(RuntimeOrigin::signed(alice.clone()), b"https://www.si.edu/".to_vec()));
+            System::assert_last_event(RuntimeEvent::Miner(crate::Event::TaskClaimed {
+                miner: alice.clone(),
+                url: b"https://www.si.edu".to_vec(),
+                expires_at: 6,
+            }));
+            assert_noop!(
+                Miner::claim_task(RuntimeOrigin::signed(bob.clone()), b"https://www.si.edu".to_vec()),
+                Error::<Test>::TaskAlreadyClaimed
+            );
+
+            // Archived, but not paid for
+            assert_ok!(submit_url(&bob, b"https://www.si.edu"));
+            let id = latest_id(b"https://www.si.edu");
+            vote(id, &validators, true);
+            assert_eq!(Miner::submission_status(id), Some(SubmissionStatus::Approved));
+            assert!(payouts().is_empty());
+
+            assert_ok!(submit_url(&alice, b"https://www.si.edu"));
+            let id = latest_id(b"https://www.si.edu");
+            vote(id, &validators, true);
+            assert_eq!(payouts(), vec![alice.clone()]);
+            assert_eq!(task(b"https://www.si.edu"), Some(crate::CrawlTask { due_at: 101, claim: None }));
+
+            // Not due again until the re-archive interval has passed
+            assert_noop!(
+                Miner::claim_task(RuntimeOrigin::signed(bob.clone()), b"https://www.si.edu".to_vec()),
+                Error::<Test>::TaskNotDue
+            );
+            assert_ok!(submit_url(&alice, b"https://www.si.edu"));
+            let id = latest_id(b"https://www.si.edu");
+            vote(id, &validators, true);
+            assert_eq!(payouts(), vec![alice]);
+
+            System::set_block_number(101);
+            assert_ok!(Miner::claim_task(RuntimeOrigin::signed(bob), b"https://www.si.edu".to_vec()));
+        });
+    }
+
+    #[test]
+    fn task_claims_expire() {
+        new_test_ext().execute_with(|| {
+            let (alice, bob) = (AccountId32::new([1; 32]), AccountId32::new([4; 32]));
+            let _ = Balances::deposit_creating(&bob, 1_000);
+            for miner in [&alice, &bob] {
+                assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner.clone()), 100));
+            }
+            whitelist(b"https://www.bbc.com/news");
+
+            // Pages that were never archived are due without being queued
+            assert_ok!(Miner::claim_task(RuntimeOrigin::signed(alice), b"https://www.bbc.com/news/world".to_vec()));
+            System::set_block_number(5);
+            assert_noop!(
+                Miner::claim_task(RuntimeOrigin::signed(bob.clone()), b"https://www.bbc.com/news/world".to_vec()),
+                Error::<Test>::TaskAlreadyClaimed
+            );
+            System::set_block_number(6);
+            assert_ok!(Miner::claim_task(RuntimeOrigin::signed(bob.clone()), b"https://www.bbc.com/news/world".to_vec()));
+
+            assert_noop!(
+                Miner::claim_task(RuntimeOrigin::signed(bob.clone()), b"https://www.si.edu".to_vec()),
+                Error::<Test>::NotWhitelisted
+            );
+            assert_ok!(Miner::chill(RuntimeOrigin::signed(bob.clone())));
+            assert_noop!(
+                Miner::claim_task(RuntimeOrigin::signed(bob), b"https://www.bbc.com/news".to_vec()),
+                Error::<Test>::MinerChilled
+            );
+        });
+    }
+
+    #[test]
+    fn failed_submissions_release_their_task() {
+        new_test_ext().execute_with(|| {
+            let (alice, bob) = (AccountId32::new([1; 32]), AccountId32::new([4; 32]));
+            let validators = [AccountId32::new([2; 32]), AccountId32::new([3; 32])];
+            let _ = Balances::deposit_creating(&bob, 1_000);
+            add_validators(&validators);
+            for miner in [&alice, &bob] {
+                assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner.clone()), 100));
+            }
+
+            // Submitting claims the task until the voting window closes
+            let id = submit_whitelisted(&alice);
+            assert_noop!(
+                Miner::claim_task(RuntimeOrigin::signed(bob.clone()), b"http://example.com".to_vec()),
+                Error::<Test>::TaskAlreadyClaimed
+            );
+            vote(id, &validators, false);
+            assert_eq!(task(b"http://example.com").unwrap().claim, None);
+
+            let id = submit_whitelisted(&bob);
+            System::set_block_number(11);
+            Miner::on_initialize(11);
+            assert_eq!(Miner::submission_status(id), Some(SubmissionStatus::Expired));
+            assert_eq!(task(b"http://example.com").unwrap().claim, None);
+            assert_ok!(Miner::claim_task(RuntimeOrigin::signed(alice), b"http://example.com".to_vec()));
+        });
+    }
+
+    fn submit_example(miner: &AccountId32, hash: H256) -> sp_runtime::DispatchResult {
+        Miner::submit_hash(RuntimeOrigin::signed(miner.clone()), b"http://example.com".to_vec(), hash, b"bafkqaaa".to_vec(), 1_024, b"text/html".to_vec(), None)
+    }
+
+    #[test]
+    fn unchanged_content_is_attested_once_due() {
+        use crate::mock::PAYOUTS;
+        use sp_runtime::Perbill;
+
+        new_test_ext().execute_with(|| {
+            let miner = AccountId32::new([1; 32]);
+            let validators = [AccountId32::new([2; 32]), AccountId32::new([3; 32])];
+            add_validators(&validators);
+            let id = submit_whitelisted(&miner);
+            vote(id, &validators, true);
+            let record = Miner::submissions(id).unwrap();
+            let hash = record.content_hash;
+
+            // Within the re-archive interval
+            assert_noop!(submit_example(&miner, hash), Error::<Test>::UnchangedContent);
+
+            System::set_block_number(101);
+            let balance = Balances::free_balance(&miner);
+            assert_ok!(submit_example(&miner, hash));
+            let attestation = MinerPallet::<Test>::attestation_id(&id, 101);
+            System::assert_last_event(RuntimeEvent::Miner(crate::Event::UnchangedContentAttested {
+                miner: miner.clone(),
+                url: b"http://example.com".to_vec(),
+                hash,
+                id: attestation,
+            }));
+            assert_noop!(submit_example(&miner, hash), Error::<Test>::DuplicateSubmission);
+
+            // A lower fee, and nothing is paid until the committee confirms
+            assert_eq!(Balances::free_balance(&miner), balance - 2);
+            assert_eq!(Miner::attestations(attestation), Some(()));
+            assert_eq!(committee(attestation), validators.to_vec());
+            let url = BoundedVec::<u8, MaxUrlLength>::try_from(b"http://example.com".to_vec()).unwrap();
+            assert_eq!(Miner::history(&url)[1].status, SubmissionStatus::Pending);
+            assert_eq!(PAYOUTS.with(|payouts| payouts.borrow().len()), 1);
+
+            vote(attestation, &validators, true);
+            System::assert_has_event(RuntimeEvent::Miner(crate::Event::SubmissionUnchanged {
+                miner: miner.clone(),
+                url: b"http://example.com".to_vec(),
+                hash,
+                id: attestation,
+            }));
+            assert_eq!(Miner::submission_status(attestation), Some(SubmissionStatus::Unchanged));
+            assert_eq!(Miner::attestations(attestation), None);
+
+            // No new record, only a snapshot
+            assert_eq!(Miner::submission_status(id), Some(SubmissionStatus::Approved));
+            assert_eq!(Miner::submissions(id), Some(record));
+            assert_eq!(Miner::submissions(attestation), None);
+            let history = Miner::history(&url);
+            assert_eq!(history.len(), 2);
+            assert_eq!((history[1].block_number, history[1].status), (101, SubmissionStatus::Unchanged));
+            assert_eq!(task(b"http://example.com").unwrap().due_at, 201);
+
+            // Normal priority pays half of the full reward, of which unchanged content gets 10%
+            let payouts = PAYOUTS.with(|payouts| payouts.borrow().clone());
+            assert_eq!(payouts, vec![(miner.clone(), Perbill::from_percent(50)), (miner.clone(), Perbill::from_percent(5))]);
+
+            assert_noop!(submit_example(&miner, hash), Error::<Test>::UnchangedContent);
+            // Changed content is archived as usual
+            assert_ok!(submit_example(&miner, H256::random()));
+        });
+    }
+
+    #[test]
+    fn rejected_unchanged_attestations_slash_the_miner() {
+        new_test_ext().execute_with(|| {
+            let miner = AccountId32::new([1; 32]);
+            let validators = [AccountId32::new([2; 32]), AccountId32::new([3; 32])];
+            add_validators(&validators);
+            let id = submit_whitelisted(&miner);
+            let hash = content_hash(id);
+            vote(id, &validators, true);
+
+            System::set_block_number(101);
+            assert_ok!(submit_example(&miner, hash));
+            let attestation = MinerPallet::<Test>::attestation_id(&id, 101);
+            vote(attestation, &validators, false);
+
+            assert_eq!(Miner::submission_status(attestation), Some(SubmissionStatus::Rejected));
+            assert_eq!(Miner::submission_status(id), Some(SubmissionStatus::Approved));
+            assert_eq!(Balances::reserved_balance(&miner), 80);
+            assert_eq!(task(b"http://example.com").unwrap().claim, None);
+            assert_eq!(crate::mock::PAYOUTS.with(|payouts| payouts.borrow().len()), 1);
+        });
+    }
+
+    #[test]
+    fn unchanged_content_needs_the_task() {
+        new_test_ext().execute_with(|| {
+            let (alice, bob) = (AccountId32::new([1; 32]), AccountId32::new([4; 32]));
+            let validators = [AccountId32::new([2; 32]), AccountId32::new([3; 32])];
+            let _ = Balances::deposit_creating(&bob, 1_000);
+            add_validators(&validators);
+            let id = submit_whitelisted(&alice);
+            let hash = content_hash(id);
+            vote(id, &validators, true);
+            assert_ok!(Miner::register_miner(RuntimeOrigin::signed(bob.clone()), 100));
+
+            System::set_block_number(101);
+            assert_ok!(Miner::claim_task(RuntimeOrigin::signed(bob.clone()), b"http://example.com".to_vec()));
+            assert_noop!(submit_example(&alice, hash), Error::<Test>::UnchangedContent);
+            assert_ok!(submit_example(&bob, hash));
+        });
+    }
+
+    const SALT: [u8; 32] = [7; 32];
+
+    fn commit(miner: &AccountId32, url: &[u8], hash: H256) -> sp_runtime::DispatchResult {
+        let commitment = MinerPallet::<Test>::commitment_of(url, &hash, &SALT, miner);
+        Miner::commit_submission(RuntimeOrigin::signed(miner.clone()), commitment)
+    }
+
+    fn reveal(miner: &AccountId32, url: &[u8], hash: H256) -> sp_runtime::DispatchResult {
+        Miner::reveal_submission(RuntimeOrigin::signed(miner.clone()), url.to_vec(), hash, b"bafkqaaa".to_vec(), 1_024, b"text/html".to_vec(), None, SALT)
+    }
+
+    #[test]
+    fn committed_submissions_are_revealed() {
+        new_test_ext().execute_with(|| {
+            let miner = AccountId32::new([1; 32]);
+            assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner.clone()), 100));
+            whitelist(b"http://example.com");
+            let hash = H256::random();
+
+            assert_ok!(commit(&miner, b"http://example.com", hash));
+            assert_noop!(commit(&miner, b"http://example.com", hash), Error::<Test>::DuplicateCommitment);
+            // The salt and the miner are part of the commitment
+            assert_noop!(
+                Miner::reveal_submission(RuntimeOrigin::signed(miner.clone()), b"http://example.com".to_vec(), hash, b"bafkqaaa".to_vec(), 1_024, b"text/html".to_vec(), None, [0; 32]),
+                Error::<Test>::CommitmentNotFound
+            );
+            assert_noop!(reveal(&AccountId32::new([2; 32]), b"http://example.com", hash), Error::<Test>::CommitmentNotFound);
+
+            System::set_block_number(2);
+            assert_ok!(reveal(&miner, b"http://example.com", hash));
+            let pending = Miner::pending_submissions(submission_id(b"http://example.com", hash)).unwrap();
+            assert_eq!((pending.record.miner, pending.submitted_at, pending.committed_at), (miner.clone(), 2, 1));
+            assert_eq!(Miner::commitments(MinerPallet::<Test>::commitment_of(b"http://example.com", &hash, &SALT, &miner)), None);
+            assert_noop!(reveal(&miner, b"http://example.com", hash), Error::<Test>::CommitmentNotFound);
+        });
+    }
+
+    #[test]
+    fn commitments_must_be_revealed_in_time() {
+        new_test_ext().execute_with(|| {
+            let miner = AccountId32::new([1; 32]);
+            assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner.clone()), 100));
+            whitelist(b"http://example.com");
+            let hash = H256::random();
+            assert_ok!(commit(&miner, b"http://example.com", hash));
+
+            System::set_block_number(5);
+            assert_noop!(reveal(&miner, b"http://example.com", hash), Error::<Test>::CommitmentExpired);
+        });
+    }
+
+    #[test]
+    fn first_committer_takes_over_copied_submissions() {
+        new_test_ext().execute_with(|| {
+            let (honest, lazy) = (AccountId32::new([1; 32]), AccountId32::new([4; 32]));
+            let validator = AccountId32::new([2; 32]);
+            let _ = Balances::deposit_creating(&lazy, 1_000);
+            add_validators(core::slice::from_ref(&validator));
+            for miner in [&honest, &lazy] {
+                assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner.clone()), 100));
+            }
+            whitelist(b"http://example.com");
+            let hash = H256::random();
+            let id = submission_id(b"http://example.com", hash);
+            assert_ok!(commit(&honest, b"http://example.com", hash));
+
+            // The lazy miner copies the hash and gets it in first
+            System::set_block_number(2);
+            assert_ok!(submit_example(&lazy, hash));
+            vote(id, core::slice::from_ref(&validator), true);
+            assert_eq!(Balances::free_balance(&lazy), 890);
+
+            assert_ok!(reveal(&honest, b"http://example.com", hash));
+            System::assert_last_event(RuntimeEvent::Miner(crate::Event::SubmissionReassigned {
+                id,
+                from: lazy.clone(),
+                to: honest.clone(),
+            }));
+            let pending = Miner::pending_submissions(id).unwrap();
+            assert_eq!((pending.record.miner, pending.committed_at, pending.ayes), (honest.clone(), 1, 1));
+            assert_eq!(Balances::free_balance(&lazy), 900);
+            assert_eq!(Miner::pending_submission_count(&lazy), 0);
+            assert_eq!(Miner::pending_submission_count(&honest), 1);
+            let url = BoundedVec::<u8, MaxUrlLength>::try_from(b"http://example.com".to_vec()).unwrap();
+            assert_eq!(Miner::history(&url).len(), 1);
+            assert_eq!(Miner::history(&url)[0].miner, honest);
+            assert_eq!(task(b"http://example.com").unwrap().claim.unwrap().miner, honest);
+
+            // Later committers cannot take it back
+            assert_noop!(submit_example(&lazy, hash), Error::<Test>::DuplicateSubmission);
+        });
+    }
+
+    #[test]
+    fn expired_commitments_are_removed_when_idle() {
+        new_test_ext().execute_with(|| {
+            let miner = AccountId32::new([1; 32]);
+            assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner.clone()), 100));
+            let (stale, revealed) = (H256::repeat_byte(1), H256::random());
+            whitelist(b"http://example.com");
+            assert_ok!(Miner::commit_submission(RuntimeOrigin::signed(miner.clone()), stale));
+            assert_ok!(commit(&miner, b"http://example.com", revealed));
+            assert_ok!(reveal(&miner, b"http://example.com", revealed));
+            System::set_block_number(2);
+            let fresh = H256::repeat_byte(2);
+            assert_ok!(Miner::commit_submission(RuntimeOrigin::signed(miner), fresh));
+
+            Miner::on_idle(3, frame_support::weights::Weight::MAX);
+            assert!(Miner::commitments(stale).is_some());
+
+            Miner::on_idle(4, frame_support::weights::Weight::MAX);
+            assert_eq!(Miner::commitments(stale), None);
+            assert_eq!(Miner::commitments(fresh), Some(2));
+            System::assert_last_event(RuntimeEvent::Miner(crate::Event::CommitmentsExpired { count: 1 }));
+        });
+    }
+
+    fn committee(id: H256) -> Vec<AccountId32> {
+        Miner::committees(id).map(|committee| committee.members.into_inner()).unwrap_or_default()
+    }
+
+    #[test]
+    fn submissions_are_assigned_a_committee() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            let validators: Vec<_> = (2..=5).map(|i| AccountId32::new([i; 32])).collect();
+            // The miner is a validator too, but never sits on its own committee
+            add_validators(core::slice::from_ref(&miner_id));
+            add_validators(&validators);
+            let id = submit_whitelisted(&miner_id);
+
+            assert_eq!(committee(id), validators[..3].to_vec());
+            assert_eq!(Miner::committees(id).unwrap().assigned_at, 1);
+            System::assert_has_event(RuntimeEvent::Miner(crate::Event::CommitteeAssigned {
+                id,
+                validators: validators[..3].to_vec(),
+            }));
+            assert_noop!(commit_vote(&validators[3], id, true), Error::<Test>::NotAssigned);
+
+            // The committee is cleared with the submission
+            vote(id, &validators[..3], true);
+            assert_eq!(Miner::committees(id), None);
+        });
+    }
+
+    #[test]
+    fn committees_are_not_assigned_without_randomness() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            add_validators(&[AccountId32::new([2; 32]), AccountId32::new([3; 32])]);
+            whitelist(b"http://example.com");
+            assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner_id.clone()), 100));
+
+            RANDOMNESS.with(|randomness| *randomness.borrow_mut() = false);
+            assert_noop!(submit_example(&miner_id, H256::repeat_byte(1)), Error::<Test>::RandomnessUnavailable);
+
+            RANDOMNESS.with(|randomness| *randomness.borrow_mut() = true);
+            assert_ok!(submit_example(&miner_id, H256::repeat_byte(1)));
+        });
+    }
+
+    #[test]
+    fn timed_out_committee_members_are_replaced() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            let validators: Vec<_> = (2..=7).map(|i| AccountId32::new([i; 32])).collect();
+            add_validators(&validators[..3]);
+            let id = submit_whitelisted(&miner_id);
+            assert_ok!(commit_vote(&validators[0], id, true));
+
+            add_validators(&validators[3..]);
+            // The committee was assigned at block 1 and has 4 blocks to vote
+            System::set_block_number(4);
+            assert_noop!(
+                Miner::reassign_committee(RuntimeOrigin::signed(miner_id.clone()), id),
+                Error::<Test>::CommitteeActive
+            );
+
+            System::set_block_number(5);
+            assert_ok!(Miner::reassign_committee(RuntimeOrigin::signed(miner_id.clone()), id));
+            let expected = vec![validators[0].clone(), validators[3].clone(), validators[4].clone()];
+            assert_eq!(committee(id), expected);
+            assert_eq!(Miner::committees(id).unwrap().assigned_at, 5);
+            System::assert_last_event(RuntimeEvent::Miner(crate::Event::CommitteeAssigned { id, validators: expected }));
+            // Members replaced for not voting missed their vote
+            assert_eq!(MISSED.with(|missed| missed.borrow().clone()), validators[1..3].to_vec());
+            assert_noop!(commit_vote(&validators[1], id, true), Error::<Test>::NotAssigned);
+            assert_ok!(commit_vote(&validators[3], id, true));
+            assert_ok!(commit_vote(&validators[4], id, true));
+            assert_ok!(reveal_vote(&validators[0], id, true));
+            assert_ok!(reveal_vote(&validators[3], id, true));
+            assert_ok!(reveal_vote(&validators[4], id, true));
+            assert_eq!(Miner::submission_status(id), Some(SubmissionStatus::Approved));
+        });
+    }
+
+    #[test]
+    fn empty_committee_seats_are_filled_later() {
+        new_test_ext().execute_with(|| {
+            let miner_id = AccountId32::new([1; 32]);
+            let validators = [AccountId32::new([2; 32]), AccountId32::new([3; 32])];
+            add_validators(&validators[..1]);
+            let id = submit_whitelisted(&miner_id);
+            assert_eq!(committee(id), validators[..1].to_vec());
+
+            // Seats left empty can be filled right away, but only once someone is available
+            assert_noop!(
+                Miner::reassign_committee(RuntimeOrigin::signed(miner_id.clone()), id),
+                Error::<Test>::NoValidatorsAvailable
+            );
+            add_validators(&validators[1..]);
+            assert_ok!(Miner::reassign_committee(RuntimeOrigin::signed(miner_id.clone()), id));
+            assert_eq!(committee(id), validators.to_vec());
+
+            assert_noop!(
+                Miner::reassign_committee(RuntimeOrigin::signed(miner_id), H256::random()),
+                Error::<Test>::SubmissionNotPending
+            );
+        });
+    }
+
+    #[test]
+    fn similar_fingerprints_approve_dynamic_pages() {
+        new_test_ext().execute_with(|| {
+            let miner = AccountId32::new([1; 32]);
+            let validators = [AccountId32::new([2; 32]), AccountId32::new([3; 32]), AccountId32::new([4; 32])];
+            add_validators(&validators);
+            assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner.clone()), 100));
+            whitelist(b"http://example.com");
+            assert_ok!(Miner::update_whitelist_entry(RuntimeOrigin::root(), b"http://example.com".to_vec(), None, None, None, None, Some(3)));
+
+            let hash = H256::random();
+            assert_ok!(Miner::submit_hash(RuntimeOrigin::signed(miner.clone()), b"http://example.com".to_vec(), hash, b"bafkqaaa".to_vec(), 1_024, b"text/html".to_vec(), Some(0xf0)));
+            let id = submission_id(b"http://example.com", hash);
+            // A fingerprint two bits away, one eight bits away, and the exact content
+            let scrapes = [(H256::repeat_byte(1), Some(0xf3)), (H256::repeat_byte(2), Some(0x0f)), (hash, None)];
+            for (validator, (content_hash, fingerprint)) in validators.iter().zip(scrapes) {
+                let commitment = MinerPallet::<Test>::vote_commitment_of(&content_hash, &fingerprint, &[7; 32], validator);
+                assert_ok!(Miner::commit_vote(RuntimeOrigin::signed(validator.clone()), id, commitment));
+            }
+            for (validator, (content_hash, fingerprint)) in validators.iter().zip(scrapes) {
+                assert_ok!(Miner::reveal_vote(RuntimeOrigin::signed(validator.clone()), id, content_hash, fingerprint, [7; 32]));
+            }
+
+            assert_eq!(Miner::submission_status(id), Some(SubmissionStatus::Approved));
+            let record = Miner::submissions(id).unwrap();
+            assert_eq!(record.fingerprint, Some(0xf0));
+            assert_eq!(record.similarity, Perbill::from_rational(62u32, 64));
+            assert_eq!(outcomes(), vec![(validators[0].clone(), true), (validators[1].clone(), false), (validators[2].clone(), true)]);
+        });
+    }
+
+    #[test]
+    fn vote_similarity_needs_fingerprints_within_the_rule_distance() {
+        new_test_ext().execute_with(|| {
+            let miner = AccountId32::new([1; 32]);
+            assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner.clone()), 100));
+            whitelist(b"http://example.com");
+            let hash = H256::random();
+            assert_ok!(Miner::submit_hash(RuntimeOrigin::signed(miner), b"http://example.com".to_vec(), hash, b"bafkqaaa".to_vec(), 1_024, b"text/html".to_vec(), Some(1)));
+            let record = Miner::pending_submissions(submission_id(b"http://example.com", hash)).unwrap().record;
+            let other = H256::repeat_byte(1);
+
+            assert_eq!(MinerPallet::<Test>::vote_similarity(&record, &hash, None), Some(Perbill::one()));
+            assert_eq!(MinerPallet::<Test>::vote_similarity(&record, &other, None), None);
+            // Rules only accept identical fingerprints by default
+            assert_eq!(MinerPallet::<Test>::vote_similarity(&record, &other, Some(1)), Some(Perbill::one()));
+            assert_eq!(MinerPallet::<Test>::vote_similarity(&record, &other, Some(3)), None);
+
+            assert_ok!(Miner::update_whitelist_entry(RuntimeOrigin::root(), b"http://example.com".to_vec(), None, None, None, None, Some(1)));
+            assert_eq!(MinerPallet::<Test>::vote_similarity(&record, &other, Some(3)), Some(Perbill::from_rational(63u32, 64)));
+
+            let unfingerprinted = crate::ArchiveRecord { fingerprint: None, ..record };
+            assert_eq!(MinerPallet::<Test>::vote_similarity(&unfingerprinted, &other, Some(1)), None);
+        });
+    }
+
+    /// An approved submission by a fresh miner, and a funded challenger.
+    fn approved_submission(validators: &[AccountId32]) -> (AccountId32, AccountId32, H256) {
+        let miner = AccountId32::new([1; 32]);
+        // Not the reward pool, so that payouts to either can be told apart
+        let challenger = AccountId32::new([7; 32]);
+        add_validators(validators);
+        let id = submit_whitelisted(&miner);
+        vote(id, validators, true);
+        Balances::make_free_balance_be(&challenger, 100);
+        (miner, challenger, id)
+    }
+
+    #[test]
+    fn approved_records_can_be_challenged_within_the_period() {
+        new_test_ext().execute_with(|| {
+            let validators = [AccountId32::new([2; 32]), AccountId32::new([3; 32])];
+            let (miner, challenger, id) = approved_submission(&validators);
+            let pending = submit_whitelisted(&miner);
+
+            assert_noop!(
+                Miner::challenge_submission(RuntimeOrigin::signed(challenger.clone()), pending),
+                Error::<Test>::NotChallengeable
+            );
+            assert_noop!(
+                Miner::challenge_submission(RuntimeOrigin::signed(challenger.clone()), H256::random()),
+                Error::<Test>::NotChallengeable
+            );
+            assert_noop!(
+                Miner::challenge_submission(RuntimeOrigin::signed(AccountId32::new([8; 32])), id),
+                Error::<Test>::InsufficientFunds
+            );
+            // The record was approved at block 1 and the challenge period is 20 blocks
+            assert_eq!(Miner::challenge_deadlines(id), Some(21));
+            System::set_block_number(22);
+            assert_noop!(
+                Miner::challenge_submission(RuntimeOrigin::signed(challenger.clone()), id),
+                Error::<Test>::ChallengePeriodOver
+            );
+
+            System::set_block_number(21);
+            // Validators are not assigned to their own challenges, nor to records they approved
+            add_validators(core::slice::from_ref(&challenger));
+            let reviewers = [AccountId32::new([5; 32]), AccountId32::new([6; 32])];
+            add_validators(&reviewers);
+            assert_eq!(Miner::approving_committees(id).map(|committee| committee.into_inner()), Some(validators.to_vec()));
+            assert_ok!(Miner::challenge_submission(RuntimeOrigin::signed(challenger.clone()), id));
+            assert_eq!(Balances::reserved_balance(&challenger), 50);
+            assert_eq!(Miner::submission_status(id), Some(SubmissionStatus::Disputed));
+            assert_eq!(
+                Miner::history(example_url()).iter().find(|snapshot| snapshot.content_hash == content_hash(id)).map(|snapshot| snapshot.status),
+                Some(SubmissionStatus::Disputed)
+            );
+            assert_eq!(Miner::disputes(id).map(|dispute| dispute.challenger), Some(challenger.clone()));
+            assert_eq!(committee(id), reviewers.to_vec());
+            System::assert_last_event(RuntimeEvent::Miner(crate::Event::SubmissionChallenged {
+                id,
+                challenger: challenger.clone(),
+                bond: 50,
+            }));
+
+            // The record cannot be challenged twice, resubmitted or reported while disputed
+            assert_noop!(
+                Miner::challenge_submission(RuntimeOrigin::signed(challenger), id),
+                Error::<Test>::NotChallengeable
+            );
+            assert_noop!(
+                Miner::submit_hash(RuntimeOrigin::signed(miner), b"http://example.com".to_vec(), content_hash(id), b"bafkqaaa".to_vec(), 1_024, b"text/html".to_vec(), None),
+                Error::<Test>::DuplicateSubmission
+            );
+            assert_noop!(
+                Miner::report_mismatch(RuntimeOrigin::root(), id),
+                Error::<Test>::SubmissionDisputed
+            );
+        });
+    }
+
+    #[test]
+    fn rejected_challenges_overturn_the_record() {
+        new_test_ext().execute_with(|| {
+            let validators = [AccountId32::new([2; 32]), AccountId32::new([3; 32])];
+            let (miner, challenger, id) = approved_submission(&validators);
+            let reviewers = [AccountId32::new([5; 32]), AccountId32::new([6; 32])];
+            add_validators(&reviewers);
+            assert_ok!(Miner::challenge_submission(RuntimeOrigin::signed(challenger.clone()), id));
+
+            OUTCOMES.with(|outcomes| outcomes.borrow_mut().clear());
+            vote(id, &reviewers, false);
+            assert_eq!(Miner::submission_status(id), Some(SubmissionStatus::Overturned));
+            assert_eq!(Miner::history(example_url()).last().map(|snapshot| snapshot.status), Some(SubmissionStatus::Overturned));
+            assert_eq!(Miner::submissions(id), None);
+            assert_eq!(Miner::disputes(id), None);
+            assert_eq!(Miner::pending_submission_count(&miner), 0);
+            assert_eq!(outcomes(), vec![(reviewers[0].clone(), true), (reviewers[1].clone(), true)]);
+            assert_eq!(Miner::approving_committees(id), None);
+
+            // 20 of the miner's deposit is slashed, half of it to the challenger who gets their
+            // bond back and the other half to the reviewers
+            assert_eq!(Balances::reserved_balance(&miner), 80);
+            assert_eq!(Balances::reserved_balance(&challenger), 0);
+            assert_eq!(Balances::free_balance(&challenger), 110);
+            assert_eq!(Balances::free_balance(&reviewers[0]), 5);
+            assert_eq!(Balances::free_balance(&reviewers[1]), 5);
+            assert_eq!(Balances::free_balance(RewardPool::get()), 6);
+            System::assert_last_event(RuntimeEvent::Miner(crate::Event::SubmissionOverturned {
+                id,
+                challenger,
+            }));
+        });
+    }
+
+    #[test]
+    fn upheld_challenges_slash_the_challenger() {
+        new_test_ext().execute_with(|| {
+            let validators = [AccountId32::new([2; 32]), AccountId32::new([3; 32])];
+            let (miner, challenger, id) = approved_submission(&validators);
+            let reviewers = [AccountId32::new([5; 32]), AccountId32::new([6; 32])];
+            add_validators(&reviewers);
+            assert_ok!(Miner::challenge_submission(RuntimeOrigin::signed(challenger.clone()), id));
+
+            vote(id, &reviewers, true);
+            assert_eq!(Miner::submission_status(id), Some(SubmissionStatus::Approved));
+            assert_eq!(Miner::history(example_url()).last().map(|snapshot| snapshot.status), Some(SubmissionStatus::Approved));
+            assert_eq!(Miner::submissions(id).map(|record| record.miner), Some(miner.clone()));
+
+            // Half of the bond goes to the miner, the rest to the reviewers and what does not
+            // split evenly to the reward pool
+            assert_eq!(Balances::reserved_balance(&challenger), 0);
+            assert_eq!(Balances::free_balance(&challenger), 50);
+            assert_eq!(Balances::free_balance(&miner), 915);
+            assert_eq!(Balances::free_balance(&reviewers[0]), 12);
+            assert_eq!(Balances::free_balance(&reviewers[1]), 12);
+            assert_eq!(Balances::free_balance(RewardPool::get()), 7);
+            System::assert_last_event(RuntimeEvent::Miner(crate::Event::ChallengeFailed {
+                id,
+                challenger: challenger.clone(),
+                slashed: 50,
+            }));
+
+            // A record is only challenged once
+            assert_noop!(
+                Miner::challenge_submission(RuntimeOrigin::signed(challenger), id),
+                Error::<Test>::ChallengePeriodOver
+            );
+        });
+    }
+
+    #[test]
+    fn expired_challenges_return_the_bond() {
+        new_test_ext().execute_with(|| {
+            let validators = [AccountId32::new([2; 32]), AccountId32::new([3; 32])];
+            let (miner, challenger, id) = approved_submission(&validators);
+            assert_ok!(Miner::challenge_submission(RuntimeOrigin::signed(challenger.clone()), id));
+
+            Miner::on_initialize(11);
+            assert_eq!(Miner::submission_status(id), Some(SubmissionStatus::Approved));
+            assert_eq!(Miner::submissions(id).map(|record| record.miner), Some(miner.clone()));
+            assert_eq!(Balances::reserved_balance(&miner), 100);
+            assert_eq!(Balances::free_balance(&challenger), 100);
+            System::assert_last_event(RuntimeEvent::Miner(crate::Event::ChallengeExpired { id, challenger }));
+        });
+    }
+
+    fn submit_url_with(miner: &AccountId32, url: &[u8], hash: H256) -> sp_runtime::DispatchResult {
+        Miner::submit_hash(RuntimeOrigin::signed(miner.clone()), url.to_vec(), hash, b"bafkqaaa".to_vec(), 1_024, b"text/html".to_vec(), None)
+    }
+
+    #[test]
+    fn mirrored_and_reverted_content_is_archived() {
+        new_test_ext().execute_with(|| {
+            let miner = AccountId32::new([1; 32]);
+            let validators = [AccountId32::new([2; 32]), AccountId32::new([3; 32])];
+            add_validators(&validators);
+            assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner.clone()), 100));
+            whitelist(b"http://example.com");
+            whitelist(b"http://mirror.example.org");
+            let (a, b) = (H256::repeat_byte(1), H256::repeat_byte(2));
+
+            // The same content served from a mirror is a separate submission
+            assert_ok!(submit_example(&miner, a));
+            assert_ok!(submit_url_with(&miner, b"http://mirror.example.org", a));
+            let (original, mirror) = (submission_id(b"http://example.com", a), submission_id(b"http://mirror.example.org", a));
+            assert_ne!(original, mirror);
+            vote(original, &validators, true);
+            vote(mirror, &validators, true);
+            assert_eq!(Miner::submissions(mirror).map(|record| record.url.to_vec()), Some(b"http://mirror.example.org".to_vec()));
+
+            // A page that changes and then changes back is archived again
+            assert_ok!(submit_example(&miner, b));
+            vote(submission_id(b"http://example.com", b), &validators, true);
+            let approved = Miner::submissions(original).unwrap();
+            System::set_block_number(2);
+            Timestamp::set_timestamp(1_700_000_000_000);
+            assert_ok!(submit_example(&miner, a));
+            assert_eq!(Miner::submission_status(original), Some(SubmissionStatus::Pending));
+
+            // The earlier record stands while the content is validated again, and if it is not
+            // approved
+            assert_eq!(Miner::submissions(original), Some(approved.clone()));
+            vote(original, &validators, false);
+            assert_eq!(Miner::submission_status(original), Some(SubmissionStatus::Approved));
+            assert_eq!(Miner::submissions(original), Some(approved));
+            assert!(Miner::challenge_deadlines(original).is_some());
+
+            assert_ok!(submit_example(&miner, a));
+            vote(original, &validators, true);
+            assert_eq!(Miner::submission_status(original), Some(SubmissionStatus::Approved));
+            assert_eq!(Miner::submissions(original).map(|record| record.timestamp), Some(1_700_000_000_000));
+            let history: Vec<_> = Miner::history(example_url()).iter().map(|snapshot| (snapshot.content_hash, snapshot.status)).collect();
+            // The oldest snapshot made room for the newest
+            assert_eq!(
+                history,
+                vec![
+                    (b, SubmissionStatus::Approved),
+                    (a, SubmissionStatus::Rejected),
+                    (a, SubmissionStatus::Approved),
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn pages_are_canonicalized_like_the_reference_tooling() {
+        use crate::offchain::canonicalize;
+
+        let page = b"<!DOCTYPE html><html><head><title>Title</title><style>p { color: red; }</style></head>\
+            <body><script>if (a < b) {}</script><div class=\"Header\">Menu</div>\
+            <p>Fish &amp; chips&nbsp;</p>\n<p>a < b</p><img class=\"ad\" src=\"x.png\"><!-- <p>comment</p> -->\
+            <p title=\"x > y\">Caf&#233;  au lait</p></body></html>";
+        // Like the reference tooling, any class containing "ad" drops its element
+        assert_eq!(canonicalize(page), "Title Fish & chips a < b Café  au lait".as_bytes());
+
+        // Only the main content is kept, if there is one
+        let page = b"<nav>Menu</nav><MAIN><h1>News</h1><div class='ad-slot'><div>Buy</div>now</div><p>Story</p></main><footer>Footer</footer>";
+        assert_eq!(canonicalize(page), b"News Story");
+        assert_eq!(canonicalize(b"  plain text  "), b"plain text");
+    }
+
+    #[test]
+    fn fingerprints_of_similar_pages_are_close() {
+        use crate::offchain::fingerprint;
+
+        let text = b"the quick brown fox jumps over the lazy dog while the cat sleeps in the warm sun";
+        let edited = b"the quick brown fox jumps over the lazy dog while the cat naps in the warm sun";
+        let other = b"an entirely different page about something else altogether with other words";
+        assert_eq!(fingerprint(text), fingerprint(text));
+        assert_eq!(fingerprint(b"a b"), fingerprint(b"b  a"));
+        assert!((fingerprint(text) ^ fingerprint(edited)).count_ones() < 8);
+        assert!((fingerprint(text) ^ fingerprint(other)).count_ones() > 16);
+    }
+
+    #[test]
+    fn offchain_worker_commits_and_reveals_votes() {
+        use crate::mock::{RuntimeCall, UncheckedExtrinsic};
+        use codec::Decode;
+        use sp_core::offchain::{testing, OffchainDbExt, OffchainWorkerExt, TransactionPoolExt};
+        use sp_keystore::{testing::MemoryKeystore, Keystore, KeystoreExt};
+
+        let (offchain, offchain_state) = testing::TestOffchainExt::new();
+        let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+        let keystore = MemoryKeystore::new();
+        let validator = AccountId32::from(keystore.sr25519_generate_new(crate::offchain::KEY_TYPE, None).unwrap());
+        let mut ext = new_test_ext();
+        ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+        ext.register_extension(OffchainDbExt::new(offchain));
+        ext.register_extension(TransactionPoolExt::new(pool));
+        ext.register_extension(KeystoreExt::new(keystore));
+        let sent = || -> Vec<RuntimeCall> {
+            pool_state
+                .write()
+                .transactions
+                .drain(..)
+                .map(|tx| UncheckedExtrinsic::decode(&mut &*tx).unwrap().function)
+                .collect()
+        };
+
+        ext.execute_with(|| {
+            let miner = AccountId32::new([1; 32]);
+            add_validators(core::slice::from_ref(&validator));
+            whitelist(b"http://example.com");
+            assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner.clone()), 100));
+            let hash = H256(sp_io::hashing::sha2_256(b"Hello world"));
+            assert_ok!(Miner::submit_hash(RuntimeOrigin::signed(miner), b"http://example.com".to_vec(), hash, b"bafkqaaa".to_vec(), 1_024, b"text/html".to_vec(), None));
+            let id = submission_id(b"http://example.com", hash);
+
+            offchain_state.write().expect_request(testing::PendingRequest {
+                method: "GET".into(),
+                uri: "http://example.com/robots.txt".into(),
+                response: Some(b"User-agent: *\nDisallow: /private".to_vec()),
+                sent: true,
+                ..Default::default()
+            });
+            offchain_state.write().expect_request(testing::PendingRequest {
+                method: "GET".into(),
+                uri: "http://example.com".into(),
+                response: Some(b"<html><body><script>track()</script><main><p>Hello</p> <p>world</p></main></body></html>".to_vec()),
+                sent: true,
+                ..Default::default()
+            });
+            Miner::offchain_worker(1);
+            let calls = sent();
+            let [RuntimeCall::Miner(crate::Call::commit_vote { id: committed, commitment })] = calls.as_slice() else {
+                panic!("expected a commitment, got {:?}", calls);
+            };
+            assert_eq!(*committed, id);
+
+            // The page is not scraped again and the commitment not sent again while it is pending
+            Miner::offchain_worker(2);
+            assert!(sent().is_empty());
+
+            assert_ok!(Miner::commit_vote(RuntimeOrigin::signed(validator.clone()), id, *commitment));
+            Miner::offchain_worker(3);
+            let calls = sent();
+            let [RuntimeCall::Miner(crate::Call::reveal_vote { id: revealed, content_hash, fingerprint, salt })] = calls.as_slice() else {
+                panic!("expected a reveal, got {:?}", calls);
+            };
+            assert_eq!((*revealed, *content_hash), (id, hash));
+            assert_eq!(*fingerprint, Some(crate::offchain::fingerprint(b"Hello world")));
+
+            assert_ok!(Miner::reveal_vote(RuntimeOrigin::signed(validator.clone()), id, *content_hash, *fingerprint, *salt));
+            assert_eq!(Miner::votes(id, &validator), Some(true));
+            Miner::offchain_worker(4);
+            assert!(sent().is_empty());
+        });
+    }
+
+    #[test]
+    fn robots_txt_rules_for_every_crawler_are_followed() {
+        use crate::offchain::robots_allows;
+
+        let robots = b"# Crawlers\r\nUser-agent: Googlebot\nDisallow: /\n\nUser-agent: Bingbot\nUser-agent: *\n\
+            Disallow: /private\nAllow: /private/public\nDisallow: /*.pdf$\nDisallow:\n\nUser-agent: Other\nDisallow: /news\n";
+        assert!(robots_allows(robots, b"/"));
+        assert!(robots_allows(robots, b"/news"));
+        assert!(!robots_allows(robots, b"/private"));
+        assert!(!robots_allows(robots, b"/private/page?id=1"));
+        // The longest matching rule decides
+        assert!(robots_allows(robots, b"/private/public/page"));
+        assert!(!robots_allows(robots, b"/files/report.pdf"));
+        assert!(robots_allows(robots, b"/files/report.pdf?download=1"));
+        assert!(robots_allows(b"", b"/private"));
+        assert!(robots_allows(b"User-agent: Googlebot\nDisallow: /", b"/private"));
+    }
+
+    #[test]
+    fn offchain_worker_respects_robots_txt() {
+        use sp_core::offchain::{testing, OffchainDbExt, OffchainWorkerExt, TransactionPoolExt};
+        use sp_keystore::{testing::MemoryKeystore, Keystore, KeystoreExt};
+
+        let (offchain, offchain_state) = testing::TestOffchainExt::new();
+        let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+        let keystore = MemoryKeystore::new();
+        let validator = AccountId32::from(keystore.sr25519_generate_new(crate::offchain::KEY_TYPE, None).unwrap());
+        let mut ext = new_test_ext();
+        ext.register_extension(OffchainWorkerExt::new(offchain.clone()));
+        ext.register_extension(OffchainDbExt::new(offchain));
+        ext.register_extension(TransactionPoolExt::new(pool));
+        ext.register_extension(KeystoreExt::new(keystore));
+
+        ext.execute_with(|| {
+            let miner = AccountId32::new([1; 32]);
+            add_validators(core::slice::from_ref(&validator));
+            whitelist(b"http://example.com");
+            assert_ok!(Miner::register_miner(RuntimeOrigin::signed(miner.clone()), 100));
+            let hash = H256(sp_io::hashing::sha2_256(b"Hello world"));
+            assert_ok!(Miner::submit_hash(RuntimeOrigin::signed(miner), b"http://example.com/private/page".to_vec(), hash, b"bafkqaaa".to_vec(), 1_024, b"text/html".to_vec(), None));
+
+            // The page itself is never fetched and the validator abstains
+            offchain_state.write().expect_request(testing::PendingRequest {
+                method: "GET".into(),
+                uri: "http://example.com/robots.txt".into(),
+                response: Some(b"User-agent: *\nDisallow: /private".to_vec()),
+                sent: true,
+                ..Default::default()
+            });
+            Miner::offchain_worker(1);
+            assert!(pool_state.read().transactions.is_empty());
+
+            // Entries that do not respect robots.txt are fetched without checking it
+            assert_ok!(Miner::update_whitelist_entry(RuntimeOrigin::root(), b"http://example.com".to_vec(), None, None, None, Some(false), None));
+            offchain_state.write().expect_request(testing::PendingRequest {
+                method: "GET".into(),
+                uri: "http://example.com/private/page".into(),
+                response: Some(b"<p>Hello world</p>".to_vec()),
+                sent: true,
+                ..Default::default()
+            });
+            Miner::offchain_worker(2);
+            assert_eq!(pool_state.read().transactions.len(), 1);
         });
     }
 }
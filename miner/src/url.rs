@@ -0,0 +1,312 @@
+//! URL canonicalization for the miner pallet.
+//!
+//! Every submitted URL is brought into a canonical form before it is used as an archive key, so
+//! that `HTTP://Example.com:80/`, `http://example.com` and `http://example.com/#top` all archive
+//! the same page:
+//!
+//! - the scheme must be `http` or `https` and is lowercased,
+//! - the host is lowercased and loses a trailing `.`,
+//! - the port is dropped if it is the scheme's default,
+//! - `.` and `..` path segments are resolved and trailing slashes removed,
+//! - percent-escapes use uppercase hex digits,
+//! - tracking query parameters such as `utm_source` are dropped and the rest sorted,
+//! - the fragment is dropped.
+//!
+//! Whitelist rules are URLs whose host may start with `*.` to also cover every subdomain, and
+//! whose path is a prefix matched on whole path segments: `https://*.wikipedia.org/wiki` allows
+//! `https://en.wikipedia.org/wiki/Rust` but not `https://en.wikipedia.org/wikis`. A wildcard
+//! host has at most [`MAX_WILDCARD_LABELS`] labels and a path prefix at most
+//! [`MAX_RULE_SEGMENTS`] segments, so a URL is only ever matched against
+//! [`MAX_CANDIDATE_RULES`] rules however long its host and path are.
+
+use sp_std::vec::Vec;
+
+/// Why a URL or whitelist rule was rejected.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum UrlError {
+	/// The URL has no `scheme://` prefix.
+	MissingScheme,
+	/// Only `http` and `https` URLs can be archived.
+	UnsupportedScheme,
+	/// The URL has no host.
+	MissingHost,
+	/// The host contains characters other than letters, digits, `-` and `.`, or an empty label.
+	InvalidHost,
+	/// The port is not a number between 0 and 65535.
+	InvalidPort,
+	/// The URL contains whitespace, control or non-ASCII bytes.
+	InvalidCharacter,
+	/// A `%` is not followed by two hex digits.
+	InvalidPercentEncoding,
+	/// Whitelist rules cannot have a query, a wildcard host with more than
+	/// [`MAX_WILDCARD_LABELS`] labels or a path prefix with more than [`MAX_RULE_SEGMENTS`]
+	/// segments.
+	InvalidRule,
+}
+
+/// A URL in canonical form, split into its parts.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Url {
+	pub scheme: Vec<u8>,
+	pub host: Vec<u8>,
+	/// `None` for the scheme's default port.
+	pub port: Option<u16>,
+	/// Empty for the root path.
+	pub path: Vec<u8>,
+	/// `None` if no parameters are left after tracking parameters are removed.
+	pub query: Option<Vec<u8>>,
+}
+
+/// The parts of a whitelist rule that are matched against submitted URLs.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Rule {
+	/// The host, prefixed with `*.` if subdomains are included.
+	pub host: Vec<u8>,
+	/// Empty if the whole host is whitelisted.
+	pub path_prefix: Vec<u8>,
+}
+
+/// Prefix of a rule's host that also covers every subdomain.
+pub const WILDCARD: &[u8] = b"*.";
+
+/// Most labels a wildcard rule's host can have, not counting the `*`.
+pub const MAX_WILDCARD_LABELS: usize = 4;
+
+/// Most path segments a rule's path prefix can have.
+pub const MAX_RULE_SEGMENTS: usize = 4;
+
+/// Most rules [`Url::candidate_rules`] returns for any URL: the exact host and one wildcard per
+/// label, each combined with the empty path and one prefix per segment.
+pub const MAX_CANDIDATE_RULES: usize = (1 + MAX_WILDCARD_LABELS) * (1 + MAX_RULE_SEGMENTS);
+
+/// Query parameters that only identify where a visitor came from and are dropped.
+const TRACKING_PARAMS: &[&[u8]] =
+	&[b"fbclid", b"gclid", b"dclid", b"msclkid", b"yclid", b"mc_cid", b"mc_eid", b"_ga"];
+
+/// Prefix of the Google Analytics campaign parameters, which are dropped.
+const TRACKING_PREFIX: &[u8] = b"utm_";
+
+impl Url {
+	/// Parse an absolute `http` or `https` URL into its canonical form.
+	pub fn parse(input: &[u8]) -> Result<Self, UrlError> {
+		if input.iter().any(|byte| !byte.is_ascii_graphic()) {
+			return Err(UrlError::InvalidCharacter)
+		}
+		let separator = find(input, b"://").ok_or(UrlError::MissingScheme)?;
+		let scheme = input[..separator].to_ascii_lowercase();
+		let default_port = match &scheme[..] {
+			b"http" => 80,
+			b"https" => 443,
+			_ => return Err(UrlError::UnsupportedScheme),
+		};
+
+		let rest = &input[separator + 3..];
+		let (rest, _fragment) = split_off(rest, b'#');
+		let (rest, query) = split_off(rest, b'?');
+		let authority_end = rest.iter().position(|&byte| byte == b'/').unwrap_or(rest.len());
+		let (authority, path) = rest.split_at(authority_end);
+
+		let (host, port) = match authority.iter().rposition(|&byte| byte == b':') {
+			Some(colon) => (&authority[..colon], Some(parse_port(&authority[colon + 1..])?)),
+			None => (authority, None),
+		};
+		let mut host = host.to_ascii_lowercase();
+		if host.last() == Some(&b'.') {
+			host.pop();
+		}
+		ensure_valid_host(host.strip_prefix(WILDCARD).unwrap_or(&host[..]))?;
+
+		Ok(Url {
+			scheme,
+			host,
+			port: port.filter(|&port| port != default_port),
+			path: canonical_path(path)?,
+			query: query.map(canonical_query).transpose()?.flatten(),
+		})
+	}
+
+	/// Whether the host is a `*.` wildcard, which is only meaningful for whitelist rules.
+	pub fn has_wildcard_host(&self) -> bool {
+		self.host.starts_with(WILDCARD)
+	}
+
+	/// The canonical URL as bytes.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut bytes = Vec::with_capacity(self.scheme.len() + self.host.len() + self.path.len() + 3);
+		bytes.extend_from_slice(&self.scheme);
+		bytes.extend_from_slice(b"://");
+		bytes.extend_from_slice(&self.host);
+		if let Some(port) = self.port {
+			bytes.push(b':');
+			push_decimal(&mut bytes, port);
+		}
+		bytes.extend_from_slice(&self.path);
+		if let Some(query) = &self.query {
+			bytes.push(b'?');
+			bytes.extend_from_slice(query);
+		}
+		bytes
+	}
+
+	/// Every whitelist rule that would allow this URL: the host itself and wildcards for it and
+	/// each of its parent domains, combined with every prefix of the path that ends on a segment
+	/// boundary. Wildcards and prefixes longer than a rule can be are skipped, so there are never
+	/// more than [`MAX_CANDIDATE_RULES`].
+	pub fn candidate_rules(&self) -> impl Iterator<Item = Rule> + '_ {
+		let labels = self.host.iter().filter(|&&byte| byte == b'.').count() + 1;
+		let hosts = core::iter::once(self.host.clone()).chain(
+			core::iter::once(0)
+				.chain(
+					self.host
+						.iter()
+						.enumerate()
+						.filter(|(_, &byte)| byte == b'.')
+						.map(|(index, _)| index + 1),
+				)
+				.skip(labels.saturating_sub(MAX_WILDCARD_LABELS))
+				.map(|start| [WILDCARD, &self.host[start..]].concat()),
+		);
+		hosts.flat_map(move |host| {
+			self.path_prefixes().map(move |path_prefix| Rule { host: host.clone(), path_prefix })
+		})
+	}
+
+	fn path_prefixes(&self) -> impl Iterator<Item = Vec<u8>> + '_ {
+		let boundaries = self
+			.path
+			.iter()
+			.enumerate()
+			.skip(1)
+			.filter(|(_, &byte)| byte == b'/')
+			.map(|(index, _)| index)
+			.chain(core::iter::once(self.path.len()).filter(|&len| len > 0))
+			.take(MAX_RULE_SEGMENTS);
+		core::iter::once(Vec::new()).chain(boundaries.map(|end| self.path[..end].to_vec()))
+	}
+}
+
+impl Rule {
+	/// Parse a whitelist rule such as `https://*.wikipedia.org/wiki`. The scheme must be `http`
+	/// or `https` and the rule covers both.
+	pub fn parse(input: &[u8]) -> Result<Self, UrlError> {
+		let url = Url::parse(input)?;
+		let labels = url.host.iter().filter(|&&byte| byte == b'.').count();
+		let segments = url.path.iter().filter(|&&byte| byte == b'/').count();
+		if url.query.is_some() ||
+			(url.has_wildcard_host() && labels > MAX_WILDCARD_LABELS) ||
+			segments > MAX_RULE_SEGMENTS
+		{
+			return Err(UrlError::InvalidRule)
+		}
+		Ok(Rule { host: url.host, path_prefix: url.path })
+	}
+}
+
+fn ensure_valid_host(host: &[u8]) -> Result<(), UrlError> {
+	let valid_label = |label: &[u8]| {
+		!label.is_empty() && label.iter().all(|byte| byte.is_ascii_alphanumeric() || *byte == b'-')
+	};
+	if host.is_empty() {
+		return Err(UrlError::MissingHost)
+	}
+	if !host.split(|&byte| byte == b'.').all(valid_label) {
+		return Err(UrlError::InvalidHost)
+	}
+	Ok(())
+}
+
+fn parse_port(digits: &[u8]) -> Result<u16, UrlError> {
+	if digits.is_empty() || digits.len() > 5 || !digits.iter().all(u8::is_ascii_digit) {
+		return Err(UrlError::InvalidPort)
+	}
+	let port = digits.iter().fold(0u32, |port, digit| port * 10 + (digit - b'0') as u32);
+	u16::try_from(port).map_err(|_| UrlError::InvalidPort)
+}
+
+/// Resolve dot segments, drop trailing slashes and uppercase percent-escapes.
+fn canonical_path(path: &[u8]) -> Result<Vec<u8>, UrlError> {
+	let mut segments: Vec<&[u8]> = Vec::new();
+	for segment in path.split(|&byte| byte == b'/').skip(1) {
+		match segment {
+			b"." => {},
+			b".." => {
+				segments.pop();
+			},
+			_ => segments.push(segment),
+		}
+	}
+	while segments.last().is_some_and(|segment| segment.is_empty()) {
+		segments.pop();
+	}
+
+	let mut canonical = Vec::with_capacity(path.len());
+	for segment in segments {
+		canonical.push(b'/');
+		canonical.extend_from_slice(&uppercase_escapes(segment)?);
+	}
+	Ok(canonical)
+}
+
+/// Drop empty and tracking parameters and sort the rest, returning `None` if nothing is left.
+fn canonical_query(query: &[u8]) -> Result<Option<Vec<u8>>, UrlError> {
+	let mut params = Vec::new();
+	for param in query.split(|&byte| byte == b'&') {
+		let name = param.split(|&byte| byte == b'=').next().unwrap_or_default();
+		let tracking = name.starts_with(TRACKING_PREFIX) ||
+			TRACKING_PARAMS.contains(&name);
+		if param.is_empty() || tracking {
+			continue
+		}
+		params.push(uppercase_escapes(param)?);
+	}
+	if params.is_empty() {
+		return Ok(None)
+	}
+	params.sort();
+	Ok(Some(params.join(&b'&')))
+}
+
+fn uppercase_escapes(input: &[u8]) -> Result<Vec<u8>, UrlError> {
+	let mut output = input.to_vec();
+	let mut index = 0;
+	while index < output.len() {
+		if output[index] == b'%' {
+			let escape = output.get_mut(index + 1..index + 3).ok_or(UrlError::InvalidPercentEncoding)?;
+			if !escape.iter().all(u8::is_ascii_hexdigit) {
+				return Err(UrlError::InvalidPercentEncoding)
+			}
+			escape.make_ascii_uppercase();
+			index += 3;
+		} else {
+			index += 1;
+		}
+	}
+	Ok(output)
+}
+
+fn push_decimal(bytes: &mut Vec<u8>, value: u16) {
+	let mut digits = [0u8; 5];
+	let mut start = digits.len();
+	let mut value = value;
+	loop {
+		start -= 1;
+		digits[start] = b'0' + (value % 10) as u8;
+		value /= 10;
+		if value == 0 {
+			break
+		}
+	}
+	bytes.extend_from_slice(&digits[start..]);
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+	haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+/// Split `input` at the first `delimiter`, returning what follows it, if any.
+fn split_off(input: &[u8], delimiter: u8) -> (&[u8], Option<&[u8]>) {
+	match input.iter().position(|&byte| byte == delimiter) {
+		Some(index) => (&input[..index], Some(&input[index + 1..])),
+		None => (input, None),
+	}
+}
@@ -1,90 +1,485 @@
-
-//! Autogenerated weights for pallet_template
+//! Hand-written weights for pallet_miner.
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
-//! DATE: 2023-04-06, STEPS: `50`, REPEAT: `20`, LOW RANGE: `[]`, HIGH RANGE: `[]`
-//! WORST CASE MAP SIZE: `1000000`
-//! HOSTNAME: `_`, CPU: `<UNKNOWN>`
-//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("dev"), DB CACHE: 1024
-
-// Executed Command:
-// ../../target/release/node-template
-// benchmark
-// pallet
-// --chain
-// dev
-// --pallet
-// pallet_template
-// --extrinsic
-// *
-// --steps=50
-// --repeat=20
-// --wasm-execution=compiled
-// --output
-// pallets/template/src/weights.rs
-// --template
-// ../../.maintain/frame-weight-template.hbs
+//! These are not benchmark results. The reads and writes are counted from the storage each call
+//! accesses and the proof sizes from the `MaxEncodedLen` of those items, while the execution
+//! times are estimates doubled as a safety margin. Replace this file with the output of
+//! `benchmark pallet --pallet pallet_miner` once it has been run on reference hardware.
 
 #![cfg_attr(rustfmt, rustfmt_skip)]
 #![allow(unused_parens)]
 #![allow(unused_imports)]
 
-use frame::{deps::frame_support::weights::constants::RocksDbWeight, prelude::*};
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
 use core::marker::PhantomData;
 
-/// Weight functions needed for pallet_template.
+/// Weight functions needed for pallet_miner.
 pub trait WeightInfo {
-	fn do_something() -> Weight;
-	fn cause_error() -> Weight;
+	fn register_miner() -> Weight;
+	fn submit_hash(u: u32, v: u32, ) -> Weight;
+	fn add_to_whitelist(u: u32, ) -> Weight;
+	fn remove_from_whitelist(u: u32, ) -> Weight;
+	fn batch_add_to_whitelist(n: u32, ) -> Weight;
+	fn batch_remove_from_whitelist(n: u32, ) -> Weight;
+	fn update_whitelist_entry(u: u32, ) -> Weight;
+	fn claim_task(u: u32, ) -> Weight;
+	fn commit_submission() -> Weight;
+	fn reveal_submission(u: u32, v: u32, ) -> Weight;
+	fn reassign_committee(v: u32, ) -> Weight;
+	fn challenge_submission(v: u32, ) -> Weight;
+	fn propose_whitelist_change(u: u32, ) -> Weight;
+	fn vote_whitelist_proposal() -> Weight;
+	fn unlock_proposal_vote() -> Weight;
+	fn bond_extra() -> Weight;
+	fn chill() -> Weight;
+	fn unbond() -> Weight;
+	fn withdraw_unbonded() -> Weight;
+	fn commit_vote() -> Weight;
+	fn reveal_vote() -> Weight;
+	fn report_mismatch() -> Weight;
+	fn finalize_submission() -> Weight;
 }
 
-/// Weights for pallet_template using the Substrate node and recommended hardware.
+/// Conservative weights for pallet_miner, until it is benchmarked.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
-	/// Storage: Template Something (r:0 w:1)
-	/// Proof: Template Something (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
-	fn do_something() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `0`
-		//  Estimated: `0`
-		// Minimum execution time: 8_000_000 picoseconds.
-		Weight::from_parts(9_000_000, 0)
+	/// Storage: Miner Miners (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	fn register_miner() -> Weight {
+		Weight::from_parts(66_000_000, 3593)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: Miner Miners (r:1 w:0)
+	/// Storage: Miner Chilled (r:1 w:0)
+	/// Storage: Miner Whitelist (r:25 w:0)
+	/// Storage: Miner SubmissionStatuses (r:1 w:1)
+	/// Storage: Miner VotingDeadlines (r:1 w:1)
+	/// Storage: System Account (r:2 w:2)
+	/// Storage: Miner CrawlTasks (r:1 w:1)
+	/// Storage: Miner History (r:1 w:1)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Storage: Miner PendingSubmissionCount (r:1 w:1)
+	/// Storage: Miner PendingSubmissions (r:0 w:1)
+	/// Storage: ParachainSystem ValidationData (r:1 w:0)
+	/// Storage: Validator Validators (r:v w:0)
+	/// Storage: Validator Reputation (r:v w:0)
+	/// Storage: Miner Committees (r:0 w:1)
+	/// The range of component `u` is `[31, 512]`.
+	/// The range of component `v` is `[5, 1000]`.
+	fn submit_hash(u: u32, v: u32, ) -> Weight {
+		Weight::from_parts(172_000_000, 49230)
+			.saturating_add(Weight::from_parts(22_000, 0).saturating_mul(u.into()))
+			.saturating_add(Weight::from_parts(18_000_000, 0).saturating_mul(v.into()))
+			.saturating_add(T::DbWeight::get().reads(37_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(v.into())))
+			.saturating_add(T::DbWeight::get().writes(9_u64))
+			.saturating_add(Weight::from_parts(0, 65).saturating_mul(u.into()))
+			.saturating_add(Weight::from_parts(0, 5_110).saturating_mul(v.into()))
+	}
+	/// Storage: Miner Whitelist (r:1 w:1)
+	/// Storage: Miner CrawlTasks (r:1 w:1)
+	/// The range of component `u` is `[31, 512]`.
+	fn add_to_whitelist(u: u32, ) -> Weight {
+		Weight::from_parts(40_000_000, 4565)
+			.saturating_add(Weight::from_parts(8_000, 0).saturating_mul(u.into()))
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: Miner Whitelist (r:1 w:1)
+	/// The range of component `u` is `[31, 512]`.
+	fn remove_from_whitelist(u: u32, ) -> Weight {
+		Weight::from_parts(30_000_000, 3995)
+			.saturating_add(Weight::from_parts(4_000, 0).saturating_mul(u.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: Miner Whitelist (r:256 w:256)
+	/// Storage: Miner CrawlTasks (r:256 w:256)
+	/// The range of component `n` is `[1, 256]`.
+	fn batch_add_to_whitelist(n: u32, ) -> Weight {
+		Weight::from_parts(30_000_000, 990)
+			.saturating_add(Weight::from_parts(54_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 3575).saturating_mul(n.into()))
+	}
+	/// Storage: Miner Whitelist (r:256 w:256)
+	/// The range of component `n` is `[1, 256]`.
+	fn batch_remove_from_whitelist(n: u32, ) -> Weight {
+		Weight::from_parts(32_000_000, 990)
+			.saturating_add(Weight::from_parts(34_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 3575).saturating_mul(n.into()))
+	}
+	/// Storage: Miner Whitelist (r:1 w:1)
+	/// The range of component `u` is `[31, 512]`.
+	fn update_whitelist_entry(u: u32, ) -> Weight {
+		Weight::from_parts(34_000_000, 4515)
+			.saturating_add(Weight::from_parts(4_000, 0).saturating_mul(u.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: Miner Miners (r:1 w:0)
+	/// Storage: Miner Chilled (r:1 w:0)
+	/// Storage: Miner Whitelist (r:25 w:0)
+	/// Storage: Miner CrawlTasks (r:1 w:1)
+	/// The range of component `u` is `[31, 512]`.
+	fn claim_task(u: u32, ) -> Weight {
+		Weight::from_parts(66_000_000, 4565)
+			.saturating_add(Weight::from_parts(16_000, 0).saturating_mul(u.into()))
+			.saturating_add(T::DbWeight::get().reads(28_u64))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
-	/// Storage: Template Something (r:1 w:1)
-	/// Proof: Template Something (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
-	fn cause_error() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `32`
-		//  Estimated: `1489`
-		// Minimum execution time: 6_000_000 picoseconds.
-		Weight::from_parts(6_000_000, 1489)
+	/// Storage: Miner Miners (r:1 w:0)
+	/// Storage: Miner Chilled (r:1 w:0)
+	/// Storage: Miner Commitments (r:1 w:1)
+	/// Storage: Miner CommitmentExpiries (r:1 w:1)
+	fn commit_submission() -> Weight {
+		Weight::from_parts(60_000_000, 11671)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: Miner Commitments (r:1 w:1)
+	/// Storage: Miner Miners (r:1 w:0)
+	/// Storage: Miner Chilled (r:1 w:0)
+	/// Storage: Miner Whitelist (r:25 w:0)
+	/// Storage: Miner SubmissionStatuses (r:1 w:1)
+	/// Storage: Miner PendingSubmissions (r:1 w:1)
+	/// Storage: Miner CrawlTasks (r:2 w:2)
+	/// Storage: Miner Votes (r:1 w:1)
+	/// Storage: System Account (r:3 w:3)
+	/// Storage: Miner History (r:1 w:1)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Storage: Miner PendingSubmissionCount (r:2 w:2)
+	/// Storage: Miner Committees (r:1 w:1)
+	/// Storage: ParachainSystem ValidationData (r:1 w:0)
+	/// Storage: Validator Validators (r:v w:0)
+	/// Storage: Validator Reputation (r:v w:0)
+	/// The range of component `u` is `[31, 512]`.
+	/// The range of component `v` is `[5, 1000]`.
+	fn reveal_submission(u: u32, v: u32, ) -> Weight {
+		Weight::from_parts(216_000_000, 52820)
+			.saturating_add(Weight::from_parts(24_000, 0).saturating_mul(u.into()))
+			.saturating_add(Weight::from_parts(18_000_000, 0).saturating_mul(v.into()))
+			.saturating_add(T::DbWeight::get().reads(42_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(v.into())))
+			.saturating_add(T::DbWeight::get().writes(14_u64))
+			.saturating_add(Weight::from_parts(0, 67).saturating_mul(u.into()))
+			.saturating_add(Weight::from_parts(0, 5_110).saturating_mul(v.into()))
+	}
+	/// Storage: Miner PendingSubmissions (r:1 w:0)
+	/// Storage: Miner Committees (r:1 w:1)
+	/// Storage: Miner VoteCommitments (r:3 w:0)
+	/// Storage: Miner Votes (r:3 w:0)
+	/// Storage: ParachainSystem ValidationData (r:1 w:0)
+	/// Storage: Validator Validators (r:v w:5)
+	/// Storage: Validator Reputation (r:v w:0)
+	/// Storage: Miner ApprovingCommittees (r:1 w:0)
+	/// The range of component `v` is `[10, 1000]`.
+	fn reassign_committee(v: u32, ) -> Weight {
+		Weight::from_parts(86_000_000, 10119)
+			.saturating_add(Weight::from_parts(18_000_000, 0).saturating_mul(v.into()))
+			.saturating_add(T::DbWeight::get().reads(13_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(v.into())))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+			.saturating_add(Weight::from_parts(0, 5_110).saturating_mul(v.into()))
+	}
+	/// Storage: Miner SubmissionStatuses (r:1 w:1)
+	/// Storage: Miner ChallengeDeadlines (r:1 w:1)
+	/// Storage: Miner Submissions (r:1 w:0)
+	/// Storage: System Account (r:1 w:1)
+	/// Storage: Miner VotingDeadlines (r:1 w:1)
+	/// Storage: Miner History (r:1 w:1)
+	/// Storage: Miner PendingSubmissions (r:0 w:1)
+	/// Storage: Miner PendingSubmissionCount (r:1 w:1)
+	/// Storage: Miner Disputes (r:0 w:1)
+	/// Storage: Miner Committees (r:0 w:1)
+	/// Storage: Miner ApprovingCommittees (r:1 w:0)
+	/// Storage: ParachainSystem ValidationData (r:1 w:0)
+	/// Storage: Validator Validators (r:v w:0)
+	/// Storage: Validator Reputation (r:v w:0)
+	/// The range of component `v` is `[10, 1000]`.
+	fn challenge_submission(v: u32, ) -> Weight {
+		Weight::from_parts(122_000_000, 14583)
+			.saturating_add(Weight::from_parts(18_000_000, 0).saturating_mul(v.into()))
+			.saturating_add(T::DbWeight::get().reads(13_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(v.into())))
+			.saturating_add(T::DbWeight::get().writes(9_u64))
+			.saturating_add(Weight::from_parts(0, 5_110).saturating_mul(v.into()))
+	}
+	/// Storage: Miner Whitelist (r:1 w:0)
+	/// Storage: Miner NextProposalId (r:1 w:1)
+	/// Storage: Miner ProposalDeadlines (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	/// Storage: Miner WhitelistProposals (r:0 w:1)
+	/// The range of component `u` is `[31, 512]`.
+	fn propose_whitelist_change(u: u32, ) -> Weight {
+		Weight::from_parts(64_000_000, 4565)
+			.saturating_add(Weight::from_parts(8_000, 0).saturating_mul(u.into()))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: Miner WhitelistProposals (r:1 w:1)
+	/// Storage: Miner ProposalVotes (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	fn vote_whitelist_proposal() -> Weight {
+		Weight::from_parts(60_000_000, 4565)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: Miner WhitelistProposals (r:1 w:0)
+	/// Storage: Miner ProposalVotes (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	fn unlock_proposal_vote() -> Weight {
+		Weight::from_parts(50_000_000, 4565)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: Miner Miners (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	fn bond_extra() -> Weight {
+		Weight::from_parts(70_000_000, 3593)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: Miner Miners (r:1 w:0)
+	/// Storage: Miner Chilled (r:0 w:1)
+	fn chill() -> Weight {
+		Weight::from_parts(30_000_000, 3539)
 			.saturating_add(T::DbWeight::get().reads(1_u64))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
+	/// Storage: Miner Miners (r:1 w:1)
+	/// Storage: Miner Unbonding (r:1 w:1)
+	/// Storage: Miner Chilled (r:1 w:1)
+	fn unbond() -> Weight {
+		Weight::from_parts(60_000_000, 4948)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: Miner Miners (r:1 w:1)
+	/// Storage: Miner PendingSubmissionCount (r:1 w:0)
+	/// Storage: Miner Unbonding (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	/// Storage: Miner Chilled (r:0 w:1)
+	/// Storage: Miner ChallengeableUntil (r:1 w:1)
+	fn withdraw_unbonded() -> Weight {
+		Weight::from_parts(80_000_000, 4948)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: Validator Validators (r:2 w:1)
+	/// Storage: Miner PendingSubmissions (r:1 w:0)
+	/// Storage: Miner Committees (r:1 w:0)
+	/// Storage: Miner VoteCommitments (r:6 w:1)
+	/// Storage: Miner Votes (r:6 w:0)
+	fn commit_vote() -> Weight {
+		Weight::from_parts(90_000_000, 16842)
+			.saturating_add(T::DbWeight::get().reads(16_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: Miner PendingSubmissions (r:1 w:1)
+	/// Storage: Miner VoteCommitments (r:2 w:6)
+	/// Storage: Miner Committees (r:1 w:1)
+	/// Storage: Miner Whitelist (r:25 w:0)
+	/// Storage: Miner Votes (r:5 w:6)
+	/// Storage: Miner PendingSubmissionCount (r:1 w:1)
+	/// Storage: Validator Reputation (r:5 w:5)
+	/// Storage: Miner Disputes (r:1 w:1)
+	/// Storage: Miner Attestations (r:1 w:1)
+	/// Storage: Miner SubmissionStatuses (r:0 w:1)
+	/// Storage: Miner History (r:1 w:1)
+	/// Storage: System Account (r:6 w:6)
+	/// Storage: Miner CrawlTasks (r:1 w:1)
+	/// Storage: Miner Submissions (r:0 w:1)
+	/// Storage: Miner ChallengeDeadlines (r:0 w:1)
+	/// Storage: Miner ApprovingCommittees (r:0 w:1)
+	fn reveal_vote() -> Weight {
+		Weight::from_parts(320_000_000, 61204)
+			.saturating_add(T::DbWeight::get().reads(50_u64))
+			.saturating_add(T::DbWeight::get().writes(34_u64))
+	}
+	/// Storage: Miner Disputes (r:1 w:0)
+	/// Storage: Miner Submissions (r:1 w:1)
+	/// Storage: Miner SubmissionStatuses (r:0 w:1)
+	/// Storage: Miner History (r:1 w:1)
+	/// Storage: Miner CrawlTasks (r:1 w:1)
+	/// Storage: Miner ChallengeDeadlines (r:0 w:1)
+	/// Storage: Miner Miners (r:1 w:1)
+	/// Storage: Miner Unbonding (r:1 w:1)
+	/// Storage: System Account (r:2 w:2)
+	/// Storage: Miner ApprovingCommittees (r:0 w:1)
+	fn report_mismatch() -> Weight {
+		Weight::from_parts(144_000_000, 52312)
+			.saturating_add(T::DbWeight::get().reads(8_u64))
+			.saturating_add(T::DbWeight::get().writes(10_u64))
+	}
+	/// Storage: Miner PendingSubmissions (r:1 w:1)
+	/// Storage: Miner Committees (r:1 w:1)
+	/// Storage: Miner VoteCommitments (r:5 w:5)
+	/// Storage: Miner Votes (r:5 w:5)
+	/// Storage: Miner PendingSubmissionCount (r:1 w:1)
+	/// Storage: Validator Validators (r:5 w:5)
+	/// Storage: Validator Reputation (r:5 w:5)
+	/// Storage: Miner Disputes (r:1 w:1)
+	/// Storage: Miner Attestations (r:1 w:1)
+	/// Storage: Miner Submissions (r:1 w:1)
+	/// Storage: Miner SubmissionStatuses (r:0 w:1)
+	/// Storage: Miner History (r:1 w:1)
+	/// Storage: System Account (r:7 w:7)
+	/// Storage: Miner CrawlTasks (r:1 w:1)
+	/// Storage: Miner Whitelist (r:25 w:0)
+	/// Storage: Treasury Budget (r:1 w:1)
+	/// Storage: Miner ChallengeDeadlines (r:0 w:1)
+	/// Storage: Miner ApprovingCommittees (r:0 w:1)
+	fn finalize_submission() -> Weight {
+		Weight::from_parts(300_000_000, 61204)
+			.saturating_add(T::DbWeight::get().reads(62_u64))
+			.saturating_add(T::DbWeight::get().writes(40_u64))
+	}
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
-	/// Storage: Template Something (r:0 w:1)
-	/// Proof: Template Something (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
-	fn do_something() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `0`
-		//  Estimated: `0`
-		// Minimum execution time: 8_000_000 picoseconds.
-		Weight::from_parts(9_000_000, 0)
+	fn register_miner() -> Weight {
+		Weight::from_parts(66_000_000, 3593)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	fn submit_hash(u: u32, v: u32, ) -> Weight {
+		Weight::from_parts(172_000_000, 49230)
+			.saturating_add(Weight::from_parts(22_000, 0).saturating_mul(u.into()))
+			.saturating_add(Weight::from_parts(18_000_000, 0).saturating_mul(v.into()))
+			.saturating_add(RocksDbWeight::get().reads(37_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(v.into())))
+			.saturating_add(RocksDbWeight::get().writes(9_u64))
+			.saturating_add(Weight::from_parts(0, 65).saturating_mul(u.into()))
+			.saturating_add(Weight::from_parts(0, 5_110).saturating_mul(v.into()))
+	}
+	fn add_to_whitelist(u: u32, ) -> Weight {
+		Weight::from_parts(40_000_000, 4565)
+			.saturating_add(Weight::from_parts(8_000, 0).saturating_mul(u.into()))
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	fn remove_from_whitelist(u: u32, ) -> Weight {
+		Weight::from_parts(30_000_000, 3995)
+			.saturating_add(Weight::from_parts(4_000, 0).saturating_mul(u.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
-	/// Storage: Template Something (r:1 w:1)
-	/// Proof: Template Something (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
-	fn cause_error() -> Weight {
-		// Proof Size summary in bytes:
-		//  Measured:  `32`
-		//  Estimated: `1489`
-		// Minimum execution time: 6_000_000 picoseconds.
-		Weight::from_parts(6_000_000, 1489)
+	fn batch_add_to_whitelist(n: u32, ) -> Weight {
+		Weight::from_parts(30_000_000, 990)
+			.saturating_add(Weight::from_parts(54_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 3575).saturating_mul(n.into()))
+	}
+	fn batch_remove_from_whitelist(n: u32, ) -> Weight {
+		Weight::from_parts(32_000_000, 990)
+			.saturating_add(Weight::from_parts(34_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 3575).saturating_mul(n.into()))
+	}
+	fn update_whitelist_entry(u: u32, ) -> Weight {
+		Weight::from_parts(34_000_000, 4515)
+			.saturating_add(Weight::from_parts(4_000, 0).saturating_mul(u.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn claim_task(u: u32, ) -> Weight {
+		Weight::from_parts(66_000_000, 4565)
+			.saturating_add(Weight::from_parts(16_000, 0).saturating_mul(u.into()))
+			.saturating_add(RocksDbWeight::get().reads(28_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn commit_submission() -> Weight {
+		Weight::from_parts(60_000_000, 11671)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	fn reveal_submission(u: u32, v: u32, ) -> Weight {
+		Weight::from_parts(216_000_000, 52820)
+			.saturating_add(Weight::from_parts(24_000, 0).saturating_mul(u.into()))
+			.saturating_add(Weight::from_parts(18_000_000, 0).saturating_mul(v.into()))
+			.saturating_add(RocksDbWeight::get().reads(42_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(v.into())))
+			.saturating_add(RocksDbWeight::get().writes(14_u64))
+			.saturating_add(Weight::from_parts(0, 67).saturating_mul(u.into()))
+			.saturating_add(Weight::from_parts(0, 5_110).saturating_mul(v.into()))
+	}
+	fn reassign_committee(v: u32, ) -> Weight {
+		Weight::from_parts(86_000_000, 10119)
+			.saturating_add(Weight::from_parts(18_000_000, 0).saturating_mul(v.into()))
+			.saturating_add(RocksDbWeight::get().reads(13_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(v.into())))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+			.saturating_add(Weight::from_parts(0, 5_110).saturating_mul(v.into()))
+	}
+	fn challenge_submission(v: u32, ) -> Weight {
+		Weight::from_parts(122_000_000, 14583)
+			.saturating_add(Weight::from_parts(18_000_000, 0).saturating_mul(v.into()))
+			.saturating_add(RocksDbWeight::get().reads(13_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(v.into())))
+			.saturating_add(RocksDbWeight::get().writes(9_u64))
+			.saturating_add(Weight::from_parts(0, 5_110).saturating_mul(v.into()))
+	}
+	fn propose_whitelist_change(u: u32, ) -> Weight {
+		Weight::from_parts(64_000_000, 4565)
+			.saturating_add(Weight::from_parts(8_000, 0).saturating_mul(u.into()))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	fn vote_whitelist_proposal() -> Weight {
+		Weight::from_parts(60_000_000, 4565)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	fn unlock_proposal_vote() -> Weight {
+		Weight::from_parts(50_000_000, 4565)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	fn bond_extra() -> Weight {
+		Weight::from_parts(70_000_000, 3593)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	fn chill() -> Weight {
+		Weight::from_parts(30_000_000, 3539)
 			.saturating_add(RocksDbWeight::get().reads(1_u64))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
+	fn unbond() -> Weight {
+		Weight::from_parts(60_000_000, 4948)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	fn withdraw_unbonded() -> Weight {
+		Weight::from_parts(80_000_000, 4948)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	fn commit_vote() -> Weight {
+		Weight::from_parts(90_000_000, 16842)
+			.saturating_add(RocksDbWeight::get().reads(16_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	fn reveal_vote() -> Weight {
+		Weight::from_parts(320_000_000, 61204)
+			.saturating_add(RocksDbWeight::get().reads(50_u64))
+			.saturating_add(RocksDbWeight::get().writes(34_u64))
+	}
+	fn report_mismatch() -> Weight {
+		Weight::from_parts(144_000_000, 52312)
+			.saturating_add(RocksDbWeight::get().reads(8_u64))
+			.saturating_add(RocksDbWeight::get().writes(10_u64))
+	}
+	fn finalize_submission() -> Weight {
+		Weight::from_parts(300_000_000, 61204)
+			.saturating_add(RocksDbWeight::get().reads(62_u64))
+			.saturating_add(RocksDbWeight::get().writes(40_u64))
+	}
 }
@@ -1,7 +1,10 @@
+use std::path::Path;
+
 use parachain_template_runtime as runtime;
 use sc_chain_spec::{ChainSpecExtension, ChainSpecGroup};
 use sc_service::ChainType;
 use serde::{Deserialize, Serialize};
+use sp_genesis_builder::PresetId;
 
 /// Specialized `ChainSpec` for the normal parachain runtime.
 pub type ChainSpec = sc_service::GenericChainSpec<Extensions>;
@@ -24,14 +27,76 @@ impl Extensions {
 	}
 }
 
-pub fn development_config() -> ChainSpec {
+/// The format of `whitelist.json`, as written by `Whitelist_tools/list_to_json.py`.
+#[derive(Debug, Deserialize)]
+struct WhitelistFile {
+	whitelist: Vec<WhitelistEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WhitelistEntry {
+	#[allow(dead_code)]
+	name: String,
+	url: String,
+	status: String,
+}
+
+/// Only entries the scraper could reach are whitelisted.
+const ACCESSIBLE: &str = "Accessible";
+
+/// Read the accessible URLs from a `whitelist.json` file. Every URL must be a valid whitelist
+/// rule, as the genesis build would fail on it otherwise.
+pub fn load_whitelist(path: &Path) -> Result<Vec<String>, String> {
+	let file = std::fs::File::open(path)
+		.map_err(|e| format!("Error opening whitelist file `{}`: {}", path.display(), e))?;
+	let whitelist: WhitelistFile = serde_json::from_reader(std::io::BufReader::new(file))
+		.map_err(|e| format!("Error parsing whitelist file `{}`: {}", path.display(), e))?;
+	let urls: Vec<String> = whitelist
+		.whitelist
+		.into_iter()
+		.filter(|entry| entry.status == ACCESSIBLE)
+		.map(|entry| entry.url)
+		.collect();
+	for url in &urls {
+		pallet_miner::Pallet::<runtime::Runtime>::whitelist_keys(url.as_bytes()).map_err(|e| {
+			format!("Invalid whitelist rule `{}` in `{}`: {:?}", url, path.display(), e)
+		})?;
+	}
+	Ok(urls)
+}
+
+/// The runtime's genesis preset `name`, with the miner whitelist replaced by the entries of the
+/// given `whitelist.json` file.
+fn genesis_patch(name: &str, whitelist: &Path) -> Result<serde_json::Value, String> {
+	let preset = runtime::genesis_config_presets::get_preset(&PresetId::from(name))
+		.ok_or_else(|| format!("Unknown genesis preset `{}`", name))?;
+	let mut patch: serde_json::Value =
+		serde_json::from_slice(&preset).map_err(|e| format!("Invalid genesis preset `{}`: {}", name, e))?;
+	let urls = load_whitelist(whitelist)?.into_iter().map(String::into_bytes).collect::<Vec<_>>();
+	sc_chain_spec::json_merge(&mut patch, serde_json::json!({ "miner": { "whitelist": urls } }));
+	Ok(patch)
+}
+
+/// Use the genesis preset `name`, seeding the miner whitelist from `whitelist` if given.
+fn with_genesis(
+	builder: sc_chain_spec::ChainSpecBuilder<Extensions>,
+	name: &str,
+	whitelist: Option<&Path>,
+) -> Result<sc_chain_spec::ChainSpecBuilder<Extensions>, String> {
+	Ok(match whitelist {
+		Some(whitelist) => builder.with_genesis_config_patch(genesis_patch(name, whitelist)?),
+		None => builder.with_genesis_config_preset_name(name),
+	})
+}
+
+pub fn development_config(whitelist: Option<&Path>) -> Result<ChainSpec, String> {
 	// Give your base currency a unit name and decimal places
 	let mut properties = sc_chain_spec::Properties::new();
 	properties.insert("tokenSymbol".into(), "UNIT".into());
 	properties.insert("tokenDecimals".into(), 12.into());
 	properties.insert("ss58Format".into(), 42.into());
 
-	ChainSpec::builder(
+	let builder = ChainSpec::builder(
 		runtime::WASM_BINARY.expect("WASM binary was not built, please build it!"),
 		Extensions {
 			relay_chain: "rococo-local".into(),
@@ -41,12 +106,12 @@ pub fn development_config() -> ChainSpec {
 	)
 	.with_name("Development")
 	.with_id("dev")
-	.with_chain_type(ChainType::Development)
-	.with_genesis_config_preset_name(sp_genesis_builder::DEV_RUNTIME_PRESET)
-	.build()
+	.with_chain_type(ChainType::Development);
+
+	Ok(with_genesis(builder, sp_genesis_builder::DEV_RUNTIME_PRESET, whitelist)?.build())
 }
 
-pub fn local_testnet_config() -> ChainSpec {
+pub fn local_testnet_config(whitelist: Option<&Path>) -> Result<ChainSpec, String> {
 	// Give your base currency a unit name and decimal places
 	let mut properties = sc_chain_spec::Properties::new();
 	properties.insert("tokenSymbol".into(), "UNIT".into());
@@ -54,7 +119,7 @@ pub fn local_testnet_config() -> ChainSpec {
 	properties.insert("ss58Format".into(), 42.into());
 
 	#[allow(deprecated)]
-	ChainSpec::builder(
+	let builder = ChainSpec::builder(
 		runtime::WASM_BINARY.expect("WASM binary was not built, please build it!"),
 		Extensions {
 			relay_chain: "rococo-local".into(),
@@ -65,8 +130,8 @@ pub fn local_testnet_config() -> ChainSpec {
 	.with_name("Local Testnet")
 	.with_id("local_testnet")
 	.with_chain_type(ChainType::Local)
-	.with_genesis_config_preset_name(sc_chain_spec::LOCAL_TESTNET_RUNTIME_PRESET)
 	.with_protocol_id("template-local")
-	.with_properties(properties)
-	.build()
+	.with_properties(properties);
+
+	Ok(with_genesis(builder, sc_chain_spec::LOCAL_TESTNET_RUNTIME_PRESET, whitelist)?.build())
 }
@@ -76,6 +76,13 @@ pub struct Cli {
 	#[arg(long)]
 	pub no_hardware_benchmarks: bool,
 
+	/// Seed the miner whitelist of the built-in chain specs from a `whitelist.json` file.
+	///
+	/// Only entries with the status `Accessible` are included. Chain specs loaded from a file
+	/// are not affected.
+	#[arg(long, global = true, value_name = "PATH")]
+	pub whitelist: Option<PathBuf>,
+
 	/// Relay chain arguments
 	#[arg(raw = true)]
 	pub relay_chain_args: Vec<String>,
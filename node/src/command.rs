@@ -15,11 +15,14 @@ use crate::{
 	service::new_partial,
 };
 
-fn load_spec(id: &str) -> std::result::Result<Box<dyn ChainSpec>, String> {
+fn load_spec(
+	id: &str,
+	whitelist: Option<&std::path::Path>,
+) -> std::result::Result<Box<dyn ChainSpec>, String> {
 	Ok(match id {
-		"dev" => Box::new(chain_spec::development_config()),
-		"template-rococo" => Box::new(chain_spec::local_testnet_config()),
-		"" | "local" => Box::new(chain_spec::local_testnet_config()),
+		"dev" => Box::new(chain_spec::development_config(whitelist)?),
+		"template-rococo" => Box::new(chain_spec::local_testnet_config(whitelist)?),
+		"" | "local" => Box::new(chain_spec::local_testnet_config(whitelist)?),
 		path => Box::new(chain_spec::ChainSpec::from_json_file(std::path::PathBuf::from(path))?),
 	})
 }
@@ -56,7 +59,7 @@ impl SubstrateCli for Cli {
 	}
 
 	fn load_spec(&self, id: &str) -> std::result::Result<Box<dyn sc_service::ChainSpec>, String> {
-		load_spec(id)
+		load_spec(id, self.whitelist.as_deref())
 	}
 }
 
@@ -26,14 +26,16 @@
 mod xcm_config;
 
 // Substrate and Polkadot dependencies
-use cumulus_pallet_parachain_system::RelayNumberMonotonicallyIncreases;
-use cumulus_primitives_core::{AggregateMessageOrigin, ParaId};
+use codec::Encode;
+use cumulus_pallet_parachain_system::{RelayChainStateProof, RelayNumberMonotonicallyIncreases};
+use cumulus_primitives_core::{relay_chain, AggregateMessageOrigin, ParaId};
 use frame_support::{
 	derive_impl,
 	dispatch::DispatchClass,
 	parameter_types,
 	traits::{
-		ConstBool, ConstU32, ConstU64, ConstU8, EitherOfDiverse, TransformOrigin, VariantCountOf,
+		ConstBool, ConstU32, ConstU64, ConstU8, EitherOfDiverse, Randomness, TransformOrigin,
+		VariantCountOf,
 	},
 	weights::{ConstantMultiplier, Weight},
 	PalletId,
@@ -48,7 +50,11 @@ use polkadot_runtime_common::{
 	xcm_sender::NoPriceForMessageDelivery, BlockHashCount, SlowAdjustingFeeUpdate,
 };
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
-use sp_runtime::Perbill;
+use sp_runtime::{
+	generic::{self, Era},
+	traits::{AccountIdConversion, BlakeTwo256, Extrinsic as ExtrinsicT, Hash as HashT, Verify},
+	MultiAddress, Perbill, SaturatedConversion,
+};
 use sp_version::RuntimeVersion;
 use xcm::latest::prelude::BodyId;
 
@@ -56,11 +62,13 @@ use xcm::latest::prelude::BodyId;
 use super::{
 	weights::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight},
 	AccountId, Aura, Balance, Balances, Block, BlockNumber, CollatorSelection, ConsensusHook, Hash,
-	MessageQueue, Nonce, PalletInfo, ParachainSystem, Runtime, RuntimeCall, RuntimeEvent,
-	RuntimeFreezeReason, RuntimeHoldReason, RuntimeOrigin, RuntimeTask, Session, SessionKeys,
-	System, WeightToFee, XcmpQueue, AVERAGE_ON_INITIALIZE_RATIO, EXISTENTIAL_DEPOSIT, HOURS,
-	MAXIMUM_BLOCK_WEIGHT, MICRO_UNIT, NORMAL_DISPATCH_RATIO, SLOT_DURATION, VERSION,
+	MessageQueue, Nonce, PalletInfo, ParachainInfo, ParachainSystem, Runtime, RuntimeCall,
+	RuntimeEvent, RuntimeFreezeReason, RuntimeHoldReason, RuntimeOrigin, RuntimeTask, Session,
+	SessionKeys, System, Treasury, Validator, WeightToFee, XcmpQueue, AVERAGE_ON_INITIALIZE_RATIO,
+	DAYS, EXISTENTIAL_DEPOSIT, HOURS, MAXIMUM_BLOCK_WEIGHT, MICRO_UNIT, MILLI_UNIT, MINUTES,
+	NORMAL_DISPATCH_RATIO, SLOT_DURATION, UNIT, VERSION,
 };
+use super::{Signature, SignedExtra, UncheckedExtrinsic};
 use xcm_config::{RelayLocation, XcmOriginToTransactDispatchOrigin};
 
 parameter_types! {
@@ -308,3 +316,213 @@ impl pallet_parachain_template::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = pallet_parachain_template::weights::SubstrateWeight<Runtime>;
 }
+
+parameter_types! {
+	pub const MinerPalletId: PalletId = PalletId(*b"ew/miner");
+	/// Long enough for percent-encoded Wikipedia article URLs.
+	pub const MaxUrlLength: u32 = 512;
+	pub const SubmissionFee: Balance = 10 * MILLI_UNIT;
+	pub TreasuryAccount: AccountId = TreasuryPalletId::get().into_account_truncating();
+	pub const ApprovalQuorum: u32 = 2;
+	pub const VotingPeriod: BlockNumber = 10 * MINUTES;
+	pub const MaxSubmissionsPerBlock: u32 = 256;
+	pub const MinMinerDeposit: Balance = UNIT;
+	pub const MinerUnbondingPeriod: BlockNumber = 7 * DAYS;
+	pub const MaxUnbondingChunks: u32 = 16;
+	pub const MinerSlashFraction: Perbill = Perbill::from_percent(10);
+	pub const MaxSnapshotsPerUrl: u32 = 64;
+	/// Fits CIDv1 strings and Arweave transaction ids.
+	pub const MaxCidLength: u32 = 128;
+	pub const MaxMimeTypeLength: u32 = 128;
+	pub const WhitelistProposalDeposit: Balance = 10 * UNIT;
+	pub const WhitelistVotingPeriod: BlockNumber = 7 * DAYS;
+	pub const WhitelistMinTurnout: Balance = 1_000 * UNIT;
+	pub const WhitelistApprovalThreshold: Perbill = Perbill::from_percent(60);
+	pub const MaxWhitelistProposalsPerBlock: u32 = 16;
+	pub const MaxWhitelistProposalVoters: u32 = 1024;
+	pub const MaxWhitelistBatch: u32 = 256;
+	pub const DefaultRearchiveInterval: BlockNumber = DAYS;
+	pub const NormalPriorityReward: Perbill = Perbill::from_percent(50);
+	pub const LowPriorityReward: Perbill = Perbill::from_percent(20);
+	pub const UnchangedReward: Perbill = Perbill::from_percent(10);
+	pub const UnchangedFee: Balance = 2 * MILLI_UNIT;
+	pub const TaskClaimDuration: BlockNumber = HOURS;
+	pub const RevealPeriod: BlockNumber = 10 * MINUTES;
+	pub const MaxCommitmentsPerBlock: u32 = 256;
+	pub const CommitteeSize: u32 = 5;
+	pub const AssignmentTimeout: BlockNumber = 3 * MINUTES;
+	pub const ChallengeBond: Balance = UNIT;
+	pub const ChallengePeriod: BlockNumber = 7 * DAYS;
+	pub const ChallengeReward: Perbill = Perbill::from_percent(50);
+}
+
+/// Randomness from the BABE VRF output of the relay parent, read from the relay chain state
+/// proof. Neither collators nor relay chain validators can choose it without giving up their
+/// block. Only available once the parachain inherent has been applied, so not from
+/// `on_initialize`, and `None` otherwise.
+pub struct RelayChainRandomness;
+
+impl Randomness<Option<Hash>, BlockNumber> for RelayChainRandomness {
+	fn random(subject: &[u8]) -> (Option<Hash>, BlockNumber) {
+		let randomness = ParachainSystem::validation_data()
+			.zip(cumulus_pallet_parachain_system::RelayStateProof::<Runtime>::get())
+			.and_then(|(data, proof)| {
+				RelayChainStateProof::new(ParachainInfo::parachain_id(), data.relay_parent_storage_root, proof)
+					.ok()
+			})
+			.and_then(|proof| {
+				proof
+					.read_optional_entry::<Option<Hash>>(relay_chain::well_known_keys::CURRENT_BLOCK_RANDOMNESS)
+					.ok()
+			})
+			.flatten()
+			.flatten();
+		// Benchmarks run without a relay chain state proof.
+		#[cfg(feature = "runtime-benchmarks")]
+		let randomness = randomness.or(Some(Hash::zero()));
+		if randomness.is_none() {
+			log::warn!("Relay chain randomness is unavailable at block {}", System::block_number());
+		}
+		let output = randomness.map(|randomness| BlakeTwo256::hash_of(&(subject, randomness)));
+		(output, System::block_number())
+	}
+}
+
+impl frame_system::offchain::SigningTypes for Runtime {
+	type Public = <Signature as Verify>::Signer;
+	type Signature = Signature;
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
+where
+	RuntimeCall: From<C>,
+{
+	type OverarchingCall = RuntimeCall;
+	type Extrinsic = UncheckedExtrinsic;
+}
+
+/// Lets offchain workers sign transactions, such as the votes of local validators.
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Runtime
+where
+	RuntimeCall: From<LocalCall>,
+{
+	fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: RuntimeCall,
+		public: <Signature as Verify>::Signer,
+		account: AccountId,
+		nonce: Nonce,
+	) -> Option<(RuntimeCall, <UncheckedExtrinsic as ExtrinsicT>::SignaturePayload)> {
+		let period = BlockHashCount::get().checked_next_power_of_two().map(|c| c / 2).unwrap_or(2) as u64;
+		let current_block = System::block_number().saturated_into::<u64>().saturating_sub(1);
+		let extra: SignedExtra = (
+			frame_system::CheckNonZeroSender::<Runtime>::new(),
+			frame_system::CheckSpecVersion::<Runtime>::new(),
+			frame_system::CheckTxVersion::<Runtime>::new(),
+			frame_system::CheckGenesis::<Runtime>::new(),
+			frame_system::CheckEra::<Runtime>::from(Era::mortal(period, current_block)),
+			frame_system::CheckNonce::<Runtime>::from(nonce),
+			frame_system::CheckWeight::<Runtime>::new(),
+			pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(0),
+			cumulus_primitives_storage_weight_reclaim::StorageWeightReclaim::<Runtime>::new(),
+			frame_metadata_hash_extension::CheckMetadataHash::<Runtime>::new(false),
+		);
+		let raw_payload = generic::SignedPayload::new(call, extra)
+			.map_err(|err| log::warn!("Unable to create signed payload: {:?}", err))
+			.ok()?;
+		let signature = raw_payload.using_encoded(|payload| C::sign(payload, public))?;
+		let (call, extra, _) = raw_payload.deconstruct();
+		Some((call, (MultiAddress::Id(account), signature, extra)))
+	}
+}
+
+/// Configure the EverWeb miner pallet in miner.
+impl pallet_miner::Config for Runtime {
+	type WeightInfo = pallet_miner::weights::SubstrateWeight<Runtime>;
+	type Currency = Balances;
+	type SubmissionFee = SubmissionFee;
+	type RewardPool = TreasuryAccount;
+	type RuntimeEvent = RuntimeEvent;
+	type PalletId = MinerPalletId;
+	type MaxUrlLength = MaxUrlLength;
+	type ApprovalQuorum = ApprovalQuorum;
+	type VotingPeriod = VotingPeriod;
+	type MaxSubmissionsPerBlock = MaxSubmissionsPerBlock;
+	type MinMinerDeposit = MinMinerDeposit;
+	type UnbondingPeriod = MinerUnbondingPeriod;
+	type MaxUnbondingChunks = MaxUnbondingChunks;
+	type SlashFraction = MinerSlashFraction;
+	type Rewards = Treasury;
+	type Validators = Validator;
+	type Randomness = RelayChainRandomness;
+	type CommitteeSize = CommitteeSize;
+	type AssignmentTimeout = AssignmentTimeout;
+	type MaxSnapshotsPerUrl = MaxSnapshotsPerUrl;
+	type MaxCidLength = MaxCidLength;
+	type MaxMimeTypeLength = MaxMimeTypeLength;
+	type WhitelistOrigin = EnsureRoot<AccountId>;
+	type MaxWhitelistBatch = MaxWhitelistBatch;
+	type DefaultRearchiveInterval = DefaultRearchiveInterval;
+	type NormalPriorityReward = NormalPriorityReward;
+	type LowPriorityReward = LowPriorityReward;
+	type UnchangedReward = UnchangedReward;
+	type UnchangedFee = UnchangedFee;
+	type ProposalDeposit = WhitelistProposalDeposit;
+	type ProposalVotingPeriod = WhitelistVotingPeriod;
+	type ProposalMinTurnout = WhitelistMinTurnout;
+	type ProposalApprovalThreshold = WhitelistApprovalThreshold;
+	type MaxProposalsPerBlock = MaxWhitelistProposalsPerBlock;
+	type MaxProposalVoters = MaxWhitelistProposalVoters;
+	type TaskClaimDuration = TaskClaimDuration;
+	type RevealPeriod = RevealPeriod;
+	type MaxCommitmentsPerBlock = MaxCommitmentsPerBlock;
+	type ChallengeBond = ChallengeBond;
+	type ChallengePeriod = ChallengePeriod;
+	type ChallengeReward = ChallengeReward;
+	type AuthorityId = pallet_miner::offchain::crypto::ValidatorAuthId;
+}
+
+parameter_types! {
+	pub const TreasuryPalletId: PalletId = PalletId(*b"ew/trsry");
+	/// The README caps rewards at one token per archived page.
+	pub const MaxRewardPerPage: Balance = UNIT;
+	pub const RewardFee: Perbill = Perbill::from_percent(10);
+}
+
+/// Configure the EverWeb treasury pallet in treasury.
+impl pallet_treasury::Config for Runtime {
+	type Currency = Balances;
+	type RuntimeEvent = RuntimeEvent;
+	type PalletId = TreasuryPalletId;
+	type MaxRewardPerPage = MaxRewardPerPage;
+	type RewardFee = RewardFee;
+	type WeightInfo = pallet_treasury::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const MinValidatorBond: Balance = 10 * UNIT;
+	pub const ValidatorUnbondingPeriod: BlockNumber = 7 * DAYS;
+	pub const InitialReputation: Perbill = Perbill::from_percent(50);
+	pub const ReputationReward: Perbill = Perbill::from_percent(1);
+	pub const ReputationPenalty: Perbill = Perbill::from_percent(5);
+	pub const MaxValidators: u32 = 1_000;
+	pub const MaxMissedVotes: u32 = 10;
+	pub const InactivityPenalty: Perbill = Perbill::from_percent(10);
+	pub const InactivitySlash: Perbill = Perbill::from_percent(1);
+}
+
+/// Configure the EverWeb validator pallet in validator.
+impl pallet_validator::Config for Runtime {
+	type Currency = Balances;
+	type RuntimeEvent = RuntimeEvent;
+	type MinValidatorBond = MinValidatorBond;
+	type UnbondingPeriod = ValidatorUnbondingPeriod;
+	type InitialReputation = InitialReputation;
+	type ReputationReward = ReputationReward;
+	type ReputationPenalty = ReputationPenalty;
+	type MaxValidators = MaxValidators;
+	type MaxMissedVotes = MaxMissedVotes;
+	type InactivityPenalty = InactivityPenalty;
+	type InactivitySlash = InactivitySlash;
+	type RewardPool = TreasuryAccount;
+	type WeightInfo = pallet_validator::weights::SubstrateWeight<Runtime>;
+}
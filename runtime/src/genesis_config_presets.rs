@@ -1,18 +1,31 @@
 use cumulus_primitives_core::ParaId;
 
 use crate::{
-	AccountId, BalancesConfig, CollatorSelectionConfig, ParachainInfoConfig, PolkadotXcmConfig,
-	RuntimeGenesisConfig, SessionConfig, SessionKeys, SudoConfig, EXISTENTIAL_DEPOSIT,
+	configs::TreasuryPalletId, AccountId, BalancesConfig, CollatorSelectionConfig, MinerConfig,
+	ParachainInfoConfig, PolkadotXcmConfig, RuntimeGenesisConfig, SessionConfig, SessionKeys,
+	SudoConfig, EXISTENTIAL_DEPOSIT,
 };
 use alloc::{vec, vec::Vec};
 use parachains_common::{genesis_config_helpers::*, AuraId};
 use serde_json::Value;
 use sp_core::sr25519;
 use sp_genesis_builder::PresetId;
+use sp_runtime::traits::AccountIdConversion;
 
 /// The default XCM version to set in genesis config.
 const SAFE_XCM_VERSION: u32 = xcm::prelude::XCM_VERSION;
 
+/// The sites from `whitelist.json` that miners may archive from genesis, including every
+/// Wikipedia language subdomain.
+fn everweb_whitelist() -> Vec<Vec<u8>> {
+	vec![
+		b"https://*.wikipedia.org".to_vec(),
+		b"https://www.bbc.com/news".to_vec(),
+		b"https://www.si.edu".to_vec(),
+		b"https://www.data.gov".to_vec(),
+	]
+}
+
 /// Generate the session keys from individual elements.
 ///
 /// The input must be a tuple of individual keys (a single arg for now since we have just one key).
@@ -26,11 +39,14 @@ fn testnet_genesis(
 	root: AccountId,
 	id: ParaId,
 ) -> Value {
+	// The treasury account is endowed as well so that it can pay out archiving rewards.
+	let treasury_account: AccountId = TreasuryPalletId::get().into_account_truncating();
 	let config = RuntimeGenesisConfig {
 		balances: BalancesConfig {
 			balances: endowed_accounts
 				.iter()
 				.cloned()
+				.chain(core::iter::once(treasury_account))
 				.map(|k| (k, 1u128 << 60))
 				.collect::<Vec<_>>(),
 		},
@@ -58,6 +74,7 @@ fn testnet_genesis(
 			..Default::default()
 		},
 		sudo: SudoConfig { key: Some(root) },
+		miner: MinerConfig { whitelist: everweb_whitelist(), ..Default::default() },
 		..Default::default()
 	};
 
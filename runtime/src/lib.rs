@@ -10,7 +10,7 @@ pub mod apis;
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarks;
 pub mod configs;
-mod genesis_config_presets;
+pub mod genesis_config_presets;
 mod weights;
 
 extern crate alloc;
@@ -95,7 +95,10 @@ pub type UncheckedExtrinsic =
 ///
 /// This can be a tuple of types, each implementing `OnRuntimeUpgrade`.
 #[allow(unused_parens)]
-type Migrations = ();
+type Migrations = (
+	pallet_treasury::migrations::v1::MigrateToV1<Runtime>,
+	pallet_miner::migrations::v2::MigrateToV2<Runtime>,
+);
 
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
@@ -308,6 +311,14 @@ mod runtime {
 	// Template
 	#[runtime::pallet_index(50)]
 	pub type TemplatePallet = pallet_parachain_template;
+
+	// EverWeb
+	#[runtime::pallet_index(60)]
+	pub type Miner = pallet_miner;
+	#[runtime::pallet_index(61)]
+	pub type Treasury = pallet_treasury;
+	#[runtime::pallet_index(62)]
+	pub type Validator = pallet_validator;
 }
 
 #[docify::export(register_validate_block)]
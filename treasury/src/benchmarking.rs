@@ -1,42 +1,66 @@
+//! Benchmarking setup for pallet-treasury
 #![cfg(feature = "runtime-benchmarks")]
 
 use super::*;
-use frame::deps::frame_benchmarking::v2::*;
+use frame_benchmarking::v2::*;
+use frame_system::RawOrigin;
+use sp_runtime::traits::Bounded;
+
+/// A balance large enough for any benchmark while leaving room for transfers into the account.
+fn large_balance<T: Config>() -> BalanceOf<T> {
+    BalanceOf::<T>::max_value() / 4u32.into()
+}
+
+/// Fill the treasury account so payouts never run out of funds.
+fn fund_treasury<T: Config>() {
+    T::Currency::make_free_balance_be(&Pallet::<T>::account_id(), large_balance::<T>());
+}
 
 #[benchmarks]
 mod benchmarks {
     use super::*;
-    #[cfg(test)]
-    use crate::pallet::Pallet as Template;
-    use frame_system::RawOrigin;
 
     #[benchmark]
-    fn do_something() {
+    fn deposit_funds() {
         let caller: T::AccountId = whitelisted_caller();
+        T::Currency::make_free_balance_be(&caller, large_balance::<T>());
+        fund_treasury::<T>();
+        let amount = T::Currency::minimum_balance().saturating_mul(100u32.into());
+        let before = Pallet::<T>::treasury_balance();
+
         #[extrinsic_call]
-        do_something(RawOrigin::Signed(caller), 100);
+        deposit_funds(RawOrigin::Signed(caller), amount);
 
-        assert_eq!(
-            Something::<T>::get().map(|v| v.block_number),
-            Some(100u32.into())
-        );
+        assert_eq!(Pallet::<T>::treasury_balance(), before.saturating_add(amount));
     }
 
     #[benchmark]
-    fn cause_error() {
-        let caller: T::AccountId = whitelisted_caller();
-        Something::<T>::put(CompositeStruct {
-            block_number: 100u32.into(),
-            someone: caller.clone(),
-        });
+    fn transfer_funds() {
+        fund_treasury::<T>();
+        // A fresh recipient account has to be created.
+        let recipient: T::AccountId = account("recipient", 0, 0);
+        let amount = T::Currency::minimum_balance().saturating_mul(100u32.into());
+
+        #[extrinsic_call]
+        transfer_funds(RawOrigin::Root, recipient.clone(), amount);
+
+        assert_eq!(T::Currency::free_balance(&recipient), amount);
+    }
+
+    #[benchmark]
+    fn distribute_rewards() {
+        fund_treasury::<T>();
+        // Both recipients are fresh accounts that have to be created.
+        let miner: T::AccountId = account("miner", 0, 0);
+        let validator: T::AccountId = account("validator", 0, 0);
+        let reward = T::Currency::minimum_balance().saturating_mul(100u32.into());
+
         #[extrinsic_call]
-        cause_error(RawOrigin::Signed(caller));
+        distribute_rewards(RawOrigin::Root, miner.clone(), validator.clone(), reward, reward);
 
-        assert_eq!(
-            Something::<T>::get().map(|v| v.block_number),
-            Some(101u32.into())
-        );
+        assert_eq!(T::Currency::free_balance(&miner), reward);
+        assert_eq!(T::Currency::free_balance(&validator), reward);
     }
 
-    impl_benchmark_test_suite!(Template, crate::mock::new_test_ext(), crate::mock::Test);
+    impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
 }
@@ -2,19 +2,42 @@
 
 pub use pallet::*;
 
+pub mod migrations;
+pub mod weights;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
 use frame_support::{
     pallet_prelude::*,
     traits::{Currency, ExistenceRequirement, Get},
     PalletId,
 };
 use frame_system::pallet_prelude::*;
-use sp_runtime::traits::AccountIdConversion;
+use sp_runtime::{
+    traits::{AccountIdConversion, Saturating, Zero},
+    Perbill,
+};
 
-use log::debug;
+
+pub use weights::WeightInfo;
 
 // Type alias for balance using the Currency trait
 type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
+/// Pays archiving rewards out of the treasury without any privileged origin.
+pub trait RewardPayout<AccountId> {
+    /// Reward `miner` for an approved submission with `share` of the full per-page reward and
+    /// share the reward fee with the `validators` that approved it.
+    fn payout(miner: &AccountId, validators: &[AccountId], share: Perbill) -> DispatchResult;
+}
+
+impl<AccountId> RewardPayout<AccountId> for () {
+    fn payout(_miner: &AccountId, _validators: &[AccountId], _share: Perbill) -> DispatchResult {
+        Ok(())
+    }
+}
+
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
@@ -30,16 +53,27 @@ pub mod pallet {
         /// Pallet ID for treasury
         #[pallet::constant]
         type PalletId: Get<PalletId>;
+
+        /// Maximum reward paid for a single archived page
+        #[pallet::constant]
+        type MaxRewardPerPage: Get<BalanceOf<Self>>;
+
+        /// Share of each reward kept as a fee, split evenly between the validators and the
+        /// reward pool
+        #[pallet::constant]
+        type RewardFee: Get<Perbill>;
+
+        /// Weights for this pallet's dispatchables
+        type WeightInfo: WeightInfo;
     }
 
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     #[pallet::without_storage_info]
     pub struct Pallet<T>(_);
 
-    #[pallet::storage]
-    #[pallet::getter(fn treasury_balance)]
-    pub type TreasuryBalance<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
-
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
@@ -54,6 +88,12 @@ pub mod pallet {
             miner_reward: BalanceOf<T>,
             validator_reward: BalanceOf<T>,
         },
+        /// An approved submission has been rewarded
+        SubmissionRewarded {
+            miner: T::AccountId,
+            miner_reward: BalanceOf<T>,
+            validators_reward: BalanceOf<T>,
+        },
     }
 
     #[pallet::error]
@@ -62,13 +102,37 @@ pub mod pallet {
         InsufficientFunds,
     }
 
+    #[pallet::genesis_config]
+    #[derive(frame_support::DefaultNoBound)]
+    pub struct GenesisConfig<T: Config> {
+        #[serde(skip)]
+        pub _config: core::marker::PhantomData<T>,
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+        fn build(&self) {
+            // Make sure the treasury account exists so that it can receive any amount.
+            let account_id = Pallet::<T>::account_id();
+            let min = T::Currency::minimum_balance();
+            if T::Currency::free_balance(&account_id) < min {
+                let _ = T::Currency::make_free_balance_be(&account_id, min);
+            }
+        }
+    }
+
     #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        #[cfg(feature = "try-runtime")]
+        fn try_state(_n: BlockNumberFor<T>) -> Result<(), sp_runtime::TryRuntimeError> {
+            Self::do_try_state()
+        }
+    }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         #[pallet::call_index(0)]
-        #[pallet::weight(10_000)] // Static weight for deposit_funds
+        #[pallet::weight(T::WeightInfo::deposit_funds())]
         pub fn deposit_funds(origin: OriginFor<T>, amount: BalanceOf<T>) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
@@ -80,15 +144,13 @@ pub mod pallet {
                 ExistenceRequirement::KeepAlive,
             )?;
 
-            TreasuryBalance::<T>::mutate(|balance| *balance += amount);
-
             Self::deposit_event(Event::FundsDeposited { who, amount });
 
             Ok(())
         }
 
         #[pallet::call_index(1)]
-        #[pallet::weight(8_000)] // Static weight for transfer_funds
+        #[pallet::weight(T::WeightInfo::transfer_funds())]
         pub fn transfer_funds(
             origin: OriginFor<T>,
             recipient: T::AccountId,
@@ -97,7 +159,7 @@ pub mod pallet {
             ensure_root(origin)?;
 
             ensure!(
-                TreasuryBalance::<T>::get() >= amount,
+                Self::treasury_balance() >= amount,
                 Error::<T>::InsufficientFunds
             );
 
@@ -109,15 +171,13 @@ pub mod pallet {
                 ExistenceRequirement::KeepAlive,
             )?;
 
-            TreasuryBalance::<T>::mutate(|balance| *balance -= amount);
-
             Self::deposit_event(Event::FundsTransferred { recipient, amount });
 
             Ok(())
         }
 
         #[pallet::call_index(2)]
-        #[pallet::weight(12_000)] // Static weight for distribute_rewards
+        #[pallet::weight(T::WeightInfo::distribute_rewards())]
         pub fn distribute_rewards(
             origin: OriginFor<T>,
             miner: T::AccountId,
@@ -129,13 +189,8 @@ pub mod pallet {
 
             let total_reward = miner_reward + validator_reward;
 
-            debug!(
-                "Attempting to distribute rewards: miner_reward = {:?}, validator_reward = {:?}, total_reward = {:?}",
-                miner_reward, validator_reward, total_reward
-            );
-
             ensure!(
-                TreasuryBalance::<T>::get() >= total_reward,
+                Self::treasury_balance() >= total_reward,
                 Error::<T>::InsufficientFunds
             );
 
@@ -153,8 +208,6 @@ pub mod pallet {
                 ExistenceRequirement::AllowDeath,
             )?;
 
-            TreasuryBalance::<T>::mutate(|balance| *balance -= total_reward);
-
             Self::deposit_event(Event::RewardsDistributed {
                 miner,
                 validator,
@@ -171,6 +224,67 @@ pub mod pallet {
         pub fn account_id() -> T::AccountId {
             T::PalletId::get().into_account_truncating()
         }
+
+        /// Funds the treasury can spend: the free balance of the treasury account, minus the
+        /// existential deposit that keeps it alive. Anything sent to the account counts,
+        /// whether it came from `deposit_funds`, a plain transfer, slashing, fees or XCM.
+        pub fn treasury_balance() -> BalanceOf<T> {
+            T::Currency::free_balance(&Self::account_id())
+                .saturating_sub(T::Currency::minimum_balance())
+        }
+
+        #[cfg(any(feature = "try-runtime", test))]
+        pub fn do_try_state() -> Result<(), sp_runtime::TryRuntimeError> {
+            ensure!(
+                T::Currency::free_balance(&Self::account_id()) >= T::Currency::minimum_balance(),
+                "Treasury account is below the existential deposit"
+            );
+            Ok(())
+        }
+    }
+
+    impl<T: Config> RewardPayout<T::AccountId> for Pallet<T> {
+        /// Pays up to `share` of `MaxRewardPerPage`, less when the treasury is running low.
+        /// `RewardFee` of the reward is withheld from the miner; half of it goes to the
+        /// validators and the other half stays in the treasury as the reward pool.
+        fn payout(miner: &T::AccountId, validators: &[T::AccountId], share: Perbill) -> DispatchResult {
+            frame_support::storage::with_storage_layer(|| {
+                let reward = (share * T::MaxRewardPerPage::get()).min(Self::treasury_balance());
+                ensure!(!reward.is_zero(), Error::<T>::InsufficientFunds);
+
+                let fee = T::RewardFee::get() * reward;
+                let miner_reward = reward.saturating_sub(fee);
+                let mut validators_reward = BalanceOf::<T>::zero();
+                if !validators.is_empty() {
+                    let per_validator = fee / BalanceOf::<T>::from(2u32) /
+                        BalanceOf::<T>::from(validators.len() as u32);
+                    for validator in validators {
+                        T::Currency::transfer(
+                            &Self::account_id(),
+                            validator,
+                            per_validator,
+                            ExistenceRequirement::KeepAlive,
+                        )?;
+                        validators_reward = validators_reward.saturating_add(per_validator);
+                    }
+                }
+
+                T::Currency::transfer(
+                    &Self::account_id(),
+                    miner,
+                    miner_reward,
+                    ExistenceRequirement::KeepAlive,
+                )?;
+
+                Self::deposit_event(Event::SubmissionRewarded {
+                    miner: miner.clone(),
+                    miner_reward,
+                    validators_reward,
+                });
+
+                Ok(())
+            })
+        }
     }
 }
 
@@ -178,4 +292,5 @@ pub mod pallet {
 mod mock;
 
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests;
\ No newline at end of file
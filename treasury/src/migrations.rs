@@ -0,0 +1,52 @@
+//! Storage migrations for the treasury pallet.
+
+use super::*;
+use frame_support::{migrations::VersionedMigration, traits::UncheckedOnRuntimeUpgrade};
+#[cfg(feature = "try-runtime")]
+use sp_std::vec::Vec;
+
+pub mod v1 {
+    use super::*;
+
+    /// The shadow balance the pallet kept before reading its funds from the treasury account.
+    #[frame_support::storage_alias]
+    pub type TreasuryBalance<T: Config> = StorageValue<Pallet<T>, BalanceOf<T>, OptionQuery>;
+
+    pub struct InnerMigrateToV1<T>(core::marker::PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateToV1<T> {
+        fn on_runtime_upgrade() -> Weight {
+            TreasuryBalance::<T>::kill();
+            T::DbWeight::get().writes(1)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+            let shadow = TreasuryBalance::<T>::get();
+            let actual = Pallet::<T>::treasury_balance();
+            if shadow != Some(actual) {
+                log::warn!(
+                    "TreasuryBalance {:?} was out of sync with the treasury account ({:?})",
+                    shadow,
+                    actual
+                );
+            }
+            Ok(Vec::new())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(_state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+            ensure!(!TreasuryBalance::<T>::exists(), "TreasuryBalance was not removed");
+            Pallet::<T>::do_try_state()
+        }
+    }
+
+    /// Removes `TreasuryBalance` and bumps the storage version from 0 to 1.
+    pub type MigrateToV1<T> = VersionedMigration<
+        0,
+        1,
+        InnerMigrateToV1<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
@@ -4,22 +4,17 @@ use crate as pallet_treasury;
 use frame_support::{parameter_types, traits::ConstU128, traits::ConstU64};
 use sp_core::H256;
 use sp_runtime::{
-    testing::Header,
     traits::{BlakeTwo256, IdentityLookup},
     BuildStorage,
     AccountId32,
+    Perbill,
 };
 
 
-type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
 
 frame_support::construct_runtime!(
-    pub enum Test where
-        Block = Block,
-        NodeBlock = Block,
-        UncheckedExtrinsic = UncheckedExtrinsic,
-    {
+    pub enum Test {
         System: frame_system,
         Balances: pallet_balances,
         Treasury: pallet_treasury,
@@ -30,6 +25,8 @@ parameter_types! {
     pub const BlockHashCount: u64 = 250;
     pub const TreasuryPalletId: frame_support::PalletId = frame_support::PalletId(*b"py/trsry");
     pub const ExistentialDeposit: u128 = 1;
+    pub const MaxRewardPerPage: u128 = 100;
+    pub const RewardFee: Perbill = Perbill::from_percent(10);
 }
 
 // Frame System Config
@@ -87,6 +84,9 @@ impl pallet_treasury::Config for Test {
     type Currency = Balances;
     type RuntimeEvent = RuntimeEvent;
     type PalletId = TreasuryPalletId;
+    type MaxRewardPerPage = MaxRewardPerPage;
+    type RewardFee = RewardFee;
+    type WeightInfo = ();
 }
 
 pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
@@ -94,8 +94,17 @@ pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
         .build_storage()
         .unwrap();
 
-    
-    
+    pallet_balances::GenesisConfig::<Test> {
+        balances: vec![(AccountId32::new([1; 32]), 1_000)],
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+
+    // Creates the treasury account with the existential deposit
+    pallet_treasury::GenesisConfig::<Test>::default()
+        .assimilate_storage(&mut storage)
+        .unwrap();
+
     let mut ext = sp_io::TestExternalities::from(storage);
     ext.execute_with(|| System::set_block_number(1));
     ext
@@ -0,0 +1,94 @@
+//! Benchmarking setup for pallet-validator
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::v2::*;
+use frame_system::RawOrigin;
+use sp_runtime::traits::Bounded;
+
+/// A funded account holding the smallest bond a validator can register with.
+fn funded_account<T: Config>(name: &'static str) -> (T::AccountId, BalanceOf<T>) {
+    let who: T::AccountId = account(name, 0, 0);
+    T::Currency::make_free_balance_be(&who, BalanceOf::<T>::max_value() / 4u32.into());
+    (who, T::MinValidatorBond::get().max(T::Currency::minimum_balance()))
+}
+
+/// Register `name` as a validator with the smallest bond.
+fn registered<T: Config>(name: &'static str) -> (T::AccountId, BalanceOf<T>) {
+    let (validator, bond) = funded_account::<T>(name);
+    Pallet::<T>::register_validator(RawOrigin::Signed(validator.clone()).into(), bond)
+        .expect("validator can register");
+    (validator, bond)
+}
+
+#[benchmarks]
+mod benchmarks {
+    use super::*;
+
+    #[benchmark]
+    fn register_validator() {
+        // Worst case: the last free seat is taken.
+        ValidatorCount::<T>::put(T::MaxValidators::get().saturating_sub(1));
+        let (validator, bond) = funded_account::<T>("validator");
+
+        #[extrinsic_call]
+        register_validator(RawOrigin::Signed(validator.clone()), bond);
+
+        assert!(Validators::<T>::contains_key(&validator));
+    }
+
+    #[benchmark]
+    fn bond_extra() {
+        let (validator, bond) = registered::<T>("validator");
+
+        #[extrinsic_call]
+        bond_extra(RawOrigin::Signed(validator.clone()), bond);
+
+        assert_eq!(Validators::<T>::get(&validator).map(|info| info.bond), Some(bond.saturating_add(bond)));
+    }
+
+    #[benchmark]
+    fn unregister_validator() {
+        let (validator, _) = registered::<T>("validator");
+
+        #[extrinsic_call]
+        unregister_validator(RawOrigin::Signed(validator.clone()));
+
+        assert!(Unbonding::<T>::contains_key(&validator));
+    }
+
+    #[benchmark]
+    fn withdraw_unbonded() {
+        let (validator, _) = registered::<T>("validator");
+        Pallet::<T>::unregister_validator(RawOrigin::Signed(validator.clone()).into())
+            .expect("validator can unregister");
+        frame_system::Pallet::<T>::set_block_number(
+            frame_system::Pallet::<T>::block_number().saturating_add(T::UnbondingPeriod::get()),
+        );
+
+        #[extrinsic_call]
+        withdraw_unbonded(RawOrigin::Signed(validator.clone()));
+
+        assert!(!Unbonding::<T>::contains_key(&validator));
+    }
+
+    #[benchmark]
+    fn report_inactivity() {
+        // The worst case leaves the validator below the minimum bond, which removes it.
+        let (validator, _) = registered::<T>("validator");
+        let reporter: T::AccountId = whitelisted_caller();
+        for _ in 0..T::MaxMissedVotes::get() {
+            <Pallet<T> as ValidatorSet<_>>::note_missed(&validator);
+        }
+
+        #[extrinsic_call]
+        report_inactivity(RawOrigin::Signed(reporter), validator.clone());
+
+        assert_eq!(
+            Reputation::<T>::get(&validator),
+            T::InitialReputation::get().saturating_sub(T::InactivityPenalty::get())
+        );
+    }
+
+    impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
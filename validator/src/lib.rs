@@ -0,0 +1,445 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use pallet::*;
+
+pub mod weights;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+use frame_support::{
+    pallet_prelude::*,
+    traits::{Currency, Get, Imbalance, ReservableCurrency},
+};
+use frame_system::pallet_prelude::*;
+use sp_runtime::{
+    traits::{SaturatedConversion, Saturating, Zero},
+    Perbill,
+};
+use sp_std::vec::Vec;
+
+pub use weights::WeightInfo;
+
+// Type alias for balance using the Currency trait
+type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// The validator set as seen by the pallets that rely on validators to vote.
+pub trait ValidatorSet<AccountId> {
+    /// Whether `who` is a registered validator.
+    fn is_validator(who: &AccountId) -> bool;
+
+    /// Every registered validator.
+    fn validators() -> Vec<AccountId>;
+
+    /// Reputation of `who`, used to select and weight validators against each other.
+    fn reputation(who: &AccountId) -> Perbill;
+
+    /// Most validators the set can hold, which bounds the cost of `select`.
+    fn max_validators() -> u32;
+
+    /// Sample up to `count` distinct validators that are not in `exclude`, drawing from `seed`.
+    fn select(seed: &[u8], count: u32, exclude: &[AccountId]) -> Vec<AccountId>;
+
+    /// Record that `who` cast a vote it was assigned, which resets its missed votes.
+    fn note_vote(who: &AccountId);
+
+    /// Record that `who` did not cast a vote it was assigned to.
+    fn note_missed(who: &AccountId);
+
+    /// Record whether a vote cast by `who` agreed with the final outcome of the vote.
+    fn note_outcome(who: &AccountId, agreed: bool);
+
+    /// Make `who` a validator, for the benchmarks of pallets relying on validators.
+    #[cfg(feature = "runtime-benchmarks")]
+    fn register_for_benchmarks(who: &AccountId);
+}
+
+impl<AccountId> ValidatorSet<AccountId> for () {
+    fn is_validator(_who: &AccountId) -> bool {
+        false
+    }
+
+    fn validators() -> Vec<AccountId> {
+        Vec::new()
+    }
+
+    fn reputation(_who: &AccountId) -> Perbill {
+        Perbill::zero()
+    }
+
+    fn max_validators() -> u32 {
+        0
+    }
+
+    fn select(_seed: &[u8], _count: u32, _exclude: &[AccountId]) -> Vec<AccountId> {
+        Vec::new()
+    }
+
+    fn note_vote(_who: &AccountId) {}
+
+    fn note_missed(_who: &AccountId) {}
+
+    fn note_outcome(_who: &AccountId, _agreed: bool) {}
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn register_for_benchmarks(_who: &AccountId) {}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        /// Currency the validator bonds are reserved in
+        type Currency: ReservableCurrency<Self::AccountId>;
+
+        /// Event type used in the runtime
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// Smallest bond a registered validator may keep reserved
+        #[pallet::constant]
+        type MinValidatorBond: Get<BalanceOf<Self>>;
+
+        /// Number of blocks the bond of an unregistered validator stays reserved
+        #[pallet::constant]
+        type UnbondingPeriod: Get<BlockNumberFor<Self>>;
+
+        /// Reputation of validators that never voted
+        #[pallet::constant]
+        type InitialReputation: Get<Perbill>;
+
+        /// Reputation gained for a vote that agreed with the final outcome
+        #[pallet::constant]
+        type ReputationReward: Get<Perbill>;
+
+        /// Reputation lost for a vote against the final outcome
+        #[pallet::constant]
+        type ReputationPenalty: Get<Perbill>;
+
+        /// Most validators that can be registered at once
+        #[pallet::constant]
+        type MaxValidators: Get<u32>;
+
+        /// Number of assigned votes in a row a validator has to miss before it can be reported
+        /// as inactive
+        #[pallet::constant]
+        type MaxMissedVotes: Get<u32>;
+
+        /// Reputation lost every time a validator is reported as inactive
+        #[pallet::constant]
+        type InactivityPenalty: Get<Perbill>;
+
+        /// Share of the bond slashed every time a validator is reported as inactive
+        #[pallet::constant]
+        type InactivitySlash: Get<Perbill>;
+
+        /// Account receiving slashed bonds
+        type RewardPool: Get<Self::AccountId>;
+
+        /// Weights for this pallet's dispatchables
+        type WeightInfo: WeightInfo;
+    }
+
+    /// A registered validator.
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct ValidatorInfo<Balance> {
+        /// Reserved for as long as the account is a validator.
+        pub bond: Balance,
+        /// Assigned votes missed since the validator's latest vote or inactivity report.
+        pub missed_votes: u32,
+    }
+
+    pub type ValidatorInfoOf<T> = ValidatorInfo<BalanceOf<T>>;
+
+    /// The bond of an unregistered validator that is waiting out the unbonding period.
+    #[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct UnlockChunk<Balance, BlockNumber> {
+        pub value: Balance,
+        pub unlock_at: BlockNumber,
+    }
+
+    pub type UnlockChunkOf<T> = UnlockChunk<BalanceOf<T>, BlockNumberFor<T>>;
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    /// Registered validators.
+    #[pallet::storage]
+    #[pallet::getter(fn validators)]
+    pub type Validators<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, ValidatorInfoOf<T>, OptionQuery>;
+
+    /// Number of registered validators, at most `MaxValidators`.
+    #[pallet::storage]
+    #[pallet::getter(fn validator_count)]
+    pub type ValidatorCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    #[pallet::type_value]
+    pub fn DefaultReputation<T: Config>() -> Perbill {
+        T::InitialReputation::get()
+    }
+
+    /// Reputation of every account that has been a validator. It outlives the registration so
+    /// that leaving and registering again does not reset it.
+    #[pallet::storage]
+    #[pallet::getter(fn reputation)]
+    pub type Reputation<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, Perbill, ValueQuery, DefaultReputation<T>>;
+
+    /// Bonds of unregistered validators that are still reserved.
+    #[pallet::storage]
+    #[pallet::getter(fn unbonding)]
+    pub type Unbonding<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, UnlockChunkOf<T>, OptionQuery>;
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// A validator registered with a bond
+        ValidatorRegistered { validator: T::AccountId, bond: BalanceOf<T> },
+        /// A validator reserved additional funds on top of its bond
+        ValidatorBonded { validator: T::AccountId, amount: BalanceOf<T> },
+        /// A validator left the validator set and its bond started unbonding
+        ValidatorUnregistered { validator: T::AccountId, unlock_at: BlockNumberFor<T> },
+        /// An unbonded bond was released
+        ValidatorWithdrawn { validator: T::AccountId, amount: BalanceOf<T> },
+        /// A validator was reported as inactive and penalized
+        ValidatorPenalized { validator: T::AccountId, slashed: BalanceOf<T>, reputation: Perbill },
+        /// A validator's bond fell below `MinValidatorBond` and it was removed from the set
+        ValidatorKicked { validator: T::AccountId },
+        /// A validator's reputation changed after the outcome of one of its votes was known
+        ReputationUpdated { validator: T::AccountId, reputation: Perbill },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The account is already a registered validator
+        AlreadyRegistered,
+        /// The account is not a registered validator
+        NotRegistered,
+        /// The bond would be below `MinValidatorBond`
+        BondTooLow,
+        /// The account's previous bond is still unbonding
+        StillUnbonding,
+        /// No unbonded funds can be withdrawn yet
+        NothingToWithdraw,
+        /// The validator missed fewer than `MaxMissedVotes` assigned votes in a row
+        ValidatorActive,
+        /// `MaxValidators` validators are already registered
+        TooManyValidators,
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Join the validator set, reserving `bond` from the caller.
+        #[pallet::call_index(0)]
+        #[pallet::weight(T::WeightInfo::register_validator())]
+        pub fn register_validator(origin: OriginFor<T>, bond: BalanceOf<T>) -> DispatchResult {
+            let validator = ensure_signed(origin)?;
+            ensure!(!Validators::<T>::contains_key(&validator), Error::<T>::AlreadyRegistered);
+            ensure!(!Unbonding::<T>::contains_key(&validator), Error::<T>::StillUnbonding);
+            ensure!(bond >= T::MinValidatorBond::get(), Error::<T>::BondTooLow);
+            let count = ValidatorCount::<T>::get();
+            ensure!(count < T::MaxValidators::get(), Error::<T>::TooManyValidators);
+
+            T::Currency::reserve(&validator, bond)?;
+            Validators::<T>::insert(&validator, ValidatorInfo { bond, missed_votes: 0 });
+            ValidatorCount::<T>::put(count.saturating_add(1));
+            Self::deposit_event(Event::ValidatorRegistered { validator, bond });
+            Ok(())
+        }
+
+        /// Reserve additional funds on top of a registered validator's bond.
+        #[pallet::call_index(1)]
+        #[pallet::weight(T::WeightInfo::bond_extra())]
+        pub fn bond_extra(origin: OriginFor<T>, amount: BalanceOf<T>) -> DispatchResult {
+            let validator = ensure_signed(origin)?;
+            let mut info = Validators::<T>::get(&validator).ok_or(Error::<T>::NotRegistered)?;
+
+            T::Currency::reserve(&validator, amount)?;
+            info.bond = info.bond.saturating_add(amount);
+            Validators::<T>::insert(&validator, info);
+            Self::deposit_event(Event::ValidatorBonded { validator, amount });
+            Ok(())
+        }
+
+        /// Leave the validator set. The bond stays reserved for `UnbondingPeriod` blocks so
+        /// that it can still be slashed for misbehaviour found in the meantime.
+        #[pallet::call_index(2)]
+        #[pallet::weight(T::WeightInfo::unregister_validator())]
+        pub fn unregister_validator(origin: OriginFor<T>) -> DispatchResult {
+            let validator = ensure_signed(origin)?;
+            let info = Validators::<T>::take(&validator).ok_or(Error::<T>::NotRegistered)?;
+            ValidatorCount::<T>::mutate(|count| *count = count.saturating_sub(1));
+
+            let unlock_at = Self::start_unbonding(&validator, info.bond);
+            Self::deposit_event(Event::ValidatorUnregistered { validator, unlock_at });
+            Ok(())
+        }
+
+        /// Release the caller's bond once its unbonding period has elapsed.
+        #[pallet::call_index(3)]
+        #[pallet::weight(T::WeightInfo::withdraw_unbonded())]
+        pub fn withdraw_unbonded(origin: OriginFor<T>) -> DispatchResult {
+            let validator = ensure_signed(origin)?;
+            let chunk = Unbonding::<T>::get(&validator).ok_or(Error::<T>::NothingToWithdraw)?;
+            let now = frame_system::Pallet::<T>::block_number();
+            ensure!(chunk.unlock_at <= now, Error::<T>::NothingToWithdraw);
+
+            Unbonding::<T>::remove(&validator);
+            T::Currency::unreserve(&validator, chunk.value);
+            Self::deposit_event(Event::ValidatorWithdrawn { validator, amount: chunk.value });
+            Ok(())
+        }
+
+        /// Penalize a validator that missed `MaxMissedVotes` assigned votes in a row. Its
+        /// reputation drops by `InactivityPenalty` and `InactivitySlash` of its bond goes to the
+        /// reward pool. A validator left below `MinValidatorBond` is removed from the set.
+        ///
+        /// Anyone can report an inactive validator. The report resets its missed votes, so the
+        /// same validator can only be reported again after missing another `MaxMissedVotes`.
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::WeightInfo::report_inactivity())]
+        pub fn report_inactivity(origin: OriginFor<T>, validator: T::AccountId) -> DispatchResult {
+            ensure_signed(origin)?;
+            let mut info = Validators::<T>::get(&validator).ok_or(Error::<T>::NotRegistered)?;
+            ensure!(info.missed_votes >= T::MaxMissedVotes::get(), Error::<T>::ValidatorActive);
+
+            let reputation = Reputation::<T>::mutate(&validator, |reputation| {
+                *reputation = reputation.saturating_sub(T::InactivityPenalty::get());
+                *reputation
+            });
+
+            let (imbalance, _) =
+                T::Currency::slash_reserved(&validator, T::InactivitySlash::get() * info.bond);
+            let slashed = imbalance.peek();
+            T::Currency::resolve_creating(&T::RewardPool::get(), imbalance);
+            info.bond = info.bond.saturating_sub(slashed);
+            info.missed_votes = 0;
+            Self::deposit_event(Event::ValidatorPenalized {
+                validator: validator.clone(),
+                slashed,
+                reputation,
+            });
+
+            if info.bond < T::MinValidatorBond::get() {
+                Validators::<T>::remove(&validator);
+                ValidatorCount::<T>::mutate(|count| *count = count.saturating_sub(1));
+                Self::start_unbonding(&validator, info.bond);
+                Self::deposit_event(Event::ValidatorKicked { validator });
+            } else {
+                Validators::<T>::insert(&validator, info);
+            }
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Keep `bond` reserved for `UnbondingPeriod` blocks, returning the block it unlocks at.
+        fn start_unbonding(validator: &T::AccountId, bond: BalanceOf<T>) -> BlockNumberFor<T> {
+            let unlock_at =
+                frame_system::Pallet::<T>::block_number().saturating_add(T::UnbondingPeriod::get());
+            if !bond.is_zero() {
+                Unbonding::<T>::insert(validator, UnlockChunk { value: bond, unlock_at });
+            }
+            unlock_at
+        }
+    }
+
+    impl<T: Config> ValidatorSet<T::AccountId> for Pallet<T> {
+        fn is_validator(who: &T::AccountId) -> bool {
+            Validators::<T>::contains_key(who)
+        }
+
+        fn validators() -> Vec<T::AccountId> {
+            Validators::<T>::iter_keys().collect()
+        }
+
+        fn reputation(who: &T::AccountId) -> Perbill {
+            Reputation::<T>::get(who)
+        }
+
+        fn max_validators() -> u32 {
+            T::MaxValidators::get()
+        }
+
+        /// Validators are drawn one at a time with a chance proportional to their bond scaled
+        /// by their reputation, so validators without any reputation are never selected. Every
+        /// validator is read once, which registration bounds by `MaxValidators`.
+        fn select(seed: &[u8], count: u32, exclude: &[T::AccountId]) -> Vec<T::AccountId> {
+            let mut candidates: Vec<(T::AccountId, u128)> = Validators::<T>::iter()
+                .filter(|(validator, _)| !exclude.contains(validator))
+                .map(|(validator, info)| {
+                    let weight = Reputation::<T>::get(&validator)
+                        .mul_floor(info.bond.saturated_into::<u128>());
+                    (validator, weight)
+                })
+                .filter(|(_, weight)| !weight.is_zero())
+                .collect();
+
+            let mut selected = Vec::new();
+            for draw in 0..count {
+                let total = candidates
+                    .iter()
+                    .fold(0u128, |total, (_, weight)| total.saturating_add(*weight));
+                if total.is_zero() {
+                    break
+                }
+                let mut random = [0u8; 16];
+                random.copy_from_slice(&sp_io::hashing::blake2_256(&(seed, draw).encode())[..16]);
+                let mut point = u128::from_le_bytes(random) % total;
+                let Some(index) = candidates.iter().position(|(_, weight)| {
+                    if point < *weight {
+                        return true
+                    }
+                    point -= *weight;
+                    false
+                }) else {
+                    break
+                };
+                selected.push(candidates.swap_remove(index).0);
+            }
+            selected
+        }
+
+        fn note_vote(who: &T::AccountId) {
+            Validators::<T>::mutate_extant(who, |info| info.missed_votes = 0);
+        }
+
+        fn note_missed(who: &T::AccountId) {
+            Validators::<T>::mutate_extant(who, |info| {
+                info.missed_votes = info.missed_votes.saturating_add(1)
+            });
+        }
+
+        /// Raises the reputation by `ReputationReward` for agreeing with the outcome and lowers
+        /// it by `ReputationPenalty` otherwise. Validators that left since keep being scored.
+        fn note_outcome(who: &T::AccountId, agreed: bool) {
+            let reputation = Reputation::<T>::mutate(who, |reputation| {
+                *reputation = if agreed {
+                    reputation.saturating_add(T::ReputationReward::get())
+                } else {
+                    reputation.saturating_sub(T::ReputationPenalty::get())
+                };
+                *reputation
+            });
+            Self::deposit_event(Event::ReputationUpdated { validator: who.clone(), reputation });
+        }
+
+        #[cfg(feature = "runtime-benchmarks")]
+        fn register_for_benchmarks(who: &T::AccountId) {
+            let bond = T::MinValidatorBond::get().max(T::Currency::minimum_balance());
+            T::Currency::make_free_balance_be(who, bond.saturating_mul(2u32.into()));
+            Self::register_validator(frame_system::RawOrigin::Signed(who.clone()).into(), bond)
+                .expect("account is funded");
+        }
+    }
+}
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+#[allow(clippy::module_inception)]
+mod tests;
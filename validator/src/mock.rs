@@ -0,0 +1,124 @@
+#![cfg(test)]
+
+use crate as pallet_validator;
+use frame_support::{parameter_types, traits::ConstU128, traits::ConstU64};
+use sp_core::H256;
+use sp_runtime::{
+    traits::{BlakeTwo256, IdentityLookup},
+    BuildStorage,
+    AccountId32,
+    Perbill,
+};
+
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+    pub enum Test {
+        System: frame_system,
+        Balances: pallet_balances,
+        Validator: pallet_validator,
+    }
+);
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+    pub const MinValidatorBond: u128 = 50;
+    pub const UnbondingPeriod: u64 = 5;
+    pub const InitialReputation: Perbill = Perbill::from_percent(50);
+    pub const ReputationReward: Perbill = Perbill::from_percent(10);
+    pub const ReputationPenalty: Perbill = Perbill::from_percent(20);
+    pub const MaxValidators: u32 = 3;
+    pub const MaxMissedVotes: u32 = 2;
+    pub const InactivityPenalty: Perbill = Perbill::from_percent(10);
+    pub const InactivitySlash: Perbill = Perbill::from_percent(20);
+    pub RewardPool: AccountId32 = AccountId32::new([9; 32]);
+}
+
+// Frame System Config
+impl frame_system::Config for Test {
+    type BaseCallFilter = frame_support::traits::Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type AccountId = AccountId32;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Nonce = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type RuntimeEvent = RuntimeEvent;
+    type Block = Block;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<u128>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type RuntimeTask = (); // Default to `()`, adjust as necessary
+    type BlockHashCount = ConstU64<250>;
+    type DbWeight = ();
+    type MaxConsumers = frame_support::traits::ConstU32<16>;
+    type SingleBlockMigrations = ();
+    type MultiBlockMigrator = ();
+    type PreInherents = ();
+    type PostInherents = ();
+    type PostTransactions = ();
+}
+
+// Balances Pallet Config
+impl pallet_balances::Config for Test {
+    type MaxLocks = ();
+    type Balance = u128;
+    type RuntimeEvent = RuntimeEvent;
+    type DustRemoval = ();
+    type ExistentialDeposit = ConstU128<1>;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type RuntimeHoldReason = (); // Default to `()`, adjust as necessary
+    type RuntimeFreezeReason = (); // Default to `()`, adjust as necessary
+    type ReserveIdentifier = [u8; 8];
+    type FreezeIdentifier = [u8; 8];
+    type MaxReserves = frame_support::traits::ConstU32<1>;
+    type MaxFreezes = frame_support::traits::ConstU32<1>;
+}
+
+// Validator Pallet Config
+impl pallet_validator::Config for Test {
+    type Currency = Balances;
+    type RuntimeEvent = RuntimeEvent;
+    type MinValidatorBond = MinValidatorBond;
+    type UnbondingPeriod = UnbondingPeriod;
+    type InitialReputation = InitialReputation;
+    type ReputationReward = ReputationReward;
+    type ReputationPenalty = ReputationPenalty;
+    type MaxValidators = MaxValidators;
+    type MaxMissedVotes = MaxMissedVotes;
+    type InactivityPenalty = InactivityPenalty;
+    type InactivitySlash = InactivitySlash;
+    type RewardPool = RewardPool;
+    type WeightInfo = ();
+}
+
+pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
+    let mut storage = frame_system::GenesisConfig::<Test>::default()
+        .build_storage()
+        .unwrap();
+
+    pallet_balances::GenesisConfig::<Test> {
+        balances: vec![
+            (AccountId32::new([1; 32]), 1_000),
+            (AccountId32::new([2; 32]), 1_000),
+            (AccountId32::new([3; 32]), 1_000),
+            (AccountId32::new([4; 32]), 1_000),
+        ],
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+
+    let mut ext = sp_io::TestExternalities::from(storage);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
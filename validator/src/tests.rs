@@ -0,0 +1,219 @@
+#[cfg(test)]
+mod tests {
+    use crate::mock::{new_test_ext, Balances, RewardPool, RuntimeEvent, RuntimeOrigin, System, Test, Validator};
+    use crate::{Error, Event as ValidatorEvent, Reputation, UnlockChunk, ValidatorInfo, ValidatorSet, Validators};
+    use frame_support::{assert_noop, assert_ok};
+    use sp_runtime::{AccountId32, Perbill};
+
+    fn register(validator: &AccountId32, bond: u128) {
+        assert_ok!(Validator::register_validator(RuntimeOrigin::signed(validator.clone()), bond));
+    }
+
+    #[test]
+    fn register_validator_works() {
+        new_test_ext().execute_with(|| {
+            let validator = AccountId32::new([1; 32]);
+
+            assert_noop!(
+                Validator::register_validator(RuntimeOrigin::signed(validator.clone()), 49),
+                Error::<Test>::BondTooLow
+            );
+
+            register(&validator, 100);
+            assert_eq!(Validator::validators(&validator), Some(ValidatorInfo { bond: 100, missed_votes: 0 }));
+            assert_eq!(Balances::reserved_balance(&validator), 100);
+            System::assert_last_event(RuntimeEvent::Validator(ValidatorEvent::ValidatorRegistered {
+                validator: validator.clone(),
+                bond: 100,
+            }));
+
+            assert_noop!(
+                Validator::register_validator(RuntimeOrigin::signed(validator.clone()), 100),
+                Error::<Test>::AlreadyRegistered
+            );
+
+            assert_ok!(Validator::bond_extra(RuntimeOrigin::signed(validator.clone()), 50));
+            assert_eq!(Validator::validators(&validator).map(|info| info.bond), Some(150));
+            assert_eq!(Balances::reserved_balance(&validator), 150);
+        });
+    }
+
+    #[test]
+    fn validator_set_is_bounded() {
+        new_test_ext().execute_with(|| {
+            for seed in 1..=3 {
+                register(&AccountId32::new([seed; 32]), 100);
+            }
+            assert_eq!(Validator::validator_count(), 3);
+
+            let late = AccountId32::new([4; 32]);
+            assert_noop!(
+                Validator::register_validator(RuntimeOrigin::signed(late.clone()), 100),
+                Error::<Test>::TooManyValidators
+            );
+
+            // Leaving frees a seat
+            assert_ok!(Validator::unregister_validator(RuntimeOrigin::signed(AccountId32::new([1; 32]))));
+            assert_eq!(Validator::validator_count(), 2);
+            register(&late, 100);
+            assert_eq!(Validator::validator_count(), 3);
+        });
+    }
+
+    #[test]
+    fn unregistered_bond_is_released_after_unbonding() {
+        new_test_ext().execute_with(|| {
+            let validator = AccountId32::new([1; 32]);
+            register(&validator, 100);
+
+            assert_ok!(Validator::unregister_validator(RuntimeOrigin::signed(validator.clone())));
+            assert!(!<Validator as ValidatorSet<_>>::is_validator(&validator));
+            assert_eq!(Validator::unbonding(&validator), Some(UnlockChunk { value: 100, unlock_at: 6 }));
+            assert_eq!(Balances::reserved_balance(&validator), 100);
+
+            // The account cannot register again while its previous bond is unbonding
+            assert_noop!(
+                Validator::register_validator(RuntimeOrigin::signed(validator.clone()), 100),
+                Error::<Test>::StillUnbonding
+            );
+
+            System::set_block_number(5);
+            assert_noop!(
+                Validator::withdraw_unbonded(RuntimeOrigin::signed(validator.clone())),
+                Error::<Test>::NothingToWithdraw
+            );
+
+            System::set_block_number(6);
+            assert_ok!(Validator::withdraw_unbonded(RuntimeOrigin::signed(validator.clone())));
+            assert_eq!(Balances::reserved_balance(&validator), 0);
+            assert_eq!(Validator::unbonding(&validator), None);
+            System::assert_last_event(RuntimeEvent::Validator(ValidatorEvent::ValidatorWithdrawn {
+                validator,
+                amount: 100,
+            }));
+        });
+    }
+
+    #[test]
+    fn validator_set_lists_registered_validators() {
+        new_test_ext().execute_with(|| {
+            let first = AccountId32::new([1; 32]);
+            let second = AccountId32::new([2; 32]);
+            register(&first, 100);
+            register(&second, 100);
+
+            let mut validators = <Validator as ValidatorSet<_>>::validators();
+            validators.sort();
+            assert_eq!(validators, vec![first.clone(), second.clone()]);
+            assert!(<Validator as ValidatorSet<_>>::is_validator(&first));
+            assert!(!<Validator as ValidatorSet<_>>::is_validator(&AccountId32::new([3; 32])));
+            assert_eq!(<Validator as ValidatorSet<_>>::reputation(&first), Perbill::from_percent(50));
+        });
+    }
+
+    #[test]
+    fn selection_is_weighted_by_bond_and_reputation() {
+        new_test_ext().execute_with(|| {
+            let first = AccountId32::new([1; 32]);
+            let second = AccountId32::new([2; 32]);
+            register(&first, 100);
+            register(&second, 100);
+
+            let mut selected = <Validator as ValidatorSet<_>>::select(b"seed", 3, &[]);
+            selected.sort();
+            assert_eq!(selected, vec![first.clone(), second.clone()]);
+            assert_eq!(<Validator as ValidatorSet<_>>::select(b"seed", 1, core::slice::from_ref(&first)), vec![second.clone()]);
+
+            // Without any reputation a validator carries no weight
+            Reputation::<Test>::insert(&second, Perbill::zero());
+            for seed in [b"a", b"b", b"c", b"d"] {
+                assert_eq!(<Validator as ValidatorSet<_>>::select(seed, 1, &[]), vec![first.clone()]);
+            }
+            assert!(<Validator as ValidatorSet<_>>::select(b"seed", 1, &[first]).is_empty());
+        });
+    }
+
+    #[test]
+    fn reputation_follows_vote_outcomes() {
+        new_test_ext().execute_with(|| {
+            let validator = AccountId32::new([1; 32]);
+            register(&validator, 100);
+
+            <Validator as ValidatorSet<_>>::note_outcome(&validator, true);
+            assert_eq!(Reputation::<Test>::get(&validator), Perbill::from_percent(60));
+            System::assert_last_event(RuntimeEvent::Validator(ValidatorEvent::ReputationUpdated {
+                validator: validator.clone(),
+                reputation: Perbill::from_percent(60),
+            }));
+
+            <Validator as ValidatorSet<_>>::note_outcome(&validator, false);
+            <Validator as ValidatorSet<_>>::note_outcome(&validator, false);
+            assert_eq!(Reputation::<Test>::get(&validator), Perbill::from_percent(20));
+
+            // Leaving the validator set does not reset the reputation
+            assert_ok!(Validator::unregister_validator(RuntimeOrigin::signed(validator.clone())));
+            System::set_block_number(6);
+            assert_ok!(Validator::withdraw_unbonded(RuntimeOrigin::signed(validator.clone())));
+            register(&validator, 100);
+            assert_eq!(Reputation::<Test>::get(&validator), Perbill::from_percent(20));
+        });
+    }
+
+    #[test]
+    fn inactive_validators_are_penalized() {
+        new_test_ext().execute_with(|| {
+            let validator = AccountId32::new([1; 32]);
+            let reporter = AccountId32::new([2; 32]);
+            register(&validator, 100);
+
+            // Time alone does not make a validator inactive, only missing assigned votes does
+            System::set_block_number(1_000);
+            <Validator as ValidatorSet<_>>::note_missed(&validator);
+            assert_noop!(
+                Validator::report_inactivity(RuntimeOrigin::signed(reporter.clone()), validator.clone()),
+                Error::<Test>::ValidatorActive
+            );
+
+            <Validator as ValidatorSet<_>>::note_missed(&validator);
+            assert_ok!(Validator::report_inactivity(RuntimeOrigin::signed(reporter.clone()), validator.clone()));
+            assert_eq!(Validator::validators(&validator), Some(ValidatorInfo { bond: 80, missed_votes: 0 }));
+            assert_eq!(Balances::reserved_balance(&validator), 80);
+            assert_eq!(Balances::free_balance(RewardPool::get()), 20);
+            assert_eq!(Reputation::<Test>::get(&validator), Perbill::from_percent(40));
+            System::assert_last_event(RuntimeEvent::Validator(ValidatorEvent::ValidatorPenalized {
+                validator: validator.clone(),
+                slashed: 20,
+                reputation: Perbill::from_percent(40),
+            }));
+
+            // A vote resets the missed votes
+            <Validator as ValidatorSet<_>>::note_missed(&validator);
+            <Validator as ValidatorSet<_>>::note_vote(&validator);
+            <Validator as ValidatorSet<_>>::note_missed(&validator);
+            assert_noop!(
+                Validator::report_inactivity(RuntimeOrigin::signed(reporter), validator.clone()),
+                Error::<Test>::ValidatorActive
+            );
+        });
+    }
+
+    #[test]
+    fn validators_below_the_minimum_bond_are_kicked() {
+        new_test_ext().execute_with(|| {
+            let validator = AccountId32::new([1; 32]);
+            let reporter = AccountId32::new([2; 32]);
+            register(&validator, 60);
+
+            <Validator as ValidatorSet<_>>::note_missed(&validator);
+            <Validator as ValidatorSet<_>>::note_missed(&validator);
+            assert_ok!(Validator::report_inactivity(RuntimeOrigin::signed(reporter), validator.clone()));
+
+            // 20% of 60 is slashed, leaving less than the minimum bond of 50
+            assert!(!Validators::<Test>::contains_key(&validator));
+            assert_eq!(Validator::validator_count(), 0);
+            assert_eq!(Validator::unbonding(&validator), Some(UnlockChunk { value: 48, unlock_at: 6 }));
+            assert_eq!(Balances::reserved_balance(&validator), 48);
+            System::assert_last_event(RuntimeEvent::Validator(ValidatorEvent::ValidatorKicked { validator }));
+        });
+    }
+}
@@ -0,0 +1,97 @@
+//! Hand-written weights for pallet_validator.
+//!
+//! These are not benchmark results. The reads and writes are counted from the storage each call
+//! accesses and the proof sizes from the `MaxEncodedLen` of those items, while the execution
+//! times are estimates doubled as a safety margin. Replace this file with the output of
+//! `benchmark pallet --pallet pallet_validator` once it has been run on reference hardware.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_validator.
+pub trait WeightInfo {
+	fn register_validator() -> Weight;
+	fn bond_extra() -> Weight;
+	fn unregister_validator() -> Weight;
+	fn withdraw_unbonded() -> Weight;
+	fn report_inactivity() -> Weight;
+}
+
+/// Conservative weights for pallet_validator, until it is benchmarked.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: Validator Validators (r:1 w:1)
+	/// Storage: Validator Unbonding (r:1 w:0)
+	/// Storage: Validator ValidatorCount (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	fn register_validator() -> Weight {
+		Weight::from_parts(88_000_000, 3593)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: Validator Validators (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	fn bond_extra() -> Weight {
+		Weight::from_parts(76_000_000, 3593)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: Validator Validators (r:1 w:1)
+	/// Storage: Validator ValidatorCount (r:1 w:1)
+	/// Storage: Validator Unbonding (r:0 w:1)
+	fn unregister_validator() -> Weight {
+		Weight::from_parts(42_000_000, 3539)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: Validator Unbonding (r:1 w:1)
+	/// Storage: System Account (r:1 w:1)
+	fn withdraw_unbonded() -> Weight {
+		Weight::from_parts(74_000_000, 3593)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: Validator Validators (r:1 w:1)
+	/// Storage: Validator Reputation (r:1 w:1)
+	/// Storage: System Account (r:2 w:2)
+	/// Storage: Validator ValidatorCount (r:1 w:1)
+	/// Storage: Validator Unbonding (r:0 w:1)
+	fn report_inactivity() -> Weight {
+		Weight::from_parts(134_000_000, 6196)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn register_validator() -> Weight {
+		Weight::from_parts(88_000_000, 3593)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	fn bond_extra() -> Weight {
+		Weight::from_parts(76_000_000, 3593)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	fn unregister_validator() -> Weight {
+		Weight::from_parts(42_000_000, 3539)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	fn withdraw_unbonded() -> Weight {
+		Weight::from_parts(74_000_000, 3593)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	fn report_inactivity() -> Weight {
+		Weight::from_parts(134_000_000, 6196)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+}